@@ -1,7 +1,10 @@
 //! Simple async/sync channels used in various parts of this crate.
+use event_listener::Event;
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// An error on send
@@ -13,10 +16,21 @@ pub enum TrySendError<T> {
     Full(T),
 }
 
+/// An error on send with a timeout
+#[derive(Debug)]
+pub enum SendTimeoutError<T> {
+    /// The receiver was closed
+    Closed(T),
+    /// The receiver stayed full past the timeout
+    Timeout(T),
+}
+
 /// Async and Sync MPMP Sender.
 #[derive(Clone)]
 pub struct Sender<T> {
     inner: async_channel::Sender<T>,
+    // notified when a receiver takes an item, so there may be room again
+    space: Arc<Event>,
 }
 
 impl<T> std::fmt::Debug for Sender<T> {
@@ -36,6 +50,44 @@ impl<T> Sender<T> {
         self.inner.send(item).await.map_err(|e| e.into_inner())
     }
 
+    /// Send this item asynchronously, giving up if the channel is still full after `timeout`.
+    ///
+    /// On failure, returns why and the item.
+    pub async fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>>
+    where
+        T: Send,
+    {
+        let mut deadline = futures_timer::Delay::new(timeout);
+        let mut item = item;
+        loop {
+            item = match self.try_send(item) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(item)) => return Err(SendTimeoutError::Closed(item)),
+                Err(TrySendError::Full(item)) => item,
+            };
+
+            // try again once it's listening, so a receive in between isn't missed
+            let listener = self.space.listen();
+            item = match self.try_send(item) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(item)) => return Err(SendTimeoutError::Closed(item)),
+                Err(TrySendError::Full(item)) => item,
+            };
+
+            let room = async {
+                listener.await;
+                true
+            };
+            let timed_out = async {
+                (&mut deadline).await;
+                false
+            };
+            if !futures_lite::future::or(room, timed_out).await {
+                return Err(SendTimeoutError::Timeout(item));
+            }
+        }
+    }
+
     /// Send this item synchronously.
     ///
     /// On failure, returns why and the item.
@@ -47,12 +99,16 @@ impl<T> Sender<T> {
     }
 }
 
-pin_project_lite::pin_project! {
-    /// Async and Sync MPMP Receiver.
-    #[derive(Clone)]
-    pub struct Receiver<T> {
-        #[pin]
-        inner: async_channel::Receiver<T>,
+/// Async and Sync MPMP Receiver.
+#[derive(Clone)]
+pub struct Receiver<T> {
+    inner: async_channel::Receiver<T>,
+    space: Arc<Event>,
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Receiver").finish()
     }
 }
 
@@ -64,38 +120,150 @@ impl<T> Receiver<T> {
     where
         T: Send,
     {
-        self.inner.recv().await.ok()
+        let item = self.inner.recv().await.ok()?;
+        self.space.notify_additional(1);
+        Some(item)
     }
 
     /// Close the receiver
+    ///
+    /// Any senders waiting for room are woken up so they see that it was closed.
     pub fn close(&self) -> bool {
-        self.inner.close()
+        let closed = self.inner.close();
+        self.space.notify(usize::MAX);
+        closed
     }
 
     /// Synchronously receives an item
     ///
     /// If this returns None, the Sender was closed
     pub fn try_recv(&self) -> Option<T> {
-        self.inner.try_recv().ok()
+        let item = self.inner.try_recv().ok()?;
+        self.space.notify_additional(1);
+        Some(item)
     }
 }
 
 impl<T> futures_lite::Stream for Receiver<T> {
     type Item = T;
-    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
-        this.inner.poll_next(ctx)
+    fn poll_next(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = Pin::new(&mut self.inner).poll_next(ctx);
+        if let Poll::Ready(Some(..)) = item {
+            self.space.notify_additional(1);
+        }
+        item
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // the channel closes when the last receiver goes away, so close it here first
+        // and wake up any blocked senders rather than leaving them to time out
+        if self.inner.receiver_count() == 1 {
+            self.close();
+        }
     }
 }
 
 /// Create a bounded channel
 pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
     let (tx, rx) = async_channel::bounded(cap);
-    (Sender { inner: tx }, Receiver { inner: rx })
+    new(tx, rx)
 }
 
 /// Create an unbounded channel
 pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
     let (tx, rx) = async_channel::unbounded();
-    (Sender { inner: tx }, Receiver { inner: rx })
+    new(tx, rx)
+}
+
+fn new<T>(
+    tx: async_channel::Sender<T>,
+    rx: async_channel::Receiver<T>,
+) -> (Sender<T>, Receiver<T>) {
+    let space = Arc::new(Event::new());
+    let tx = Sender {
+        inner: tx,
+        space: space.clone(),
+    };
+    (tx, Receiver { inner: rx, space })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_timeout_full() {
+        futures_lite::future::block_on(async move {
+            let (tx, rx) = bounded(1);
+            tx.send_timeout(1, Duration::from_millis(50)).await.unwrap();
+
+            // the item is given back
+            let err = tx.send_timeout(2, Duration::from_millis(50)).await;
+            assert!(matches!(err, Err(SendTimeoutError::Timeout(2))));
+
+            // there's room again once the receiver catches up
+            assert_eq!(rx.recv().await.unwrap(), 1);
+            tx.send_timeout(3, Duration::from_millis(50)).await.unwrap();
+            assert_eq!(rx.recv().await.unwrap(), 3);
+
+            rx.close();
+            let err = tx.send_timeout(4, Duration::from_millis(50)).await;
+            assert!(matches!(err, Err(SendTimeoutError::Closed(4))));
+        });
+    }
+
+    #[test]
+    fn send_timeout_waits_for_room() {
+        futures_lite::future::block_on(async move {
+            let (tx, rx) = bounded(1);
+            tx.send(1).await.unwrap();
+
+            let recv = async {
+                futures_timer::Delay::new(Duration::from_millis(10)).await;
+                rx.recv().await
+            };
+            let send = tx.send_timeout(2, Duration::from_secs(5));
+            let (sent, received) = futures_lite::future::zip(send, recv).await;
+            assert!(sent.is_ok());
+            assert_eq!(received, Some(1));
+            assert_eq!(rx.recv().await, Some(2));
+        });
+    }
+
+    #[test]
+    fn send_timeout_woken_on_close() {
+        futures_lite::future::block_on(async move {
+            let (tx, rx) = bounded(1);
+            tx.send(1).await.unwrap();
+
+            let close = async {
+                futures_timer::Delay::new(Duration::from_millis(10)).await;
+                rx.close();
+            };
+            let send = tx.send_timeout(2, Duration::from_secs(5));
+            let (sent, _) = futures_lite::future::zip(send, close).await;
+            assert!(matches!(sent, Err(SendTimeoutError::Closed(2))));
+        });
+    }
+
+    #[test]
+    fn send_timeout_woken_on_last_receiver_drop() {
+        futures_lite::future::block_on(async move {
+            let (tx, rx) = bounded(1);
+            tx.send(1).await.unwrap();
+            let other = rx.clone();
+
+            let drop_all = async move {
+                futures_timer::Delay::new(Duration::from_millis(10)).await;
+                drop(other);
+                futures_timer::Delay::new(Duration::from_millis(10)).await;
+                drop(rx);
+            };
+            let send = tx.send_timeout(2, Duration::from_secs(5));
+            let (sent, _) = futures_lite::future::zip(send, drop_all).await;
+            assert!(matches!(sent, Err(SendTimeoutError::Closed(2))));
+        });
+    }
 }
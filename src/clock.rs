@@ -0,0 +1,25 @@
+//! A source of time for the [rate limits](crate::rate_limit) and the runner's timeouts
+//!
+//! This is the [SystemClock] unless you provide another one, e.g. a
+//! [TestClock](crate::test::TestClock) to test pacing without sleeping.
+use std::{sync::Arc, time::Instant};
+
+/// Something that tells the current time
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// The current time
+    fn now(&self) -> Instant;
+}
+
+/// The real clock, this uses [Instant::now()]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+pub(crate) fn system() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
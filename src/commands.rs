@@ -44,12 +44,17 @@ macro_rules! write_nl {
 }
 
 macro_rules! export_commands {
-    ($($ident:ident => $ty:ident)*) => {
+    ($($ident:ident => $ty:ident $(, $owned:ident)?)*) => {
         /// Concrete types produced by the functions in the `commands` module.
         ///
         /// e.g. `join("#museun") -> Join<'_>` (where its borrowed from the input `&str`)
+        ///
+        /// or `join_owned("#museun") -> JoinOwned` (which owns its strings)
         pub mod types {
-            $( pub use super::$ident::$ty; )*
+            $(
+                pub use super::$ident::$ty;
+                $( pub use super::$ident::$owned; )?
+            )*
         }
         $(
             mod $ident;
@@ -64,6 +69,7 @@ export_commands! {
     color           => Color
     command         => Command
     commercial      => Commercial
+    delete          => Delete
     disconnect      => Disconnect
     emote_only      => EmoteOnly
     emote_only_off  => EmoteOnlyOff
@@ -72,15 +78,16 @@ export_commands! {
     give_mod        => GiveMod
     help            => Help
     host            => Host
-    join            => Join
+    join            => Join, JoinOwned
     jtv_command     => JtvCommand
     marker          => Marker
     me              => Me
+    message         => Message
     mods            => Mods
-    part            => Part
+    part            => Part, PartOwned
     ping            => Ping
     pong            => Pong
-    privmsg         => Privmsg
+    privmsg         => Privmsg, PrivmsgOwned
     r9k_beta        => R9kBeta
     r9k_beta_off    => R9kBetaOff
     raid            => Raid
@@ -100,9 +107,21 @@ export_commands! {
     unvip           => Unvip
     vip             => Vip
     vips            => Vips
-    whisper         => Whisper
+    whisper         => Whisper, WhisperOwned
 }
 
+pub use message::Target;
+pub use whisper::whisper_many;
+
+pub use join::try_join;
+pub use part::try_part;
+pub use privmsg::try_privmsg;
+
+pub use join::join_owned;
+pub use part::part_owned;
+pub use privmsg::privmsg_owned;
+pub use whisper::whisper_owned;
+
 macro_rules! serde_for_commands {
     (@one $($x:tt)*) => { () };
     (@len $($e:expr),*) => { <[()]>::len(&[$(serde_for_commands!(@one $e)),*]); };
@@ -141,6 +160,7 @@ serde_for_commands! {
     Command { channel, data };
     JtvCommand { data };
     Commercial { channel, length };
+    Delete { channel, msg_id };
     Disconnect { };
     EmoteOnly { channel };
     EmoteOnlyOff { channel };
@@ -152,6 +172,7 @@ serde_for_commands! {
     Join { channel };
     Marker { channel, comment };
     Me { channel, msg };
+    Message { target, data };
     Mods { channel };
     Ping { token };
     Part { channel };
@@ -219,6 +240,26 @@ where
     }
 }
 
+/// Replaces any control characters (e.g. `\r` and `\n`) with spaces, so user-provided text stays on one line.
+#[derive(Copy, Clone)]
+pub(crate) struct Sanitized<'a>(pub &'a str);
+
+impl<'a> Length for Sanitized<'a> {
+    fn length(&self) -> usize {
+        self.0.chars().count()
+    }
+}
+
+impl<'a> Display for Sanitized<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write as _;
+        for ch in self.0.chars() {
+            f.write_char(if ch.is_control() { ' ' } else { ch })?;
+        }
+        Ok(())
+    }
+}
+
 /// A channel wrapper that when `.to_string()` is called will ensure a leading `#` is added.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash)]
 pub struct Channel<'a>(pub(crate) &'a str);
@@ -228,8 +269,57 @@ impl<'a> Channel<'a> {
     pub const fn new(channel: &'a str) -> Self {
         Self(channel)
     }
+
+    /// Create a new channel wrapper, checking that `channel` is a valid channel name
+    ///
+    /// Unlike [Channel::new()], this won't lowercase the name for you.
+    ///
+    /// ```
+    /// # use trovochat::commands::{Channel, ChannelError};
+    /// assert_eq!(Channel::try_new("museun").unwrap().to_string(), "#museun");
+    /// assert_eq!(Channel::try_new("#").unwrap_err(), ChannelError::Empty);
+    /// assert_eq!(Channel::try_new("some user").unwrap_err(), ChannelError::ContainsWhitespace);
+    /// assert_eq!(Channel::try_new("Museun").unwrap_err(), ChannelError::ContainsUppercase);
+    /// ```
+    pub fn try_new(channel: &'a str) -> Result<Self, ChannelError> {
+        let name = channel.strip_prefix('#').unwrap_or(channel);
+        if name.is_empty() {
+            return Err(ChannelError::Empty);
+        }
+        if name.chars().any(char::is_whitespace) {
+            return Err(ChannelError::ContainsWhitespace);
+        }
+        if name.chars().any(char::is_uppercase) {
+            return Err(ChannelError::ContainsUppercase);
+        }
+        Ok(Self(channel))
+    }
+}
+
+/// An error returned when a channel name isn't valid
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ChannelError {
+    /// The channel name was empty
+    Empty,
+    /// The channel name contained whitespace, e.g. it was a display name with a space in it
+    ContainsWhitespace,
+    /// The channel name contained uppercase characters, which Trovo would lowercase
+    ContainsUppercase,
+}
+
+impl Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("channel name is empty"),
+            Self::ContainsWhitespace => f.write_str("channel name contains whitespace"),
+            Self::ContainsUppercase => f.write_str("channel name contains uppercase characters"),
+        }
+    }
 }
 
+impl std::error::Error for ChannelError {}
+
 impl<'a> Display for Channel<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // TODO IRC has special casing rules. We should implement it
@@ -241,6 +331,61 @@ impl<'a> Display for Channel<'a> {
     }
 }
 
+/// A type that names a channel, e.g. a `&str` or a message that was sent to a channel.
+///
+/// This lets you reply to the channel of a message:
+/// ```
+/// # use trovochat::{commands, messages::Privmsg, FromIrcMessage as _};
+/// # let input = ":museun!museun@museun PRIVMSG #museun :hello\r\n";
+/// # let msg = trovochat::irc::parse(input).next().unwrap().unwrap();
+/// let pm = Privmsg::from_irc(msg).unwrap();
+/// let cmd = commands::privmsg(&pm, "hi there");
+/// ```
+pub trait IntoChannel<'a> {
+    /// Get the name of the channel
+    fn into_channel(self) -> &'a str;
+
+    /// Get the name of the channel, checking that it is valid
+    ///
+    /// See [Channel::try_new()] for what is rejected.
+    fn try_into_channel(self) -> Result<Channel<'a>, ChannelError>
+    where
+        Self: Sized,
+    {
+        Channel::try_new(self.into_channel())
+    }
+}
+
+impl<'a> IntoChannel<'a> for &'a str {
+    fn into_channel(self) -> &'a str {
+        self
+    }
+}
+
+impl<'a> IntoChannel<'a> for &'a String {
+    fn into_channel(self) -> &'a str {
+        self
+    }
+}
+
+impl<'a> IntoChannel<'a> for &'a crate::messages::Privmsg<'_> {
+    fn into_channel(self) -> &'a str {
+        self.channel()
+    }
+}
+
+impl<'a> IntoChannel<'a> for &'a crate::messages::RoomState<'_> {
+    fn into_channel(self) -> &'a str {
+        self.channel()
+    }
+}
+
+impl<'a> IntoChannel<'a> for &'a crate::messages::UserState<'_> {
+    fn into_channel(self) -> &'a str {
+        self.channel()
+    }
+}
+
 #[cfg(test)]
 fn test_encode(enc: impl Encodable, expected: impl for<'a> PartialEq<&'a str> + std::fmt::Debug) {
     let mut data = vec![];
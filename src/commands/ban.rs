@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{Channel, Encodable, MaybeEmpty, Sanitized};
 use std::io::{Result, Write};
 
 /// Permanently prevent a user from chatting.
@@ -15,6 +15,7 @@ pub struct Ban<'a> {
 /// Permanently prevent a user from chatting.
 ///
 /// Reason is optional and will be shown to the target user and other moderators.
+/// Any control characters (e.g. newlines) in it are replaced with spaces.
 ///
 /// Use [unban] to remove a ban.
 ///
@@ -34,7 +35,7 @@ impl<'a> Encodable for Ban<'a> {
     {
         write_cmd!(buf,
             Channel(self.channel) =>
-            "/ban {}{}", self.username, MaybeEmpty(self.reason)
+            "/ban {}{}", self.username, MaybeEmpty(self.reason.map(Sanitized))
         )
     }
 }
@@ -52,6 +53,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ban_reason() {
+        test_encode(
+            ban("#museun", "museun", "spamming links in chat"),
+            "PRIVMSG #museun :/ban museun spamming links in chat\r\n",
+        );
+        test_encode(
+            ban("#museun", "museun", "spamming\r\nPRIVMSG #museun :hi"),
+            "PRIVMSG #museun :/ban museun spamming  PRIVMSG #museun :hi\r\n",
+        );
+        // the length counts each control character, like the space it's written as
+        test_encode(
+            ban("#museun", "museun", "\r\n"),
+            "PRIVMSG #museun :/ban museun   \r\n",
+        );
+    }
+
     #[test]
     fn ban_ensure_channel() {
         test_encode(
@@ -0,0 +1,89 @@
+use super::{Channel, Encodable};
+use std::io::{Error, ErrorKind, Result, Write};
+
+/// Delete a single message on `channel`.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct Delete<'a> {
+    pub(crate) channel: &'a str,
+    pub(crate) msg_id: &'a str,
+}
+
+/// Delete a single message on `channel`.
+///
+/// `msg_id` is the `id` tag of the message, e.g. the `target-msg-id` of a [ClearMsg].
+///
+/// Encoding this will fail with [ErrorKind::InvalidInput] if `msg_id` is empty.
+///
+/// [ClearMsg]: crate::messages::ClearMsg
+pub const fn delete<'a>(channel: &'a str, msg_id: &'a str) -> Delete<'a> {
+    Delete { channel, msg_id }
+}
+
+impl<'a> Encodable for Delete<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        if self.msg_id.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "msg_id must not be empty",
+            ));
+        }
+
+        write_cmd!(buf, Channel(self.channel) => "/delete {}", self.msg_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn delete_encode() {
+        test_encode(
+            delete("#museun", "abc-123-def"),
+            "PRIVMSG #museun :/delete abc-123-def\r\n",
+        );
+    }
+
+    #[test]
+    fn delete_ensure_channel_encode() {
+        test_encode(
+            delete("museun", "abc-123-def"),
+            "PRIVMSG #museun :/delete abc-123-def\r\n",
+        );
+    }
+
+    #[test]
+    fn delete_empty_msg_id() {
+        for msg_id in &["", " "] {
+            let mut data = vec![];
+            let err = delete("#museun", msg_id).encode(&mut data).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput);
+            assert!(data.is_empty());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn delete_serde() {
+        test_serde(
+            delete("#museun", "abc-123-def"),
+            "PRIVMSG #museun :/delete abc-123-def\r\n",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn delete_ensure_channel_serde() {
+        test_serde(
+            delete("museun", "abc-123-def"),
+            "PRIVMSG #museun :/delete abc-123-def\r\n",
+        );
+    }
+}
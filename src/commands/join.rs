@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{Channel, ChannelError, Encodable};
 use std::io::{Result, Write};
 
 /// Join a channel. This handles prepending a leading '#' for you if you omit it.
@@ -15,9 +15,37 @@ pub const fn join(channel: &str) -> Join<'_> {
     Join { channel }
 }
 
+/// Join a channel, checking that it is a valid channel name first.
+///
+/// See [Channel::try_new()] for what is rejected.
+pub fn try_join(channel: &str) -> std::result::Result<Join<'_>, ChannelError> {
+    Channel::try_new(channel).map(|Channel(channel)| Join { channel })
+}
+
 impl<'a> Encodable for Join<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
-        write_nl!(buf, "JOIN {}", super::Channel(self.channel))
+        write_nl!(buf, "JOIN {}", Channel(self.channel))
+    }
+}
+
+/// Join a channel. This is like [Join] but it owns the channel name.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub struct JoinOwned {
+    pub(crate) channel: String,
+}
+
+/// Join a channel, with an owned channel name. See [join()].
+pub fn join_owned(channel: impl Into<String>) -> JoinOwned {
+    JoinOwned {
+        channel: channel.into(),
+    }
+}
+
+impl Encodable for JoinOwned {
+    fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        join(&self.channel).encode(buf)
     }
 }
 
@@ -32,12 +60,49 @@ mod tests {
         test_encode(join("#MUSEUN"), "JOIN #museun\r\n");
     }
 
+    #[test]
+    fn join_owned_encode() {
+        test_encode(join_owned(String::from("museun")), "JOIN #museun\r\n");
+        test_encode(join_owned("#MUSEUN"), "JOIN #museun\r\n");
+    }
+
     #[test]
     fn join_ensure_channel_encode() {
         test_encode(join("museun"), "JOIN #museun\r\n");
         test_encode(join("MUSEUN"), "JOIN #museun\r\n");
     }
 
+    #[test]
+    fn try_join_encode() {
+        test_encode(try_join("#museun").unwrap(), "JOIN #museun\r\n");
+        test_encode(try_join("museun").unwrap(), "JOIN #museun\r\n");
+
+        let inputs = &[
+            ("", ChannelError::Empty),
+            ("#", ChannelError::Empty),
+            ("some user", ChannelError::ContainsWhitespace),
+            ("#some\tuser", ChannelError::ContainsWhitespace),
+            ("Museun", ChannelError::ContainsUppercase),
+            ("#MUSEUN", ChannelError::ContainsUppercase),
+        ];
+        for (input, expected) in inputs {
+            assert_eq!(try_join(input).unwrap_err(), *expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn join_encode_without_terminator() {
+        let mut with = vec![];
+        join("museun").encode(&mut with).unwrap();
+        assert_eq!(with, b"JOIN #museun\r\n");
+
+        let mut without = vec![];
+        join("museun")
+            .encode_without_terminator(&mut without)
+            .unwrap();
+        assert_eq!(without, b"JOIN #museun");
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn join_serde() {
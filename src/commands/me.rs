@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{Channel, Encodable, IntoChannel};
 use std::io::{Result, Write};
 
 /// Sends an "emote" message in the third person to the channel
@@ -12,8 +12,13 @@ pub struct Me<'a> {
 }
 
 /// Sends an "emote" message in the third person to the channel
-pub const fn me<'a>(channel: &'a str, msg: &'a str) -> Me<'a> {
-    Me { channel, msg }
+///
+/// The `channel` can also be a message that was sent to a channel, e.g. a [Privmsg](crate::messages::Privmsg).
+pub fn me<'a>(channel: impl IntoChannel<'a>, msg: &'a str) -> Me<'a> {
+    Me {
+        channel: channel.into_channel(),
+        msg,
+    }
 }
 
 impl<'a> Encodable for Me<'a> {
@@ -0,0 +1,94 @@
+use super::{Channel, Encodable};
+use std::io::{Result, Write};
+
+/// Where a [message] should be sent
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum Target<'a> {
+    /// A channel, this'll be a normal `PRIVMSG`
+    Channel(&'a str),
+    /// A user, this'll be a whisper
+    User(&'a str),
+}
+
+/// Send a message to either a channel or a user
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Deserialize))]
+pub struct Message<'a> {
+    pub(crate) target: Target<'a>,
+    pub(crate) data: &'a str,
+}
+
+/// Send a message to either a channel or a user
+///
+/// This lets you reply the same way to a [Privmsg](crate::messages::Privmsg) and to a [Whisper](crate::messages::Whisper).
+///
+/// ```
+/// use trovochat::commands::{message, Target};
+/// # use trovochat::Encodable;
+/// let mut out = vec![];
+/// message(Target::Channel("museun"), "hello").encode(&mut out).unwrap();
+/// message(Target::User("museun"), "hello").encode(&mut out).unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&out).unwrap(),
+///     "PRIVMSG #museun :hello\r\nPRIVMSG jtv :/w museun hello\r\n"
+/// );
+/// ```
+pub const fn message<'a>(target: Target<'a>, data: &'a str) -> Message<'a> {
+    Message { target, data }
+}
+
+impl<'a> Encodable for Message<'a> {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        match self.target {
+            Target::Channel(channel) => {
+                write_nl!(buf, "PRIVMSG {} :{}", Channel(channel), self.data)
+            }
+            Target::User(user) => write_jtv_cmd!(buf, "/w {} {}", user, self.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn message_channel_encode() {
+        test_encode(
+            message(Target::Channel("#museun"), "this is a test"),
+            "PRIVMSG #museun :this is a test\r\n",
+        );
+        test_encode(
+            message(Target::Channel("museun"), "this is a test"),
+            "PRIVMSG #museun :this is a test\r\n",
+        );
+    }
+
+    #[test]
+    fn message_user_encode() {
+        test_encode(
+            message(Target::User("museun"), "this is a test"),
+            "PRIVMSG jtv :/w museun this is a test\r\n",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn message_serde() {
+        test_serde(
+            message(Target::Channel("#museun"), "this is a test"),
+            "PRIVMSG #museun :this is a test\r\n",
+        );
+        test_serde(
+            message(Target::User("museun"), "this is a test"),
+            "PRIVMSG jtv :/w museun this is a test\r\n",
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::Encodable;
+use super::{Channel, ChannelError, Encodable};
 use std::io::{Result, Write};
 
 /// Leave a channel. This handles prepending a leading '#' for you if you omit it.
@@ -15,9 +15,37 @@ pub const fn part(channel: &str) -> Part<'_> {
     Part { channel }
 }
 
+/// Leave a channel, checking that it is a valid channel name first.
+///
+/// See [Channel::try_new()] for what is rejected.
+pub fn try_part(channel: &str) -> std::result::Result<Part<'_>, ChannelError> {
+    Channel::try_new(channel).map(|Channel(channel)| Part { channel })
+}
+
 impl<'a> Encodable for Part<'a> {
     fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
-        write!(buf, "PART {}\r\n", super::Channel(self.channel))
+        write!(buf, "PART {}\r\n", Channel(self.channel))
+    }
+}
+
+/// Leave a channel. This is like [Part] but it owns the channel name.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub struct PartOwned {
+    pub(crate) channel: String,
+}
+
+/// Leave a channel, with an owned channel name. See [part()].
+pub fn part_owned(channel: impl Into<String>) -> PartOwned {
+    PartOwned {
+        channel: channel.into(),
+    }
+}
+
+impl Encodable for PartOwned {
+    fn encode<W: Write + ?Sized>(&self, buf: &mut W) -> Result<()> {
+        part(&self.channel).encode(buf)
     }
 }
 
@@ -32,12 +60,32 @@ mod tests {
         test_encode(part("#MUSEUN"), "PART #museun\r\n");
     }
 
+    #[test]
+    fn part_owned_encode() {
+        test_encode(part_owned(String::from("museun")), "PART #museun\r\n");
+        test_encode(part_owned("#MUSEUN"), "PART #museun\r\n");
+    }
+
     #[test]
     fn part_ensure_channel_encode() {
         test_encode(part("museun"), "PART #museun\r\n");
         test_encode(part("MUSEUN"), "PART #museun\r\n");
     }
 
+    #[test]
+    fn try_part_encode() {
+        test_encode(try_part("museun").unwrap(), "PART #museun\r\n");
+        assert_eq!(try_part("").unwrap_err(), ChannelError::Empty);
+        assert_eq!(
+            try_part("some user").unwrap_err(),
+            ChannelError::ContainsWhitespace
+        );
+        assert_eq!(
+            try_part("Museun").unwrap_err(),
+            ChannelError::ContainsUppercase
+        );
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn part_serde() {
@@ -1,4 +1,4 @@
-use super::{Channel, Encodable};
+use super::{Channel, ChannelError, Encodable, IntoChannel};
 use std::io::{Result, Write};
 
 /// Send a normal message to a channel
@@ -12,8 +12,25 @@ pub struct Privmsg<'a> {
 }
 
 /// Send a normal message to a channel
-pub const fn privmsg<'a>(channel: &'a str, msg: &'a str) -> Privmsg<'a> {
-    Privmsg { channel, msg }
+///
+/// The `channel` can also be a message that was sent to a channel, e.g. a [Privmsg](crate::messages::Privmsg).
+pub fn privmsg<'a>(channel: impl IntoChannel<'a>, msg: &'a str) -> Privmsg<'a> {
+    Privmsg {
+        channel: channel.into_channel(),
+        msg,
+    }
+}
+
+/// Send a normal message to a channel, checking that it is a valid channel name first.
+///
+/// See [Channel::try_new()] for what is rejected.
+pub fn try_privmsg<'a>(
+    channel: impl IntoChannel<'a>,
+    msg: &'a str,
+) -> std::result::Result<Privmsg<'a>, ChannelError> {
+    channel
+        .try_into_channel()
+        .map(|Channel(channel)| Privmsg { channel, msg })
 }
 
 impl<'a> Encodable for Privmsg<'a> {
@@ -25,6 +42,34 @@ impl<'a> Encodable for Privmsg<'a> {
     }
 }
 
+/// Send a normal message to a channel. This is like [Privmsg] but it owns its strings.
+///
+/// Being `'static`, this can be moved into a spawned task.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub struct PrivmsgOwned {
+    pub(crate) channel: String,
+    pub(crate) msg: String,
+}
+
+/// Send a normal message to a channel, with owned strings. See [privmsg()].
+pub fn privmsg_owned(channel: impl Into<String>, msg: impl Into<String>) -> PrivmsgOwned {
+    PrivmsgOwned {
+        channel: channel.into(),
+        msg: msg.into(),
+    }
+}
+
+impl Encodable for PrivmsgOwned {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        privmsg(&self.channel, &self.msg).encode(buf)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -43,6 +88,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn privmsg_into_channel() {
+        use crate::FromIrcMessage as _;
+
+        let input = ":museun!museun@museun PRIVMSG #museun :hello\r\n";
+        let msg = crate::irc::parse(input).next().unwrap().unwrap();
+        let pm = crate::messages::Privmsg::from_irc(msg).unwrap();
+        test_encode(privmsg(&pm, "hi there"), "PRIVMSG #museun :hi there\r\n");
+
+        let input = ":tmi.trovo.tv ROOMSTATE #shaken_bot\r\n";
+        let msg = crate::irc::parse(input).next().unwrap().unwrap();
+        let rs = crate::messages::RoomState::from_irc(msg).unwrap();
+        test_encode(
+            privmsg(&rs, "hi there"),
+            "PRIVMSG #shaken_bot :hi there\r\n",
+        );
+
+        let channel = String::from("museun");
+        test_encode(
+            privmsg(&channel, "hi there"),
+            "PRIVMSG #museun :hi there\r\n",
+        );
+    }
+
+    #[test]
+    fn try_privmsg_encode() {
+        test_encode(
+            try_privmsg("museun", "hello").unwrap(),
+            "PRIVMSG #museun :hello\r\n",
+        );
+        assert_eq!(
+            try_privmsg("some user", "hello").unwrap_err(),
+            ChannelError::ContainsWhitespace
+        );
+        assert_eq!(
+            try_privmsg("#Museun", "hello").unwrap_err(),
+            ChannelError::ContainsUppercase
+        );
+    }
+
+    #[test]
+    fn privmsg_owned_encode() {
+        let (channel, msg) = (String::from("museun"), String::from("hello"));
+        let cmd = privmsg_owned(channel, msg);
+
+        let handle = std::thread::spawn(move || {
+            let mut data = vec![];
+            cmd.encode(&mut data).unwrap();
+            data
+        });
+        assert_eq!(handle.join().unwrap(), b"PRIVMSG #museun :hello\r\n");
+
+        test_encode(
+            privmsg_owned("#museun", "hi there"),
+            "PRIVMSG #museun :hi there\r\n",
+        );
+    }
+
     #[test]
     fn privmsg_ensure_channel_encode() {
         test_encode(
@@ -1,5 +1,5 @@
 use super::Encodable;
-use crate::trovo::UserConfig;
+use crate::trovo::{Capability, UserConfig};
 
 use std::io::Write;
 
@@ -34,11 +34,12 @@ impl<'a> Encodable for Register<'a> {
             capabilities,
         } = &self.user_config;
 
-        // the caps have to be written first
-        for cap in capabilities {
-            let cap = cap.encode_as_str();
-            write!(buf, "{}\r\n", cap)?;
-        }
+        // the caps have to be written first. a request is rejected as a whole, so the
+        // optional ones are requested apart from the required ones
+        let (required, optional): (Vec<_>, Vec<_>) =
+            capabilities.iter().partition(|cap| cap.is_required());
+        buf.write_all(Capability::request_line(&optional).as_bytes())?;
+        buf.write_all(Capability::request_line(&required).as_bytes())?;
 
         write!(buf, "PASS {}\r\n", token)?;
         write!(buf, "NICK {}\r\n", name)?;
@@ -62,8 +63,29 @@ mod tests {
         test_encode(
             register(&config),
             "CAP REQ :trovo.tv/membership\r\n\
-            CAP REQ :trovo.tv/tags\r\n\
-            CAP REQ :trovo.tv/commands\r\n\
+            CAP REQ :trovo.tv/tags trovo.tv/commands\r\n\
+            PASS justinfan1234\r\n\
+            NICK justinfan1234\r\n",
+        )
+    }
+
+    #[test]
+    fn register_ordered_caps() {
+        let config = UserConfig {
+            name: "justinfan1234".into(),
+            token: "justinfan1234".into(),
+            capabilities: vec![
+                Capability::Commands,
+                Capability::Tags,
+                Capability::Commands,
+                Capability::Membership,
+            ],
+        };
+
+        test_encode(
+            register(&config),
+            "CAP REQ :trovo.tv/membership\r\n\
+            CAP REQ :trovo.tv/tags trovo.tv/commands\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
@@ -81,8 +103,7 @@ mod tests {
         test_serde(
             register(&config),
             "CAP REQ :trovo.tv/membership\r\n\
-            CAP REQ :trovo.tv/tags\r\n\
-            CAP REQ :trovo.tv/commands\r\n\
+            CAP REQ :trovo.tv/tags trovo.tv/commands\r\n\
             PASS justinfan1234\r\n\
             NICK justinfan1234\r\n",
         )
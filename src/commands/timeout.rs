@@ -1,4 +1,4 @@
-use super::{Channel, Encodable, MaybeEmpty};
+use super::{Channel, Encodable, MaybeEmpty, Sanitized};
 use std::io::{Result, Write};
 
 /// Temporarily prevent a user from chatting.
@@ -27,6 +27,7 @@ pub struct Timeout<'a> {
 /// Combinations like `1d2h` are also allowed.
 ///
 /// Reason is optional and will be shown to the target user and other moderators.
+/// Any control characters (e.g. newlines) in it are replaced with spaces.
 ///
 /// Use [untimeout] to remove a timeout.
 ///
@@ -54,7 +55,7 @@ impl<'a> Encodable for Timeout<'a> {
             "/timeout {}{}{}",
             self.username,
             MaybeEmpty(self.duration),
-            MaybeEmpty(self.reason),
+            MaybeEmpty(self.reason.map(Sanitized)),
         )
     }
 }
@@ -84,6 +85,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn timeout_reason() {
+        test_encode(
+            timeout("#museun", "museun", "10m", "spamming links in chat"),
+            "PRIVMSG #museun :/timeout museun 10m spamming links in chat\r\n",
+        );
+        test_encode(
+            timeout("#museun", "museun", None, "spamming\r\nPRIVMSG #museun :hi"),
+            "PRIVMSG #museun :/timeout museun spamming  PRIVMSG #museun :hi\r\n",
+        );
+    }
+
     #[test]
     fn timeout_ensure_channel_encode() {
         test_encode(
@@ -16,6 +16,21 @@ pub const fn whisper<'a>(username: &'a str, message: &'a str) -> Whisper<'a> {
     Whisper { username, message }
 }
 
+/// Whispers the same message to each of the usernames.
+///
+/// Trovo has no multi-target whisper, so this yields one [Whisper] per username.
+///
+/// When these are sent through an [AsyncWriter](crate::writer::AsyncWriter), the runner
+/// paces them with [RateLimit::trovo_whisper()](crate::rate_limit::RateLimit::trovo_whisper()).
+pub fn whisper_many<'a>(
+    usernames: &'a [&'a str],
+    message: &'a str,
+) -> impl Iterator<Item = Whisper<'a>> + 'a {
+    usernames
+        .iter()
+        .map(move |username| whisper(username, message))
+}
+
 impl<'a> Encodable for Whisper<'a> {
     fn encode<W>(&self, buf: &mut W) -> Result<()>
     where
@@ -25,6 +40,32 @@ impl<'a> Encodable for Whisper<'a> {
     }
 }
 
+/// Whispers a message to the username. This is like [Whisper] but it owns its strings.
+#[non_exhaustive]
+#[must_use = "commands must be encoded"]
+#[derive(Debug, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub struct WhisperOwned {
+    pub(crate) username: String,
+    pub(crate) message: String,
+}
+
+/// Whispers a message to the username, with owned strings. See [whisper()].
+pub fn whisper_owned(username: impl Into<String>, message: impl Into<String>) -> WhisperOwned {
+    WhisperOwned {
+        username: username.into(),
+        message: message.into(),
+    }
+}
+
+impl Encodable for WhisperOwned {
+    fn encode<W>(&self, buf: &mut W) -> Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        whisper(&self.username, &self.message).encode(buf)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::*;
@@ -38,6 +79,28 @@ mod tests {
         )
     }
 
+    #[test]
+    fn whisper_owned_encode() {
+        test_encode(
+            whisper_owned(String::from("museun"), "hello world"),
+            "PRIVMSG jtv :/w museun hello world\r\n",
+        )
+    }
+
+    #[test]
+    fn whisper_many_encode() {
+        let usernames = ["museun", "shaken_bot", "justinfan1234"];
+        let whispers = whisper_many(&usernames, "hello world").collect::<Vec<_>>();
+        assert_eq!(whispers.len(), usernames.len());
+
+        for (whisper, username) in whispers.into_iter().zip(&usernames) {
+            test_encode(
+                whisper,
+                format!("PRIVMSG jtv :/w {} hello world\r\n", username),
+            )
+        }
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn whisper_serde() {
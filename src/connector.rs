@@ -24,6 +24,12 @@
 //! | [`tokio`](https://docs.rs/tokio/0.2/tokio/)                | `tokio`     | `"tokio-util"`, `"tokio-native-tls"`, `"native-tls"` | [`native-tls`][native-tls] |
 //! | [`tokio`](https://docs.rs/tokio/0.2/tokio/)                | `tokio`     | `"tokio-util"`, `"tokio-openssl"`, `"openssl"`       | [`openssl`][openssl]       |
 //!
+//! ## WebSockets
+//!
+//! | Read/Write provider                                        | Runtime     | Features                                                     | TLS backend                |
+//! | ---------------------------------------------------------- | ----------- | ------------------------------------------------------------ | -------------------------- |
+//! | [`tokio`](https://docs.rs/tokio/0.2/tokio/)                | `tokio`     | `"tokio-util"`, `"tokio-tungstenite"`, `"futures-util"`      | [`native-tls`][native-tls] |
+//!
 //! [rustls]: https://docs.rs/rustls/0.18.1/rustls/
 //! [native-tls]: https://docs.rs/native-tls/0.2.4/native_tls/
 //! [openssl]: https://docs.rs/openssl/0.10/openssl/
@@ -80,6 +86,12 @@ macro_rules! connector_ctor {
     };
 }
 
+mod retry;
+pub use retry::RetryConnector;
+
+mod boxed;
+pub use boxed::{BoxedConnector, BoxedStream};
+
 #[cfg(feature = "async-io")]
 /// Connector for using an [`async_io`](https://docs.rs/async-io/latest/async_io/) wrapper over [`std::net::TcpStream`](https://doc.rust-lang.org/std/net/struct.TcpStream.html)
 pub mod async_io;
@@ -151,6 +163,15 @@ pub use self::tokio::ConnectorNativeTls as TokioConnectorNativeTls;
 #[doc(inline)]
 pub use self::tokio::ConnectorOpenSsl as TokioConnectorOpenSsl;
 
+#[cfg(all(
+    feature = "tokio",
+    feature = "tokio-util",
+    feature = "tokio-tungstenite",
+    feature = "futures-util"
+))]
+#[doc(inline)]
+pub use self::tokio::WsConnector as TokioWsConnector;
+
 /// The connector trait. This is used to abstract out runtimes.
 ///
 /// You can implement this on your own type to provide a custom connection behavior.
@@ -224,6 +245,14 @@ mod required {
         "'tokio', 'tokio-util' and 'webpki-roots' must be enabled when 'tokio-rustls' is enabled"
     }
 
+    #[cfg(all(
+        feature = "tokio-tungstenite",
+        not(all(feature = "tokio", feature = "tokio-util", feature = "futures-util"))
+    ))]
+    compile_error! {
+        "'tokio', 'tokio-util' and 'futures-util' must be enabled when 'tokio-tungstenite' is enabled"
+    }
+
     #[cfg(all(
         feature = "tokio-openssl",
         not(all(feature = "tokio", feature = "tokio-util", feature = "openssl"))
@@ -0,0 +1,152 @@
+use super::Connector;
+
+use futures_lite::{AsyncRead, AsyncWrite};
+use std::{
+    io::Result as IoResult,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A [Connector] with its type erased
+///
+/// This is what [AsyncRunner::into_parts()](crate::AsyncRunner::into_parts()) gives back,
+/// so it can be used to connect a new runner.
+pub struct BoxedConnector(Box<dyn DynConnector>);
+
+impl BoxedConnector {
+    /// Erase the type of `connector`
+    pub fn new<C>(connector: C) -> Self
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        Self(Box::new(connector))
+    }
+}
+
+impl std::fmt::Debug for BoxedConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoxedConnector").finish()
+    }
+}
+
+impl Clone for BoxedConnector {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl Connector for BoxedConnector {
+    type Output = BoxedStream;
+
+    fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>> {
+        self.0.connect()
+    }
+}
+
+trait DynConnector: Send + Sync {
+    fn connect(&mut self) -> crate::BoxedFuture<IoResult<BoxedStream>>;
+    fn clone_box(&self) -> Box<dyn DynConnector>;
+}
+
+impl<C> DynConnector for C
+where
+    C: Connector + 'static,
+    for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+{
+    fn connect(&mut self) -> crate::BoxedFuture<IoResult<BoxedStream>> {
+        let fut = Connector::connect(self);
+        Box::pin(async move { fut.await.map(|stream| BoxedStream(Box::new(stream))) })
+    }
+
+    fn clone_box(&self) -> Box<dyn DynConnector> {
+        Box::new(self.clone())
+    }
+}
+
+/// The connection a [BoxedConnector] produces
+pub struct BoxedStream(Box<dyn SharedStream>);
+
+impl std::fmt::Debug for BoxedStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoxedStream").finish()
+    }
+}
+
+// the runner reads and writes through a shared reference, so this is what gets erased
+trait SharedStream: Send + Sync {
+    fn poll_read(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IoResult<usize>>;
+    fn poll_write(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>>;
+    fn poll_flush(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>>;
+    fn poll_close(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>>;
+}
+
+impl<T> SharedStream for T
+where
+    T: Send + Sync,
+    for<'a> &'a T: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IoResult<usize>> {
+        Pin::new(&mut &*self).poll_read(cx, buf)
+    }
+
+    fn poll_write(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        Pin::new(&mut &*self).poll_write(cx, buf)
+    }
+
+    fn poll_flush(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut &*self).poll_flush(cx)
+    }
+
+    fn poll_close(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut &*self).poll_close(cx)
+    }
+}
+
+impl AsyncRead for &BoxedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<IoResult<usize>> {
+        self.0.poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for &BoxedStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        self.0.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.0.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.0.poll_close(cx)
+    }
+}
+
+impl AsyncRead for BoxedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<IoResult<usize>> {
+        self.0.poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for BoxedStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        self.0.poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.0.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.0.poll_close(cx)
+    }
+}
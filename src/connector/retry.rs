@@ -0,0 +1,132 @@
+use super::Connector;
+use crate::runner::RetryStrategy;
+
+use std::io::Result as IoResult;
+
+/// A [Connector] that retries connecting with a [RetryStrategy]
+///
+/// This is only for the initial connection, e.g. when the network isn't up yet.
+#[derive(Debug, Clone)]
+pub struct RetryConnector<C> {
+    connector: C,
+    strategy: RetryStrategy,
+}
+
+impl<C> RetryConnector<C>
+where
+    C: Connector,
+{
+    /// Wrap `connector`, retrying its connection with this `strategy`
+    pub fn new(connector: C, strategy: RetryStrategy) -> Self {
+        Self {
+            connector,
+            strategy,
+        }
+    }
+
+    /// Consume this, returning the wrapped connector
+    pub fn into_inner(self) -> C {
+        self.connector
+    }
+}
+
+impl<C> Connector for RetryConnector<C>
+where
+    C: Connector + 'static,
+{
+    type Output = C::Output;
+
+    fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>> {
+        let mut connector = self.connector.clone();
+        let strategy = self.strategy;
+
+        Box::pin(async move {
+            let mut attempt = 0;
+            loop {
+                let err = match connector.connect().await {
+                    Ok(conn) => return Ok(conn),
+                    Err(err) => err,
+                };
+
+                attempt += 1;
+                let delay = match strategy.delay_for(attempt) {
+                    Some(delay) => delay,
+                    None => return Err(err),
+                };
+
+                log::warn!(
+                    "could not connect: {}. retrying in {:?} (attempt {})",
+                    err,
+                    delay,
+                    attempt
+                );
+                futures_timer::Delay::new(delay).await;
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::test::{TestConn, TestConnector};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    // a connector that fails the first `fails` times
+    #[derive(Default, Clone)]
+    struct FlakyConnector {
+        fails: usize,
+        dials: Arc<AtomicUsize>,
+        inner: TestConnector,
+    }
+
+    impl Connector for FlakyConnector {
+        type Output = TestConn;
+
+        fn connect(&mut self) -> crate::BoxedFuture<IoResult<Self::Output>> {
+            if self.dials.fetch_add(1, Ordering::SeqCst) < self.fails {
+                let err = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "flaky");
+                return Box::pin(async move { Err(err) });
+            }
+            self.inner.connect()
+        }
+    }
+
+    #[test]
+    fn retry_until_connected() {
+        let connector = FlakyConnector {
+            fails: 3,
+            ..Default::default()
+        };
+        let dials = connector.dials.clone();
+
+        let strategy = RetryStrategy::constant(Duration::from_millis(1)).max_attempts(5);
+        let mut connector = RetryConnector::new(connector, strategy);
+
+        futures_lite::future::block_on(connector.connect()).unwrap();
+        assert_eq!(dials.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn retry_gives_up() {
+        let connector = FlakyConnector {
+            fails: 10,
+            ..Default::default()
+        };
+        let dials = connector.dials.clone();
+
+        let strategy = RetryStrategy::constant(Duration::from_millis(1)).max_attempts(2);
+        let mut connector = RetryConnector::new(connector, strategy);
+
+        let err = futures_lite::future::block_on(connector.connect()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::ConnectionRefused);
+        // the first dial, and then 2 retries
+        assert_eq!(dials.load(Ordering::SeqCst), 3);
+    }
+}
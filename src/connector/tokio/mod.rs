@@ -20,3 +20,9 @@ mod openssl;
 
 #[cfg(all(feature = "tokio-openssl", feature = "openssl"))]
 pub use self::openssl::*;
+
+#[cfg(all(feature = "tokio-tungstenite", feature = "futures-util"))]
+mod ws;
+
+#[cfg(all(feature = "tokio-tungstenite", feature = "futures-util"))]
+pub use ws::*;
@@ -0,0 +1,282 @@
+use super::*;
+
+use std::{
+    io::{Error, ErrorKind},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+/// A `tokio` connector that uses WebSockets (via `tokio-tungstenite`).
+///
+/// This supports both `ws://` and `wss://` (TLS) addresses.
+///
+/// The crate provides the WebSocket addresses for Trovo in the root of this crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WsConnector {
+    url: String,
+}
+
+impl WsConnector {
+    /// Create a new [`tokio`](https://docs.rs/tokio/0.2/tokio/) WebSocket connector that connects to the ***default Trovo*** TLS address.
+    pub fn trovo() -> std::io::Result<Self> {
+        Self::custom(crate::TROVO_WS_ADDRESS_TLS)
+    }
+
+    /// Create a new [`tokio`](https://docs.rs/tokio/0.2/tokio/) WebSocket connector with a custom `ws://` or `wss://` url.
+    pub fn custom<U>(url: U) -> std::io::Result<Self>
+    where
+        U: Into<String>,
+    {
+        let url = url.into();
+        if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "url must start with ws:// or wss://",
+            ));
+        }
+        Ok(Self { url })
+    }
+}
+
+type Stream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+impl crate::connector::Connector for WsConnector {
+    type Output = async_dup::Mutex<WsStream<Stream>>;
+
+    fn connect(&mut self) -> BoxedFuture<std::io::Result<Self::Output>> {
+        let url = self.url.clone();
+        let fut = async move {
+            let (stream, _) = tokio_tungstenite::connect_async(&*url)
+                .await
+                .map_err(ws_error)?;
+            Ok(async_dup::Mutex::new(WsStream::new(stream)))
+        };
+        Box::pin(fut)
+    }
+}
+
+/// An `AsyncRead + AsyncWrite` adapter over a WebSocket.
+///
+/// Reads yield the text of each message, and each line written is sent as its own text message.
+#[derive(Debug)]
+pub struct WsStream<S> {
+    stream: S,
+    read: Vec<u8>,
+    pos: usize,
+    write: Vec<u8>,
+}
+
+impl<S> WsStream<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream,
+            read: Vec::new(),
+            pos: 0,
+            write: Vec::new(),
+        }
+    }
+
+    /// Consume this, returning the wrapped WebSocket
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> WsStream<S>
+where
+    S: futures_util::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    // send each complete line that has been written so far
+    fn poll_send_lines(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while let Some(end) = self.write.iter().position(|&c| c == b'\n') {
+            futures_lite::ready!(Pin::new(&mut self.stream).poll_ready(cx)).map_err(ws_error)?;
+
+            let line: Vec<u8> = self.write.drain(..=end).collect();
+            let line = std::str::from_utf8(&line)
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))?
+                .trim_end_matches(&['\r', '\n'][..]);
+
+            Pin::new(&mut self.stream)
+                .start_send(Message::Text(line.to_string()))
+                .map_err(ws_error)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S> futures_lite::AsyncRead for WsStream<S>
+where
+    S: futures_lite::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+
+        while this.pos == this.read.len() {
+            let data = match futures_lite::ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(Ok(Message::Text(data))) => data.into_bytes(),
+                Some(Ok(Message::Binary(data))) => data,
+                // tungstenite replies to pings for us
+                Some(Ok(Message::Ping(..))) | Some(Ok(Message::Pong(..))) => continue,
+                Some(Ok(Message::Close(..))) | None => return Poll::Ready(Ok(0)),
+                Some(Err(err)) => return Poll::Ready(Err(ws_error(err))),
+            };
+
+            this.read = data;
+            this.pos = 0;
+            // the decoder expects each message to end with a \r\n
+            if !this.read.is_empty() && !this.read.ends_with(b"\n") {
+                this.read.extend_from_slice(b"\r\n");
+            }
+        }
+
+        let n = std::cmp::min(buf.len(), this.read.len() - this.pos);
+        buf[..n].copy_from_slice(&this.read[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<S> futures_lite::AsyncWrite for WsStream<S>
+where
+    S: futures_util::Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        futures_lite::ready!(this.poll_send_lines(cx))?;
+        this.write.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        futures_lite::ready!(this.poll_send_lines(cx))?;
+        Pin::new(&mut this.stream).poll_flush(cx).map_err(ws_error)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        futures_lite::ready!(this.poll_send_lines(cx))?;
+        Pin::new(&mut this.stream).poll_close(cx).map_err(ws_error)
+    }
+}
+
+fn ws_error(err: tokio_tungstenite::tungstenite::Error) -> Error {
+    use tokio_tungstenite::tungstenite::Error as WsError;
+    match err {
+        WsError::Io(err) => err,
+        WsError::ConnectionClosed | WsError::AlreadyClosed => {
+            Error::new(ErrorKind::UnexpectedEof, err)
+        }
+        err => Error::new(ErrorKind::Other, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assert_connector_trait_is_fulfilled() {
+        use crate::connector::testing::*;
+        use crate::connector::Connector as C;
+
+        assert_connector::<WsConnector>();
+        assert_type_is_read_write::<<WsConnector as C>::Output>();
+        assert_obj_is_sane(WsConnector::trovo().unwrap());
+    }
+
+    #[test]
+    fn custom_url() {
+        assert!(WsConnector::custom(crate::TROVO_WS_ADDRESS).is_ok());
+        assert!(WsConnector::custom(crate::TROVO_WS_ADDRESS_TLS).is_ok());
+        let err = WsConnector::custom(crate::TROVO_IRC_ADDRESS).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn frames_lines() {
+        futures_lite::future::block_on(async move {
+            use futures_lite::{AsyncReadExt as _, AsyncWriteExt as _};
+
+            // a fake websocket: reads pop from `incoming`, sent messages go to `sent`
+            #[derive(Default)]
+            struct Fake {
+                incoming: std::collections::VecDeque<Message>,
+                sent: Vec<Message>,
+            }
+
+            impl futures_lite::Stream for Fake {
+                type Item = Result<Message, tokio_tungstenite::tungstenite::Error>;
+                fn poll_next(
+                    self: Pin<&mut Self>,
+                    _: &mut Context<'_>,
+                ) -> Poll<Option<Self::Item>> {
+                    Poll::Ready(self.get_mut().incoming.pop_front().map(Ok))
+                }
+            }
+
+            impl futures_util::Sink<Message> for Fake {
+                type Error = tokio_tungstenite::tungstenite::Error;
+                fn poll_ready(
+                    self: Pin<&mut Self>,
+                    _: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    Poll::Ready(Ok(()))
+                }
+                fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+                    self.get_mut().sent.push(item);
+                    Ok(())
+                }
+                fn poll_flush(
+                    self: Pin<&mut Self>,
+                    _: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    Poll::Ready(Ok(()))
+                }
+                fn poll_close(
+                    self: Pin<&mut Self>,
+                    _: &mut Context<'_>,
+                ) -> Poll<Result<(), Self::Error>> {
+                    Poll::Ready(Ok(()))
+                }
+            }
+
+            let mut fake = Fake::default();
+            fake.incoming.extend(vec![
+                Message::Text("PING :1234\r\n:test!test@test JOIN #museun\r\n".into()),
+                Message::Ping(vec![]),
+                Message::Text(":tmi.trovo.tv 376 museun :>".into()),
+            ]);
+
+            let mut stream = WsStream::new(fake);
+
+            let mut out = String::new();
+            stream.read_to_string(&mut out).await.unwrap();
+            assert_eq!(
+                out,
+                "PING :1234\r\n:test!test@test JOIN #museun\r\n:tmi.trovo.tv 376 museun :>\r\n"
+            );
+
+            stream.write_all(b"PONG :1234\r\nJOIN #mu").await.unwrap();
+            stream.write_all(b"seun\r\n").await.unwrap();
+            stream.flush().await.unwrap();
+
+            assert_eq!(
+                stream.into_inner().sent,
+                vec![
+                    Message::Text("PONG :1234".into()),
+                    Message::Text("JOIN #museun".into()),
+                ]
+            );
+        });
+    }
+}
@@ -1,5 +1,6 @@
 cfg_async! {
-use crate::{irc::IrcMessage, IntoOwned,DecodeError};
+use crate::{irc::IrcMessage, IntoOwned,DecodeError, decoder::Delimiter};
+use super::sync::{filter_tags, TagFilter, MAX_MESSAGE_SIZE};
 
 use std::{
     future::Future,
@@ -7,7 +8,9 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures_lite::{io::BufReader as AsyncBufReader, AsyncBufReadExt, AsyncRead, Stream};
+use futures_lite::{
+    io::BufReader as AsyncBufReader, AsyncBufReadExt, AsyncRead, AsyncReadExt, Stream,
+};
 
 /// A decoder over [futures_lite::AsyncRead] that produces [IrcMessage]s
 ///
@@ -17,6 +20,13 @@ use futures_lite::{io::BufReader as AsyncBufReader, AsyncBufReadExt, AsyncRead,
 pub struct AsyncDecoder<R> {
     reader: AsyncBufReader<R>,
     buf: Vec<u8>,
+    delimiter: Delimiter,
+    // whether `buf` has the start of a message from a read that was cancelled
+    partial: bool,
+    // whether the rest of a message that was too long is still to be skipped
+    discard: bool,
+    max_message_size: usize,
+    tag_filter: Option<TagFilter>,
 }
 
 impl<R> std::fmt::Debug for AsyncDecoder<R> {
@@ -28,29 +38,77 @@ impl<R> std::fmt::Debug for AsyncDecoder<R> {
 impl<R: AsyncRead + Send + Sync + Unpin> AsyncDecoder<R> {
     /// Create a new AsyncDecoder from this [futures_lite::AsyncRead] instance
     pub fn new(reader: R) -> Self {
+        Self::with_delimiter(reader, Delimiter::default())
+    }
+
+    /// Create a new AsyncDecoder from this [futures_lite::AsyncRead] instance, which ends messages with this [Delimiter]
+    pub fn with_delimiter(reader: R, delimiter: Delimiter) -> Self {
         Self {
             reader: AsyncBufReader::new(reader),
             buf: Vec::with_capacity(1024),
+            delimiter,
+            partial: false,
+            discard: false,
+            max_message_size: MAX_MESSAGE_SIZE,
+            tag_filter: None,
         }
     }
 
+    /// Create a new AsyncDecoder from this [futures_lite::AsyncRead] instance, with a read buffer of `capacity` bytes
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader: AsyncBufReader::with_capacity(capacity, reader),
+            buf: Vec::with_capacity(1024),
+            delimiter: Delimiter::default(),
+            partial: false,
+            discard: false,
+            max_message_size: MAX_MESSAGE_SIZE,
+            tag_filter: None,
+        }
+    }
+
+    /// Only keep the tags whose key passes this `filter`, e.g. to drop `user-id` and `display-name` before logging.
+    ///
+    /// The tags are removed from the raw message as well.
+    pub fn with_tag_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.tag_filter.replace(Box::new(filter));
+        self
+    }
+
+    /// The maximum size of a message, in bytes, including its line ending.
+    ///
+    /// This defaults to `8 KiB`. Reading a longer message returns a [DecodeError::MessageTooLong],
+    /// and the rest of it is skipped on the next read.
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
     /// Read the next message.
     ///
     /// This returns a borrowed [IrcMessage] which is valid until the next AsyncDecoder call is made.
     ///
     /// If you just want an owned one, use the [AsyncDecoder] as an stream. e.g. dec.next().
+    ///
+    /// This is cancel-safe: if the future is dropped part way through a message,
+    /// the next call will finish reading it.
     pub async fn read_message(&mut self) -> Result<IrcMessage<'_>, DecodeError> {
-        self.buf.clear();
-        let n = self
-            .reader
-            .read_until(b'\n', &mut self.buf)
-            .await
-            .map_err(DecodeError::Io)?;
-        if n == 0 {
-            return Err(DecodeError::Eof);
+        // read_until keeps what it read in `buf`, even if this future is dropped
+        if !std::mem::replace(&mut self.partial, true) {
+            self.buf.clear();
+        }
+        let result = self.read_line().await;
+        self.partial = false;
+        result?;
+
+        if let Some(filter) = &self.tag_filter {
+            filter_tags(&mut self.buf, filter);
         }
 
-        let str = std::str::from_utf8(&self.buf[..n]).map_err(DecodeError::InvalidUtf8)?;
+        let str = std::str::from_utf8(&self.buf).map_err(DecodeError::InvalidUtf8)?;
         log::trace!("< {}", str.escape_debug());
 
         // this should only ever parse 1 message
@@ -59,6 +117,47 @@ impl<R: AsyncRead + Send + Sync + Unpin> AsyncDecoder<R> {
             .map(|(_, msg)| msg)
     }
 
+    async fn read_line(&mut self) -> Result<(), DecodeError> {
+        loop {
+            // read one byte past the maximum, so a message that is too long can be told apart
+            let limit = (self.max_message_size + 1).saturating_sub(self.buf.len()) as u64;
+            let n = (&mut self.reader)
+                .take(limit)
+                .read_until(b'\n', &mut self.buf)
+                .await
+                .map_err(DecodeError::Io)?;
+            if n == 0 {
+                if self.buf.is_empty() || self.discard {
+                    return Err(DecodeError::Eof);
+                }
+                return Ok(());
+            }
+
+            let complete = self.delimiter.complete_line(&mut self.buf);
+            if self.discard {
+                self.discard = !complete;
+                self.buf.clear();
+                continue;
+            }
+            if self.buf.len() > self.max_message_size {
+                self.discard = !complete;
+                return Err(DecodeError::MessageTooLong {
+                    max: self.max_message_size,
+                });
+            }
+            if complete {
+                return self.delimiter.check_line(&self.buf);
+            }
+        }
+    }
+
+    /// The last line read by [AsyncDecoder::read_message], including its line ending.
+    ///
+    /// Invalid utf-8 is replaced, so this can be used for diagnostics after a decode error.
+    pub(crate) fn last_line(&self) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(&self.buf)
+    }
+
     /// Consume the decoder returning the inner Reader
     pub fn into_inner(self) -> R {
         self.reader.into_inner()
@@ -121,5 +220,86 @@ mod tests {
 
         futures_lite::future::block_on(fut);
     }
+
+    #[test]
+    fn read_async_tag_filter() {
+        let data = "@display-name=museun;user-id=23196011 :museun!museun@museun PRIVMSG #museun :hello\r\n";
+
+        futures_lite::future::block_on(async move {
+            let mut dec = AsyncDecoder::new(data.as_bytes()).with_tag_filter(|key| key != "user-id");
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(
+                msg.get_raw(),
+                "@display-name=museun :museun!museun@museun PRIVMSG #museun :hello\r\n"
+            );
+        });
+    }
+
+    #[test]
+    fn read_async_lenient_delimiter() {
+        let fut = async move {
+            let data = b"PING :1234\nPING :5678\r\n".to_vec();
+            let reader = futures_lite::io::Cursor::new(data);
+            let mut dec = AsyncDecoder::with_delimiter(reader, Delimiter::Lenient);
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :1234\r\n");
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :5678\r\n");
+
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::Eof
+            ))
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_strict_delimiter() {
+        let fut = async move {
+            let data = b"PING :1234\nPING :5678\r\n".to_vec();
+            let mut dec = AsyncDecoder::new(futures_lite::io::Cursor::new(data));
+
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::BareNewline
+            ));
+
+            let msg = dec.read_message().await.unwrap();
+            assert_eq!(msg.get_raw(), "PING :5678\r\n");
+        };
+
+        futures_lite::future::block_on(fut);
+    }
+
+    #[test]
+    fn read_async_max_message_size() {
+        let long = format!("PRIVMSG #museun :{}\r\n", "a".repeat(10 * 1024));
+        let short = format!("PRIVMSG #museun :{}\r\n", "a".repeat(4 * 1024));
+        let input = format!("{}{}", long, short);
+
+        futures_lite::future::block_on(async move {
+            let mut dec = AsyncDecoder::new(input.as_bytes());
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::MessageTooLong {
+                    max: MAX_MESSAGE_SIZE
+                }
+            ));
+            // the rest of the long message is skipped
+            assert_eq!(dec.read_message().await.unwrap().get_raw(), short);
+            assert!(matches!(
+                dec.read_message().await.unwrap_err(),
+                DecodeError::Eof
+            ));
+
+            let mut dec = AsyncDecoder::new(input.as_bytes()).with_max_message_size(16 * 1024);
+            assert_eq!(dec.read_message().await.unwrap().get_raw(), long);
+            assert_eq!(dec.read_message().await.unwrap().get_raw(), short);
+        });
+    }
 }
 }
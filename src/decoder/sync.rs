@@ -1,5 +1,9 @@
-use crate::{IntoOwned as _, IrcMessage, MessageError};
-use std::io::{BufRead, BufReader, Read};
+use crate::{IntoOwned as _, IrcMessage, MaybeOwned, MessageError};
+use std::{
+    io::{BufRead, BufReader, Read},
+    ops::Range,
+    time::Duration,
+};
 
 /// An error produced by a Decoder.
 #[derive(Debug)]
@@ -13,6 +17,28 @@ pub enum DecodeError {
     ParseError(MessageError),
     /// EOF was reached
     Eof,
+    /// A complete message wasn't read in time
+    TimedOut,
+    /// A message was longer than the decoder's maximum message size
+    MessageTooLong {
+        /// The maximum message size, in bytes
+        max: usize,
+    },
+    /// A message ended with a bare `\n` when the [Delimiter] only allows `\r\n`
+    BareNewline,
+}
+
+impl DecodeError {
+    /// Whether this is because the message wasn't complete yet.
+    ///
+    /// When decoding from a buffer that is still being filled, this means more data is needed
+    /// rather than the message being malformed.
+    pub fn is_incomplete(&self) -> bool {
+        matches!(
+            self,
+            Self::ParseError(MessageError::IncompleteMessage { .. })
+        )
+    }
 }
 
 impl std::fmt::Display for DecodeError {
@@ -22,6 +48,9 @@ impl std::fmt::Display for DecodeError {
             Self::InvalidUtf8(err) => write!(f, "invalid utf8: {}", err),
             Self::ParseError(err) => write!(f, "parse error: {}", err),
             Self::Eof => f.write_str("end of file reached"),
+            Self::TimedOut => f.write_str("timed out waiting for a message"),
+            Self::MessageTooLong { max } => write!(f, "message is longer than {} bytes", max),
+            Self::BareNewline => f.write_str("message ended with a bare \\n instead of \\r\\n"),
         }
     }
 }
@@ -37,14 +66,164 @@ impl std::error::Error for DecodeError {
     }
 }
 
+/// The default maximum size of a message, in bytes, including its line ending
+pub(crate) const MAX_MESSAGE_SIZE: usize = 8 * 1024;
+
+/// The line ending a decoder accepts as the end of a message
+#[non_exhaustive]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Delimiter {
+    /// Only `\r\n`, as Trovo sends. A bare `\n` is a [DecodeError::BareNewline].
+    #[default]
+    CrLf,
+    /// Either `\r\n` or a bare `\n`. A bare `\n` is normalized to `\r\n`.
+    Lenient,
+}
+
+impl Delimiter {
+    // whether `buf` is a complete line, normalizing its line ending if needed.
+    // a bare `\n` still ends the line with `CrLf`, but it isn't a valid message
+    pub(crate) fn complete_line(self, buf: &mut Vec<u8>) -> bool {
+        if buf.ends_with(b"\r\n") {
+            return true;
+        }
+        if !buf.ends_with(b"\n") {
+            return false;
+        }
+        if let Self::Lenient = self {
+            buf.insert(buf.len() - 1, b'\r');
+        }
+        true
+    }
+
+    // whether the complete line in `buf` ends the way this delimiter allows
+    pub(crate) fn check_line(self, buf: &[u8]) -> Result<(), DecodeError> {
+        if buf.ends_with(b"\r\n") {
+            return Ok(());
+        }
+        Err(DecodeError::BareNewline)
+    }
+}
+
+/// A predicate on a tag's key, deciding whether a decoder keeps that tag
+pub(crate) type TagFilter = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+// drop the tags from the message in `buf` that `filter` rejects
+pub(crate) fn filter_tags(buf: &mut Vec<u8>, filter: &dyn Fn(&str) -> bool) {
+    if !buf.starts_with(b"@") {
+        return;
+    }
+    let end = match buf.iter().position(|&c| c == b' ') {
+        Some(end) => end,
+        None => return,
+    };
+
+    let mut tags = Vec::with_capacity(end);
+    for tag in buf[1..end].split(|&c| c == b';') {
+        let key = tag.split(|&c| c == b'=').next().unwrap_or_default();
+        // keep anything that isn't utf-8, so the parser reports it
+        if std::str::from_utf8(key).map_or(true, filter) {
+            if !tags.is_empty() {
+                tags.push(b';');
+            }
+            tags.extend_from_slice(tag);
+        }
+    }
+
+    let rest = buf.split_off(end + 1);
+    buf.clear();
+    if !tags.is_empty() {
+        buf.push(b'@');
+        buf.extend_from_slice(&tags);
+        buf.push(b' ');
+    }
+    buf.extend_from_slice(&rest);
+}
+
 /// A decoder over [std::io::Read] that produces [IrcMessage]s
 ///
 /// This will return an [DecodeError::Eof] when reading manually.
 ///
 /// When reading it as a iterator, `Eof` will signal the end of the iterator (e.g. `None`)
 pub struct Decoder<R> {
-    reader: BufReader<R>,
+    reader: Source<R>,
     buf: Vec<u8>,
+    delimiter: Delimiter,
+    // whether `buf` has the start of a message from a read that timed out
+    partial: bool,
+    // whether the rest of a message that was too long is still to be skipped
+    discard: bool,
+    max_message_size: usize,
+    tag_filter: Option<TagFilter>,
+}
+
+enum Source<R> {
+    Buffered(BufReader<R>),
+    // the caller already buffers this, so read lines from it directly
+    Direct(R, ReadUntil<R>),
+}
+
+// reads a line of at most `limit` bytes from a `BufRead`. `Decoder<R>` only knows
+// `R: Read`, so `Decoder::from_bufread` picks this while it still knows `R: BufRead`
+type ReadUntil<R> = fn(&mut R, &mut Vec<u8>, u64) -> std::io::Result<usize>;
+
+impl<R> Source<R> {
+    fn get_ref(&self) -> &R {
+        match self {
+            Self::Buffered(reader) => reader.get_ref(),
+            Self::Direct(reader, ..) => reader,
+        }
+    }
+}
+
+/// A source whose reads can time out, for [Decoder::read_message_timeout()]
+pub trait ReadTimeout {
+    /// How long a read can block for. `None` blocks until there is data.
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>>;
+
+    /// Set how long a read can block for. `None` blocks until there is data.
+    fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()>;
+}
+
+impl ReadTimeout for std::net::TcpStream {
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+        std::net::TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()> {
+        std::net::TcpStream::set_read_timeout(self, dur)
+    }
+}
+
+#[cfg(unix)]
+impl ReadTimeout for std::os::unix::net::UnixStream {
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+        std::os::unix::net::UnixStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()> {
+        std::os::unix::net::UnixStream::set_read_timeout(self, dur)
+    }
+}
+
+impl<T: ReadTimeout + ?Sized> ReadTimeout for &T {
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+        (**self).read_timeout()
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()> {
+        (**self).set_read_timeout(dur)
+    }
+}
+
+impl<T: ReadTimeout + ?Sized> ReadTimeout for &mut T {
+    fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+        (**self).read_timeout()
+    }
+
+    fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()> {
+        (**self).set_read_timeout(dur)
+    }
 }
 
 impl<R> std::fmt::Debug for Decoder<R> {
@@ -59,28 +238,175 @@ where
 {
     /// Create a new Decoder from this [std::io::Read] instance
     pub fn new(reader: R) -> Self {
+        Self::with_delimiter(reader, Delimiter::default())
+    }
+
+    /// Create a new Decoder from this [std::io::Read] instance, which ends messages with this [Delimiter]
+    pub fn with_delimiter(reader: R, delimiter: Delimiter) -> Self {
         Self {
-            reader: BufReader::new(reader),
+            reader: Source::Buffered(BufReader::new(reader)),
             buf: Vec::with_capacity(1024),
+            delimiter,
+            partial: false,
+            discard: false,
+            max_message_size: MAX_MESSAGE_SIZE,
+            tag_filter: None,
         }
     }
 
+    /// Create a new Decoder from this [std::io::Read] instance, with a read buffer of `capacity` bytes
+    ///
+    /// A larger buffer means fewer reads, e.g. when decoding a large captured log file.
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader: Source::Buffered(BufReader::with_capacity(capacity, reader)),
+            buf: Vec::with_capacity(1024),
+            delimiter: Delimiter::default(),
+            partial: false,
+            discard: false,
+            max_message_size: MAX_MESSAGE_SIZE,
+            tag_filter: None,
+        }
+    }
+
+    /// Only keep the tags whose key passes this `filter`, e.g. to drop `user-id` and `display-name` before logging.
+    ///
+    /// The tags are removed from the raw message as well.
+    ///
+    /// ```
+    /// let input = "@display-name=museun;user-id=23196011 :museun!museun@museun PRIVMSG #museun :hello\r\n";
+    /// let mut decoder = trovochat::Decoder::new(input.as_bytes())
+    ///     .with_tag_filter(|key| key != "user-id");
+    ///
+    /// let msg = decoder.read_message().unwrap();
+    /// assert_eq!(msg.get_raw(), "@display-name=museun :museun!museun@museun PRIVMSG #museun :hello\r\n");
+    /// ```
+    pub fn with_tag_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.tag_filter.replace(Box::new(filter));
+        self
+    }
+
+    /// The maximum size of a message, in bytes, including its line ending.
+    ///
+    /// This defaults to `8 KiB`. Reading a longer message returns a [DecodeError::MessageTooLong],
+    /// and the rest of it is skipped on the next read.
+    ///
+    /// ```
+    /// let input = format!("PRIVMSG #museun :{}\r\nPING :1234\r\n", "a".repeat(64));
+    /// let mut decoder = trovochat::Decoder::new(input.as_bytes()).with_max_message_size(32);
+    ///
+    /// let err = decoder.read_message().unwrap_err();
+    /// assert!(matches!(err, trovochat::DecodeError::MessageTooLong { max: 32 }));
+    ///
+    /// let msg = decoder.read_message().unwrap();
+    /// assert_eq!(msg.get_raw(), "PING :1234\r\n");
+    /// ```
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
     /// Read the next message.
     ///
     /// This returns a borrowed [IrcMessage] which is valid until the next Decoder call is made.
     ///
     /// If you just want an owned one, use the [Decoder] as an iterator. e.g. dec.next().
     pub fn read_message(&mut self) -> Result<IrcMessage<'_>, DecodeError> {
-        self.buf.clear();
-        let n = self
-            .reader
-            .read_until(b'\n', &mut self.buf)
+        self.read_line(false)?;
+        self.parse_line()
+    }
+
+    /// Read the next message, waiting up to `dur` for each read from the source.
+    ///
+    /// This sets the source's [read timeout](ReadTimeout) to `dur` while reading,
+    /// and puts the previous one back afterwards. If the read times out before a complete
+    /// message was read, this returns a [DecodeError::TimedOut].
+    ///
+    /// Any partially read message is kept, so the next read will complete it.
+    ///
+    /// A [TcpStream] doesn't allow a zero `dur`, that returns a [DecodeError::Io].
+    ///
+    /// [TcpStream]: std::net::TcpStream
+    pub fn read_message_timeout(&mut self, dur: Duration) -> Result<IrcMessage<'_>, DecodeError>
+    where
+        R: ReadTimeout,
+    {
+        let source = self.reader.get_ref();
+        let previous = source.read_timeout().map_err(DecodeError::Io)?;
+        source
+            .set_read_timeout(Some(dur))
             .map_err(DecodeError::Io)?;
-        if n == 0 {
-            return Err(DecodeError::Eof);
+
+        let read = self.read_line(true);
+        let restore = self.reader.get_ref().set_read_timeout(previous);
+        read?;
+        restore.map_err(DecodeError::Io)?;
+
+        self.parse_line()
+    }
+
+    fn read_line(&mut self, timeout: bool) -> Result<(), DecodeError> {
+        use std::io::ErrorKind;
+
+        if !std::mem::take(&mut self.partial) {
+            self.buf.clear();
+        }
+
+        loop {
+            // read one byte past the maximum, so a message that is too long can be told apart
+            let limit = (self.max_message_size + 1).saturating_sub(self.buf.len()) as u64;
+            let read = match &mut self.reader {
+                Source::Buffered(reader) => reader.take(limit).read_until(b'\n', &mut self.buf),
+                Source::Direct(reader, read_until) => read_until(reader, &mut self.buf, limit),
+            };
+
+            let n = match read {
+                Ok(n) => n,
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    // read_until keeps the bytes it read before the error
+                    self.partial = true;
+                    if timeout {
+                        return Err(DecodeError::TimedOut);
+                    }
+                    return Err(DecodeError::Io(err));
+                }
+                Err(err) => return Err(DecodeError::Io(err)),
+            };
+
+            if n == 0 {
+                if self.buf.is_empty() || self.discard {
+                    return Err(DecodeError::Eof);
+                }
+                return Ok(());
+            }
+
+            let complete = self.delimiter.complete_line(&mut self.buf);
+            if self.discard {
+                self.discard = !complete;
+                self.buf.clear();
+                continue;
+            }
+            if self.buf.len() > self.max_message_size {
+                self.discard = !complete;
+                return Err(DecodeError::MessageTooLong {
+                    max: self.max_message_size,
+                });
+            }
+            if complete {
+                return self.delimiter.check_line(&self.buf);
+            }
+        }
+    }
+
+    fn parse_line(&mut self) -> Result<IrcMessage<'_>, DecodeError> {
+        if let Some(filter) = &self.tag_filter {
+            filter_tags(&mut self.buf, filter);
         }
 
-        let str = std::str::from_utf8(&self.buf[..n]).map_err(DecodeError::InvalidUtf8)?;
+        let str = std::str::from_utf8(&self.buf).map_err(DecodeError::InvalidUtf8)?;
 
         // this should only ever parse 1 message
         crate::irc::parse_one(str)
@@ -97,7 +423,39 @@ where
 
     /// Consume the decoder returning the inner Reader
     pub fn into_inner(self) -> R {
-        self.reader.into_inner()
+        match self.reader {
+            Source::Buffered(reader) => reader.into_inner(),
+            Source::Direct(reader, ..) => reader,
+        }
+    }
+}
+
+impl<R> Decoder<R>
+where
+    R: BufRead,
+{
+    /// Create a new Decoder from this [std::io::BufRead] instance
+    ///
+    /// Unlike [Decoder::new()], this doesn't wrap the reader in another buffer.
+    /// Lines are read directly from it, so anything it hasn't read yet is still
+    /// available from it (e.g. after [Decoder::into_inner()]).
+    pub fn from_bufread(reader: R) -> Self {
+        Self::from_bufread_with_delimiter(reader, Delimiter::default())
+    }
+
+    /// Create a new Decoder from this [std::io::BufRead] instance, which ends messages with this [Delimiter]
+    pub fn from_bufread_with_delimiter(reader: R, delimiter: Delimiter) -> Self {
+        Self {
+            reader: Source::Direct(reader, |reader, buf, limit| {
+                reader.take(limit).read_until(b'\n', buf)
+            }),
+            buf: Vec::with_capacity(1024),
+            delimiter,
+            partial: false,
+            discard: false,
+            max_message_size: MAX_MESSAGE_SIZE,
+            tag_filter: None,
+        }
     }
 }
 
@@ -114,6 +472,50 @@ impl<R: Read> Iterator for Decoder<R> {
     }
 }
 
+/// Decode all of the messages in `input`, along with the byte range each one occupies in it.
+///
+/// The range includes the trailing `\r\n`, so `&input[range]` is the raw message.
+///
+/// ```
+/// let input = "PING :123\r\nPONG :123\r\n";
+/// let mut iter = trovochat::decoder::decode_indexed(input);
+///
+/// let (range, msg) = iter.next().unwrap().unwrap();
+/// assert_eq!(range, 0..11);
+/// assert_eq!(msg.get_command(), "PING");
+///
+/// let (range, msg) = iter.next().unwrap().unwrap();
+/// assert_eq!(range, 11..22);
+/// assert_eq!(msg.get_command(), "PONG");
+/// ```
+pub fn decode_indexed(
+    input: &str,
+) -> impl Iterator<Item = Result<(Range<usize>, IrcMessage<'_>), DecodeError>> + '_ {
+    const CRLF: &str = "\r\n";
+
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        if pos == input.len() {
+            return None;
+        }
+
+        let end = match input[pos..].find(CRLF) {
+            Some(index) => pos + index + CRLF.len(),
+            None => {
+                let err = MessageError::IncompleteMessage { pos };
+                pos = input.len();
+                return Some(Err(DecodeError::ParseError(err)));
+            }
+        };
+
+        let start = std::mem::replace(&mut pos, end);
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(&input[start..end]))
+            .map(|msg| (start..end, msg))
+            .map_err(DecodeError::ParseError);
+        Some(msg)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +541,251 @@ mod tests {
         }
         assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
     }
+
+    #[test]
+    fn read_tag_filter() {
+        let data = "@badges=;display-name=museun;user-id=23196011 :museun!museun@museun PRIVMSG #museun :hello world\r\n\
+                    @user-id=23196011 :museun!museun@museun PRIVMSG #museun :only an id\r\n\
+                    PING :1234\r\n";
+
+        let mut dec = Decoder::new(data.as_bytes()).with_tag_filter(|key| key != "user-id");
+
+        let msg = dec.read_message().unwrap();
+        let keys = msg.tags_iter().map(|(key, _)| key).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["badges", "display-name"]);
+        assert_eq!(msg.get_data(), Some("hello world"));
+        assert_eq!(
+            msg.get_raw(),
+            "@badges=;display-name=museun :museun!museun@museun PRIVMSG #museun :hello world\r\n"
+        );
+
+        // all of the tags were removed
+        let msg = dec.read_message().unwrap();
+        assert!(msg.get_tags().is_none());
+        assert_eq!(
+            msg.get_raw(),
+            ":museun!museun@museun PRIVMSG #museun :only an id\r\n"
+        );
+
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PING :1234\r\n");
+    }
+
+    #[test]
+    fn read_bufread() {
+        let data = b"PING :1234\r\n:test!test@test PRIVMSG #museun :hello\r\nPONG :5678\r\n";
+
+        let expected = Decoder::new(&data[..])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let v = Decoder::from_bufread(&data[..])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(v, expected);
+
+        // only the first line was taken from the reader
+        let mut reader = std::io::Cursor::new(data.to_vec());
+        let mut dec = Decoder::from_bufread(&mut reader);
+        assert_eq!(dec.read_message().unwrap().get_raw(), "PING :1234\r\n");
+        assert_eq!(reader.position(), 12);
+
+        let mut dec = Decoder::from_bufread(&mut reader);
+        for _ in 0..2 {
+            dec.read_message().unwrap();
+        }
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_with_capacity() {
+        let data = b"PING :1234\r\n:test!test@test PRIVMSG #museun :hello\r\nPONG :5678\r\n";
+
+        let expected = Decoder::new(&data[..])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        // smaller and larger than a message
+        for &capacity in &[4, 64 * 1024] {
+            let v = Decoder::with_capacity(&data[..], capacity)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn read_lenient_delimiter() {
+        let data = b"PING :1234\nPING :5678\r\n".to_vec();
+        let mut dec = Decoder::with_delimiter(std::io::Cursor::new(data), Delimiter::Lenient);
+
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PING :1234\r\n");
+        assert_eq!(msg.get_data(), Some("1234"));
+
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PING :5678\r\n");
+
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_strict_delimiter() {
+        // without a line ending, the message never completes
+        let data = b"PING :1234".to_vec();
+        let mut dec = Decoder::new(std::io::Cursor::new(data));
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::ParseError(MessageError::IncompleteMessage { .. })
+        ));
+
+        // a bare \n is an error, rather than being merged with the next line
+        let data = b"PING :1234\nPONG :5678\r\n".to_vec();
+        let mut dec = Decoder::new(std::io::Cursor::new(data));
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::BareNewline
+        ));
+
+        // and it keeps going with the next line
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PONG :5678\r\n");
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof))
+    }
+
+    #[test]
+    fn read_timeout_keeps_partial() {
+        use std::cell::Cell;
+        use std::collections::VecDeque;
+        use std::io::ErrorKind;
+
+        // a reader that returns WouldBlock for each `None`
+        struct Chunks(VecDeque<Option<&'static [u8]>>, Cell<Option<Duration>>);
+        impl Read for Chunks {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.pop_front() {
+                    Some(Some(chunk)) => {
+                        buf[..chunk.len()].copy_from_slice(chunk);
+                        Ok(chunk.len())
+                    }
+                    Some(None) => Err(ErrorKind::WouldBlock.into()),
+                    None => Ok(0),
+                }
+            }
+        }
+        impl ReadTimeout for Chunks {
+            fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+                Ok(self.1.get())
+            }
+            fn set_read_timeout(&self, dur: Option<Duration>) -> std::io::Result<()> {
+                self.1.set(dur);
+                Ok(())
+            }
+        }
+
+        let chunks = vec![Some(&b"PING :12"[..]), None, Some(&b"34\r\n"[..])];
+        let mut dec = Decoder::new(Chunks(chunks.into_iter().collect(), Cell::new(None)));
+
+        assert!(matches!(
+            dec.read_message_timeout(Duration::from_millis(10))
+                .unwrap_err(),
+            DecodeError::TimedOut
+        ));
+        // the previous timeout was put back
+        assert_eq!(dec.into_inner().1.get(), None);
+
+        let chunks = vec![Some(&b"PING :12"[..]), None, Some(&b"34\r\n"[..])];
+        let mut dec = Decoder::new(Chunks(chunks.into_iter().collect(), Cell::new(None)));
+        assert!(dec.read_message_timeout(Duration::from_millis(10)).is_err());
+
+        let msg = dec.read_message().unwrap();
+        assert_eq!(msg.get_raw(), "PING :1234\r\n");
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof));
+    }
+
+    #[test]
+    fn read_timeout_tcp() {
+        use std::io::Write as _;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        let mut dec = Decoder::new(&server);
+        client.write_all(b"PING :12").unwrap();
+        assert!(matches!(
+            dec.read_message_timeout(Duration::from_millis(50))
+                .unwrap_err(),
+            DecodeError::TimedOut
+        ));
+        assert_eq!(server.read_timeout().unwrap(), None);
+
+        client.write_all(b"34\r\n").unwrap();
+        let msg = dec.read_message_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(msg.get_raw(), "PING :1234\r\n");
+    }
+
+    #[test]
+    fn decode_indexed_offsets() {
+        let input = ":tmi.trovo.tv PING :1234\r\n:museun!museun@museun PRIVMSG #museun :hello\r\n";
+
+        let msgs = decode_indexed(input)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(msgs.len(), 2);
+
+        assert_eq!(msgs[0].0, 0..26);
+        assert_eq!(msgs[0].1.get_command(), "PING");
+        assert_eq!(&input[msgs[0].0.clone()], ":tmi.trovo.tv PING :1234\r\n");
+
+        assert_eq!(msgs[1].0, 26..input.len());
+        assert_eq!(msgs[1].1.get_command(), "PRIVMSG");
+        assert_eq!(msgs[1].1.get_raw(), &input[msgs[1].0.clone()]);
+    }
+
+    #[test]
+    fn decode_indexed_incomplete() {
+        let input = "PING :1234\r\nPING :5678";
+        let mut iter = decode_indexed(input);
+
+        let (range, _) = iter.next().unwrap().unwrap();
+        assert_eq!(range, 0..12);
+
+        assert!(matches!(
+            iter.next().unwrap().unwrap_err(),
+            DecodeError::ParseError(MessageError::IncompleteMessage { pos: 12 })
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn is_incomplete() {
+        let err = decode_indexed("PING :1234").next().unwrap().unwrap_err();
+        assert!(err.is_incomplete());
+
+        let err = decode_indexed("\r\n").next().unwrap().unwrap_err();
+        assert!(!err.is_incomplete());
+
+        assert!(!DecodeError::Eof.is_incomplete());
+    }
+
+    #[test]
+    fn max_message_size() {
+        let long = format!("PRIVMSG #museun :{}\r\n", "a".repeat(10 * 1024));
+        let short = format!("PRIVMSG #museun :{}\r\n", "a".repeat(4 * 1024));
+        let input = format!("{}{}", long, short);
+
+        let mut dec = Decoder::new(input.as_bytes());
+        assert!(matches!(
+            dec.read_message().unwrap_err(),
+            DecodeError::MessageTooLong {
+                max: MAX_MESSAGE_SIZE
+            }
+        ));
+        // the rest of the long message is skipped
+        assert_eq!(dec.read_message().unwrap().get_raw(), short);
+        assert!(matches!(dec.read_message().unwrap_err(), DecodeError::Eof));
+
+        let mut dec = Decoder::from_bufread(input.as_bytes()).with_max_message_size(16 * 1024);
+        assert_eq!(dec.read_message().unwrap().get_raw(), long);
+        assert_eq!(dec.read_message().unwrap().get_raw(), short);
+    }
 }
@@ -10,6 +10,19 @@ pub trait Encodable {
     fn encode<W>(&self, buf: &mut W) -> IoResult<()>
     where
         W: Write + ?Sized;
+
+    /// Encode this message to the provided [std::io::Write] implementation, without the trailing `\r\n`
+    ///
+    /// This is useful for transports that handle the message framing themselves, such as WebSockets.
+    fn encode_without_terminator<W>(&self, buf: &mut W) -> IoResult<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut data = Vec::new();
+        self.encode(&mut data)?;
+        let data = data.strip_suffix(b"\r\n").unwrap_or(&data);
+        buf.write_all(data)
+    }
 }
 
 impl<T> Encodable for &T
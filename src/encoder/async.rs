@@ -112,6 +112,36 @@ where
         self.pos = 0;
         Ok(())
     }
+
+    /// Encode each of these [Encodable](crate::Encodable) messages to the writer.
+    ///
+    /// The messages are buffered and written with a single flush at the end.
+    ///
+    /// If a message fails to encode, its error is returned and nothing is written.
+    /// The messages before it are kept in the buffer and written by the next encode.
+    pub async fn encode_many<I, M>(&mut self, msgs: I) -> IoResult<()>
+    where
+        I: IntoIterator<Item = M>,
+        M: crate::Encodable,
+        W: Unpin,
+    {
+        for msg in msgs {
+            let len = self.data.len();
+            if let Err(err) = msg.encode(&mut self.data) {
+                // but don't keep half of a message
+                self.data.truncate(len);
+                return Err(err);
+            }
+        }
+
+        let data = &self.data[self.pos..];
+        self.writer.write_all(data).await?;
+        self.writer.flush().await?;
+
+        self.data.clear();
+        self.pos = 0;
+        Ok(())
+    }
 }
 
 impl<W> AsyncWrite for AsyncEncoder<W>
@@ -165,5 +195,48 @@ mod tests {
         };
         futures_lite::future::block_on(fut);
     }
+
+    #[test]
+    fn encode_many_async() {
+        // a message that writes part of itself, then fails when it is `None`
+        struct Maybe(Option<&'static str>);
+        impl crate::Encodable for Maybe {
+            fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+            where
+                W: Write + ?Sized,
+            {
+                buf.write_all(b"PRIVMSG ")?;
+                let data = self.0.ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "missing")
+                })?;
+                buf.write_all(data.as_bytes())
+            }
+        }
+
+        let fut = async move {
+            let mut output = vec![];
+            {
+                let mut encoder = AsyncEncoder::new(&mut output);
+                encoder
+                    .encode_many(vec![join("#museun"), join("#shaken_bot")])
+                    .await
+                    .unwrap();
+
+                let msgs = vec![Maybe(Some("#museun :hello\r\n")), Maybe(None)];
+                let err = encoder.encode_many(msgs).await.unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+                // the first message was kept, and is written with this one
+                encoder.encode(join("#test")).await.unwrap();
+            }
+
+            let s = std::str::from_utf8(&output).unwrap();
+            assert_eq!(
+                s,
+                "JOIN #museun\r\nJOIN #shaken_bot\r\nPRIVMSG #museun :hello\r\nJOIN #test\r\n"
+            );
+        };
+        futures_lite::future::block_on(fut);
+    }
 }
 }
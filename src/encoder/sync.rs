@@ -34,6 +34,25 @@ where
         msg.encode(&mut self.writer)?;
         self.writer.flush()
     }
+
+    /// Encode each of these [Encodable] messages to the writer, flushing once at the end.
+    ///
+    /// Each message is encoded to a buffer first, so a message that fails to encode
+    /// doesn't leave part of itself in the writer. Its error is returned without flushing.
+    /// The messages before it are still in the writer, they aren't rolled back.
+    pub fn encode_many<I, M>(&mut self, msgs: I) -> IoResult<()>
+    where
+        I: IntoIterator<Item = M>,
+        M: Encodable,
+    {
+        let mut buf = vec![];
+        for msg in msgs {
+            buf.clear();
+            msg.encode(&mut buf)?;
+            self.writer.write_all(&buf)?;
+        }
+        self.writer.flush()
+    }
 }
 
 impl<W> Clone for Encoder<W>
@@ -78,6 +97,64 @@ mod tests {
         assert_eq!(s, "JOIN #museun\r\nJOIN #shaken_bot\r\n");
     }
 
+    #[test]
+    fn encode_many() {
+        #[derive(Default)]
+        struct Flushes {
+            data: Vec<u8>,
+            flushes: usize,
+        }
+
+        impl Write for Flushes {
+            fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+                self.data.write(buf)
+            }
+            fn flush(&mut self) -> IoResult<()> {
+                self.flushes += 1;
+                Ok(())
+            }
+        }
+
+        let mut encoder = Encoder::new(Flushes::default());
+        encoder
+            .encode_many(vec![join("#museun"), join("#shaken_bot")])
+            .unwrap();
+
+        let out = encoder.into_inner();
+        assert_eq!(out.flushes, 1);
+        assert_eq!(out.data, b"JOIN #museun\r\nJOIN #shaken_bot\r\n");
+    }
+
+    #[test]
+    fn encode_many_error() {
+        // a message that writes part of itself, then fails when it is `None`
+        struct Maybe(Option<&'static str>);
+        impl Encodable for Maybe {
+            fn encode<W>(&self, buf: &mut W) -> IoResult<()>
+            where
+                W: Write + ?Sized,
+            {
+                buf.write_all(b"PRIVMSG ")?;
+                let data = self.0.ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "missing")
+                })?;
+                buf.write_all(data.as_bytes())
+            }
+        }
+
+        let mut encoder = Encoder::new(vec![]);
+        let msgs = vec![
+            Maybe(Some("#museun :hello\r\n")),
+            Maybe(None),
+            Maybe(Some("#museun :world\r\n")),
+        ];
+        let err = encoder.encode_many(msgs).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        // the first one was still written, but nothing of the failed one
+        assert_eq!(encoder.into_inner(), b"PRIVMSG #museun :hello\r\n");
+    }
+
     #[test]
     fn encodable_builtin() {
         fn check<T>(input: &T)
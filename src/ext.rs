@@ -1,4 +1,4 @@
-use crate::{messages::Privmsg, Encodable};
+use crate::{messages::Privmsg, trovo::Color, Encodable};
 use std::io::Write;
 
 /// Extensions to the `Privmsg` message type
@@ -7,28 +7,152 @@ pub trait PrivmsgExt {
     fn reply(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()>;
 
     /// Send a message back to the channel this Privmsg came from
+    ///
+    /// If `data` contains multiple lines (split on either `\r` or `\n`), each
+    /// non-empty line is sent as its own message.
+    ///
+    /// This returns an error if `data` is empty, or only whitespace and line breaks.
     fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()>;
+
+    /// Change your username `color` and then reply to this message with `data`
+    ///
+    /// Trovo doesn't have per-message colors, so this changes your color for all later messages.
+    ///
+    /// This returns an error if `data` is empty.
+    fn reply_with_color(
+        &mut self,
+        msg: &Privmsg<'_>,
+        data: &str,
+        color: Color,
+    ) -> std::io::Result<()>;
+}
+
+fn reply_id<'a>(msg: &'a Privmsg<'_>) -> std::io::Result<&'a str> {
+    msg.tags().get("id").ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "you must have `TAGS` enabled",
+        )
+    })
 }
 
 impl<'a, W: Write + ?Sized> PrivmsgExt for W {
     fn reply(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
-        let cmd = crate::commands::reply(
-            msg.channel(),
-            msg.tags().get("id").ok_or_else(|| {
-                std::io::Error::new(
-                    std::io::ErrorKind::PermissionDenied,
-                    "you must have `TAGS` enabled",
-                )
-            })?,
-            data,
-        );
+        let cmd = crate::commands::reply(msg.channel(), reply_id(msg)?, data);
         cmd.encode(self)?;
         self.flush()
     }
 
-    fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
-        let cmd = crate::commands::privmsg(msg.channel(), data);
+    fn reply_with_color(
+        &mut self,
+        msg: &Privmsg<'_>,
+        data: &str,
+        color: Color,
+    ) -> std::io::Result<()> {
+        if data.trim().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "cannot reply with an empty message",
+            ));
+        }
+
+        // make sure we can reply before changing the color
+        let id = reply_id(msg)?;
+
+        let cmd = match crate::commands::color(color) {
+            Ok(cmd) => cmd,
+            Err(never) => match never {},
+        };
+        cmd.encode(self)?;
+        self.flush()?;
+
+        let cmd = crate::commands::reply(msg.channel(), id, data);
         cmd.encode(self)?;
         self.flush()
     }
+
+    fn say(&mut self, msg: &Privmsg<'_>, data: &str) -> std::io::Result<()> {
+        if data.trim().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "cannot say an empty message",
+            ));
+        }
+
+        // a CR or LF would end the command early, so treat either as a line break
+        let lines = data
+            .split(&['\r', '\n'][..])
+            .filter(|line| !line.is_empty());
+        for line in lines {
+            let cmd = crate::commands::privmsg(msg.channel(), line);
+            cmd.encode(self)?;
+            // flush each message, some writers only send a single line per flush
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::parse, FromIrcMessage as _};
+
+    #[test]
+    fn say_multiple_lines() {
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n";
+        let msg = Privmsg::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+
+        let mut out = vec![];
+        out.say(&msg, "hello world").unwrap();
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "PRIVMSG #museun :hello world\r\n"
+        );
+
+        let mut out = vec![];
+        out.say(&msg, "hello\r\nworld\n\nQUIT\r").unwrap();
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "PRIVMSG #museun :hello\r\nPRIVMSG #museun :world\r\nPRIVMSG #museun :QUIT\r\n"
+        );
+
+        for data in &["", "\r\n", "\n\n"] {
+            let mut out = vec![];
+            let err = out.say(&msg, data).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+            assert!(out.is_empty());
+        }
+    }
+
+    #[test]
+    fn reply_with_color() {
+        let input = "@id=aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa :test!user@host PRIVMSG #museun :this is a test\r\n";
+        let msg = Privmsg::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+
+        let mut out = vec![];
+        out.reply_with_color(&msg, "hello", "Red".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "PRIVMSG jtv :/color Red\r\n@reply-parent-msg-id=aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa PRIVMSG #museun :hello\r\n"
+        );
+
+        let mut out = vec![];
+        let err = out
+            .reply_with_color(&msg, " ", "Red".parse().unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+
+        // without tags there's nothing to reply to, so the color isn't changed
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n";
+        let msg = Privmsg::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        let mut out = vec![];
+        let err = out
+            .reply_with_color(&msg, "hello", "Red".parse().unwrap())
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(out.is_empty());
+    }
 }
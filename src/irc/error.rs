@@ -1,3 +1,9 @@
+use alloc::{boxed::Box, string::String};
+#[cfg(not(feature = "std"))]
+use core::error::Error;
+#[cfg(feature = "std")]
+use std::error::Error;
+
 /// An invalid message was either provided, or could not be parsed
 #[derive(Debug)]
 #[non_exhaustive]
@@ -33,7 +39,7 @@ pub enum MessageError {
         /// The tag name
         name: String,
         /// The parse error
-        error: Box<dyn std::error::Error + Send + Sync>,
+        error: Box<dyn Error + Send + Sync>,
     },
 
     /// An incomplete message was provided
@@ -45,15 +51,21 @@ pub enum MessageError {
     /// An empty message was provided
     EmptyMessage,
 
+    /// The message was too long to be parsed
+    MessageTooLong {
+        /// The length of the message, in bytes
+        len: usize,
+    },
+
     /// A custom error message
     Custom {
         /// The inner error
-        error: Box<dyn std::error::Error + Send + Sync>,
+        error: Box<dyn Error + Send + Sync>,
     },
 }
 
-impl std::fmt::Display for MessageError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for MessageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidCommand { expected, got } => {
                 write!(f, "invalid command. expected '{}' got '{}'", expected, got)
@@ -65,13 +77,14 @@ impl std::fmt::Display for MessageError {
             Self::CannotParseTag { name, error } => write!(f, "cannot parse '{}': {}", name, error),
             Self::IncompleteMessage { pos } => write!(f, "incomplete message starting at: {}", pos),
             Self::EmptyMessage => write!(f, "no message could be parsed"),
+            Self::MessageTooLong { len } => write!(f, "message is too long: {} bytes", len),
             Self::Custom { error } => write!(f, "custom error: {}", error),
         }
     }
 }
 
-impl std::error::Error for MessageError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl Error for MessageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::CannotParseTag { error, .. } => Some(&**error),
             Self::Custom { error } => Some(&**error),
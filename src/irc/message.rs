@@ -1,6 +1,6 @@
 use super::{parser::Parser, MessageError, Prefix, PrefixIndex};
 use crate::{FromIrcMessage, MaybeOwned, MaybeOwnedIndex};
-use std::convert::Infallible;
+use core::convert::Infallible;
 
 /// A raw irc message `@tags :prefix COMMAND args :data\r\n`
 #[derive(Clone, PartialEq)]
@@ -21,6 +21,17 @@ pub struct IrcMessage<'a> {
 
 impl<'a> IrcMessage<'a> {
     pub(crate) fn parse(input: MaybeOwned<'a>) -> Result<Self, MessageError> {
+        // any leading whitespace would throw off the indices, so drop it
+        let start = input.len() - input.trim_start().len();
+        let input = if start == 0 {
+            input
+        } else {
+            match input {
+                MaybeOwned::Borrowed(s) => MaybeOwned::Borrowed(&s[start..]),
+                MaybeOwned::Owned(s) => MaybeOwned::Owned(s[start..].into()),
+            }
+        };
+
         // trim any \r\n off incase this was directly called
         let data = if input.ends_with("\r\n") {
             &input.as_ref()[..input.len() - 2]
@@ -28,7 +39,12 @@ impl<'a> IrcMessage<'a> {
             input.as_ref()
         };
 
-        let data = data.trim();
+        // the indices are stored as u16s, so anything longer couldn't be indexed
+        if data.len() > u16::MAX as usize {
+            return Err(MessageError::MessageTooLong { len: data.len() });
+        }
+
+        let data = data.trim_end();
         if data.is_empty() {
             return Err(super::MessageError::EmptyMessage);
         }
@@ -44,11 +60,27 @@ impl<'a> IrcMessage<'a> {
             command: p.command(),
             args: p.args(),
             data: p.data(),
-            raw: input, // NOTE: this stores the original input string, not the trimmed string
+            raw: input, // NOTE: this stores the input string without any leading whitespace, but not otherwise trimmed
         };
         Ok(this)
     }
 
+    /// Try to parse a single message from `input`.
+    ///
+    /// The trailing `\r\n` is optional. This never panics, any malformed input is returned as a [MessageError].
+    ///
+    /// ```
+    /// # use trovochat::{IrcMessage, MessageError};
+    /// let msg = IrcMessage::try_parse(":museun!museun@museun PRIVMSG #museun :hello\r\n").unwrap();
+    /// assert_eq!(msg.get_command(), "PRIVMSG");
+    ///
+    /// let err = IrcMessage::try_parse("  \r\n").unwrap_err();
+    /// assert!(matches!(err, MessageError::EmptyMessage));
+    /// ```
+    pub fn try_parse(input: &'a str) -> Result<Self, MessageError> {
+        Self::parse(MaybeOwned::Borrowed(input))
+    }
+
     /// Get the raw string
     pub fn get_raw(&self) -> &str {
         &*self.raw
@@ -56,12 +88,43 @@ impl<'a> IrcMessage<'a> {
 
     /// Get the raw tags
     pub fn get_tags(&self) -> Option<&str> {
-        self.tags.map(|index| &self.raw[index])
+        self.tags.and_then(|index| self.raw.get(index))
+    }
+
+    /// Iterate over the tags, with their values unescaped
+    ///
+    /// This walks the same [TagIndices](crate::irc::TagIndices) the message types use,
+    /// so it only allocates those and any value that has to be unescaped.
+    ///
+    /// ```
+    /// # use trovochat::IrcMessage;
+    /// let msg = IrcMessage::try_parse(r"@id=1234;system-msg=hello\sworld\:) PING :foo").unwrap();
+    /// let tags = msg.tags_iter().collect::<Vec<_>>();
+    /// assert_eq!(tags[0].0, "id");
+    /// assert_eq!(tags[0].1, "1234");
+    /// assert_eq!(tags[1].0, "system-msg");
+    /// assert_eq!(tags[1].1, "hello world;)");
+    /// ```
+    pub fn tags_iter(&self) -> impl Iterator<Item = (&str, MaybeOwned<'_>)> + '_ {
+        let indices = self
+            .get_tags()
+            .map(super::TagIndices::build_indices)
+            .unwrap_or_default();
+
+        indices
+            .map
+            .into_vec()
+            .into_iter()
+            .filter_map(move |(key, value)| {
+                let key = self.raw.get(key).filter(|key| !key.is_empty())?;
+                let value = self.raw.get(value)?;
+                Some((key, super::tags::unescape_str(value)))
+            })
     }
 
     /// Get the raw prefix
     pub fn get_prefix(&self) -> Option<&str> {
-        self.prefix.map(|index| &self.raw[index.as_index()])
+        self.prefix.and_then(|index| self.raw.get(index.as_index()))
     }
 
     /// Get the raw command
@@ -71,12 +134,20 @@ impl<'a> IrcMessage<'a> {
 
     /// Get the raw args
     pub fn get_args(&self) -> Option<&str> {
-        self.args.map(|index| &self.raw[index])
+        self.args.and_then(|index| self.raw.get(index))
     }
 
     /// Get the raw data
     pub fn get_data(&self) -> Option<&str> {
-        self.data.map(|index| &self.raw[index])
+        self.data.and_then(|index| self.raw.get(index))
+    }
+
+    /// Get an owned copy of this message, without consuming it
+    ///
+    /// This is the same as `msg.clone().into_owned()`
+    pub fn to_owned_message(&self) -> IrcMessage<'static> {
+        use crate::IntoOwned as _;
+        self.clone().into_owned()
     }
 
     /// Consumes this type returning the raw `MaybeOwned<'a>`
@@ -87,7 +158,7 @@ impl<'a> IrcMessage<'a> {
     /// Get the raw 'nth' argument
     pub fn nth_arg(&self, nth: usize) -> Option<&str> {
         self.args
-            .map(|index| &self.raw[index])?
+            .and_then(|index| self.raw.get(index))?
             .split_ascii_whitespace()
             .nth(nth)
     }
@@ -95,7 +166,7 @@ impl<'a> IrcMessage<'a> {
     /// Get the index of the 'nth' argumnet
     pub fn nth_arg_index(&self, nth: usize) -> Option<MaybeOwnedIndex> {
         let index = self.args?;
-        let args = &self.raw[index];
+        let args = self.raw.get(index)?;
 
         let mut seen = 0;
         let (mut head, mut tail) = (index.start, index.start);
@@ -111,7 +182,8 @@ impl<'a> IrcMessage<'a> {
                 seen += 1;
             }
 
-            tail += 1;
+            // these are byte offsets, so step over the whole character
+            tail += ch.len_utf8() as u16;
         }
 
         if seen == nth {
@@ -131,6 +203,14 @@ impl<'a> IrcMessage<'a> {
     ///
     /// This is sent by Trovo with your user information.
     pub const READY: &'static str = "376";
+    /// Information about the server you connected to -- `002`.
+    pub const YOUR_HOST: &'static str = "002";
+    /// When the server was created -- `003`.
+    pub const CREATED: &'static str = "003";
+    /// Information about the server's version and modes -- `004`.
+    pub const MY_INFO: &'static str = "004";
+    /// The start of the message of the day -- `375`.
+    pub const MOTD_START: &'static str = "375";
     /// A capability response -- `CAP`.
     ///
     /// This is sent to acknowledge whether the capability requested is valid and applied to your connections.
@@ -147,6 +227,12 @@ impl<'a> IrcMessage<'a> {
     pub const HOST_TARGET: &'static str = "HOSTTARGET";
     /// A Trovo event when a user joins a channel -- `JOIN`.    
     pub const JOIN: &'static str = "JOIN";
+    /// A list of users on a channel -- `353`.
+    ///
+    /// This is sent, possibly over several messages, when you join a channel with the `membership` capability.
+    pub const NAMES: &'static str = "353";
+    /// The end of the list of users on a channel -- `366`.
+    pub const END_OF_NAMES: &'static str = "366";
     /// A message from Trovo -- `NOTICE`
     pub const NOTICE: &'static str = "NOTICE";
     /// A Trovo event when a user leaves a channel -- `PART`
@@ -193,8 +279,8 @@ into_owned! {
     }
 }
 
-impl<'a> std::fmt::Debug for IrcMessage<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for IrcMessage<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("IrcMessage")
             .field("raw", &&*self.raw)
             .field("tags", &self.get_tags())
@@ -253,6 +339,22 @@ mod tests {
         crate::serde::round_trip_rmp::<IrcMessage>(input);
     }
 
+    #[test]
+    fn to_owned_message() {
+        let input = ":test!test@test PRIVMSG #museun :this is a test\r\n";
+        let msg = crate::irc::parse(input).next().unwrap().unwrap();
+        let owned: IrcMessage<'static> = msg.to_owned_message();
+
+        for msg in &[&msg, &owned] {
+            assert_eq!(msg.get_raw(), input);
+            assert_eq!(msg.get_prefix(), Some("test"));
+            assert_eq!(msg.get_command(), "PRIVMSG");
+            assert_eq!(msg.nth_arg(0), Some("#museun"));
+            assert_eq!(msg.get_data(), Some("this is a test"));
+        }
+        assert_eq!(msg, owned);
+    }
+
     #[test]
     fn parse_empty_spaces() {
         for i in 0..10 {
@@ -261,4 +363,146 @@ mod tests {
             assert!(matches!(err, MessageError::EmptyMessage))
         }
     }
+
+    #[test]
+    fn parse_leading_spaces() {
+        let msg = IrcMessage::try_parse("  @a=é é PRIVMSG #museun :hello\r\n").unwrap();
+        assert_eq!(msg.get_raw(), "@a=é é PRIVMSG #museun :hello\r\n");
+        let indices = crate::Validator::parse_tags(&msg);
+        let tags = crate::irc::Tags::from_data_indices(&msg.raw, &indices);
+        assert_eq!(tags.get("a"), Some("é"));
+        assert_eq!(msg.get_command(), "é");
+        assert_eq!(msg.get_args(), Some("PRIVMSG #museun"));
+        assert_eq!(msg.get_data(), Some("hello"));
+    }
+
+    #[test]
+    fn nth_arg_multi_byte() {
+        let msg = IrcMessage::try_parse("PRIVMSG #müseün ☃ :hello\r\n").unwrap();
+        let index = msg.nth_arg_index(1).unwrap();
+        assert_eq!(&msg.raw[index], "☃");
+        assert_eq!(msg.nth_arg(0), Some("#müseün"));
+    }
+
+    #[test]
+    fn out_of_range_index() {
+        let mut msg = IrcMessage::try_parse("@id=1 :museun PRIVMSG #müseün :hello\r\n").unwrap();
+        let len = msg.raw.len();
+
+        msg.data = Some(MaybeOwnedIndex::raw(len - 2, len + 10));
+        assert_eq!(msg.get_data(), None);
+
+        msg.tags = Some(MaybeOwnedIndex::raw(len + 1, len + 5));
+        assert_eq!(msg.get_tags(), None);
+        assert_eq!(msg.tags_iter().count(), 0);
+
+        // in bounds, but in the middle of the 'ü'
+        let start = msg.raw.find('ü').unwrap() + 1;
+        msg.args = Some(MaybeOwnedIndex::raw(start, start + 3));
+        assert_eq!(msg.get_args(), None);
+        assert_eq!(msg.nth_arg(0), None);
+        assert_eq!(msg.nth_arg_index(0), None);
+
+        assert_eq!(msg.raw.get(MaybeOwnedIndex::raw(5, 3)), None);
+    }
+
+    #[test]
+    fn parse_too_long() {
+        let input = format!("PRIVMSG #museun :{}\r\n", "a".repeat(u16::MAX as usize));
+        let err = IrcMessage::try_parse(&input).unwrap_err();
+        assert!(matches!(err, MessageError::MessageTooLong { .. }));
+    }
+
+    #[test]
+    fn parse_no_head() {
+        // just a prefix
+        let msg = IrcMessage::try_parse(":tmi.trovo.tv\r\n").unwrap();
+        assert_eq!(msg.get_prefix(), None);
+        assert_eq!(msg.get_args(), None);
+        assert_eq!(msg.get_data(), None);
+        assert_eq!(msg.nth_arg(0), None);
+
+        // just tags
+        let msg = IrcMessage::try_parse("@badges=;mod=0\r\n").unwrap();
+        assert_eq!(msg.get_tags(), None);
+        assert_eq!(msg.get_prefix(), None);
+        assert_eq!(msg.get_args(), None);
+
+        // tags and a prefix, but no command
+        let msg = IrcMessage::try_parse("@mod=0 :tmi.trovo.tv\r\n").unwrap();
+        assert_eq!(msg.get_tags(), Some("@mod=0"));
+        assert_eq!(msg.get_prefix(), None);
+        assert_eq!(msg.get_args(), None);
+        assert_eq!(msg.get_data(), None);
+    }
+
+    #[test]
+    fn tags_iter_unescaped() {
+        let input = "@badges=;msg=a\\:b\\sc\\\\d;empty=;=nokey;flag :tmi.trovo.tv PING :1234\r\n";
+        let msg = IrcMessage::try_parse(input).unwrap();
+
+        let tags = msg.tags_iter().collect::<Vec<_>>();
+        let expected = &[
+            ("badges", ""),
+            ("msg", "a;b c\\d"),
+            ("empty", ""),
+            ("flag", ""),
+        ];
+        assert_eq!(tags.len(), expected.len());
+        for ((key, value), (expected_key, expected_value)) in tags.iter().zip(expected) {
+            assert_eq!(key, expected_key);
+            assert_eq!(value, expected_value);
+        }
+
+        // the semicolon round-trips
+        let escaped = crate::irc::tags::escape_str("hello;world");
+        let input = format!("@msg={} PING :1234\r\n", escaped);
+        let msg = IrcMessage::try_parse(&input).unwrap();
+        let (_, value) = msg.tags_iter().next().unwrap();
+        assert_eq!(value, "hello;world");
+
+        let msg = IrcMessage::try_parse("PING :1234\r\n").unwrap();
+        assert_eq!(msg.tags_iter().count(), 0);
+    }
+
+    #[test]
+    fn try_parse_random_input() {
+        // a tiny xorshift, so the test is deterministic
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        // mostly the characters the parser cares about, with some multi-byte ones
+        const ALPHABET: &[&str] = &[
+            "@", ":", "!", ";", "=", " ", "#", "\r", "\n", "\\", "a", "b", "é", "ß", "🍕", "\u{0}",
+        ];
+
+        for _ in 0..20_000 {
+            let len = (next() % 48) as usize;
+            let input = (0..len)
+                .map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize])
+                .collect::<String>();
+
+            if let Ok(msg) = IrcMessage::try_parse(&input) {
+                let _ = msg.get_tags();
+                let _ = msg.get_prefix();
+                let _ = msg.get_command();
+                let _ = msg.get_args();
+                let _ = msg.get_data();
+                for nth in 0..4 {
+                    let _ = msg.nth_arg(nth);
+                    let _ = msg.nth_arg_index(nth).map(|index| &msg.raw[index]);
+                }
+                let indices = crate::Validator::parse_tags(&msg);
+                let tags = crate::irc::Tags::from_data_indices(&msg.raw, &indices);
+                for (key, value) in tags.iter() {
+                    let _ = (key, value);
+                }
+            }
+        }
+    }
 }
@@ -25,22 +25,30 @@ impl<'a> Parser<'a> {
 
     pub(super) fn prefix(&mut self) -> Option<PrefixIndex> {
         let input = self.input.get(self.pos..)?;
-        if input.starts_with(':') {
-            if let Some(pos) = input.find(' ') {
-                self.pos += 1;
-                let prefix = match input.find('!') {
-                    Some(bang) => PrefixIndex::User {
-                        nick: self.mark_index(bang - 1, pos),
-                    },
-                    None => PrefixIndex::Server {
-                        host: self.mark_index(pos - 1, pos),
-                    },
-                };
-
-                return Some(prefix);
-            }
+        if !input.starts_with(':') {
+            return None;
         }
-        None
+
+        let pos = input.find(' ')?;
+        // only look at the prefix, the rest of the line can have a '!' or '@' in it
+        let prefix = &input[1..pos];
+        self.pos += 1;
+
+        // a user is `nick`, `nick!user`, `nick@host` or `nick!user@host`,
+        // but a server name always has a '.' in it
+        let prefix = match prefix.find(&['!', '@'][..]) {
+            Some(end) => PrefixIndex::User {
+                nick: self.mark_index(end, pos),
+            },
+            None if prefix.contains('.') => PrefixIndex::Server {
+                host: self.mark_index(prefix.len(), pos),
+            },
+            None => PrefixIndex::User {
+                nick: self.mark_index(prefix.len(), pos),
+            },
+        };
+
+        Some(prefix)
     }
 
     pub(super) fn command(&mut self) -> MaybeOwnedIndex {
@@ -98,7 +106,7 @@ impl<'a> Iterator for IrcParserIter<'a> {
             }
         };
 
-        let pos = std::mem::replace(&mut self.pos, index);
+        let pos = core::mem::replace(&mut self.pos, index);
         self.data
             .get(pos..index)
             .map(MaybeOwned::from)
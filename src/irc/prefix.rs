@@ -6,8 +6,8 @@ pub struct Prefix<'a> {
     pub(crate) index: PrefixIndex,
 }
 
-impl<'a> std::fmt::Debug for Prefix<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for Prefix<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.data[self.index.as_index()].fmt(f)
     }
 }
@@ -23,6 +23,20 @@ impl<'a> Prefix<'a> {
         matches!(self.index, PrefixIndex::User{ .. })
     }
 
+    /// Was this message from the `tmi.trovo.tv` server?
+    ///
+    /// Most system messages (e.g. `ROOMSTATE`, `USERNOTICE`, `CLEARCHAT`) are sent from here.
+    pub fn is_tmi(&self) -> bool {
+        self.is_server() && self.get_raw_prefix() == "tmi.trovo.tv"
+    }
+
+    /// Was this message from `jtv`?
+    ///
+    /// This is used for older messages, such as `MODE` and notices sent as a `PRIVMSG`.
+    pub fn is_jtv(&self) -> bool {
+        self.get_raw_prefix() == "jtv"
+    }
+
     /// Get the full, raw string
     pub fn get_raw_prefix(&self) -> &'a str {
         &self.data[self.index.as_index()]
@@ -30,7 +44,9 @@ impl<'a> Prefix<'a> {
 
     /// Get the nickname of this prefix, if it was sent by a user
     pub fn get_nick(&self) -> Option<&'a str> {
-        self.index.nick_index().map(|index| &self.data[index])
+        self.index
+            .nick_index()
+            .and_then(|index| self.data.get(index))
     }
 }
 
@@ -91,3 +107,88 @@ impl IntoOwned<'static> for PrefixIndex {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IrcMessage;
+
+    fn check_prefix(input: &str, func: impl Fn(Prefix<'_>)) {
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        func(Prefix {
+            data: &msg.raw,
+            index: msg.prefix.unwrap(),
+        })
+    }
+
+    #[test]
+    fn prefix_shapes() {
+        for (input, nick) in &[
+            (
+                ":museun!museun@museun.tmi.trovo.tv JOIN #museun\r\n",
+                "museun",
+            ),
+            (":museun!museun JOIN #museun\r\n", "museun"),
+            (":museun@museun.tmi.trovo.tv JOIN #museun\r\n", "museun"),
+            (":museun JOIN #museun\r\n", "museun"),
+        ] {
+            check_prefix(input, |prefix| {
+                assert!(prefix.is_user(), "{}", input);
+                assert_eq!(prefix.get_nick(), Some(*nick), "{}", input);
+                assert_eq!(prefix.get_raw_prefix(), *nick, "{}", input);
+            });
+        }
+
+        check_prefix(":tmi.trovo.tv ROOMSTATE #museun\r\n", |prefix| {
+            assert!(prefix.is_server());
+            assert_eq!(prefix.get_nick(), None);
+            assert_eq!(prefix.get_raw_prefix(), "tmi.trovo.tv");
+        });
+    }
+
+    #[test]
+    fn prefix_ignores_rest_of_line() {
+        check_prefix(
+            ":tmi.trovo.tv NOTICE #museun :hello! foo@bar\r\n",
+            |prefix| {
+                assert!(prefix.is_server());
+                assert_eq!(prefix.get_raw_prefix(), "tmi.trovo.tv");
+            },
+        );
+
+        check_prefix(":museun PRIVMSG #museun :hello!\r\n", |prefix| {
+            assert_eq!(prefix.get_nick(), Some("museun"));
+        });
+    }
+
+    #[test]
+    fn prefix_is_tmi() {
+        check_prefix(":tmi.trovo.tv ROOMSTATE #museun\r\n", |prefix| {
+            assert!(prefix.is_tmi());
+            assert!(!prefix.is_jtv());
+        });
+
+        check_prefix(
+            ":museun!museun@museun.tmi.trovo.tv JOIN #museun\r\n",
+            |prefix| assert!(!prefix.is_tmi()),
+        );
+    }
+
+    #[test]
+    fn prefix_is_jtv() {
+        check_prefix(":jtv MODE #museun +o shaken_bot\r\n", |prefix| {
+            assert!(prefix.is_jtv());
+            assert!(!prefix.is_tmi());
+        });
+
+        check_prefix(
+            ":jtv!jtv@jtv.tmi.trovo.tv PRIVMSG museun :hello\r\n",
+            |prefix| assert!(prefix.is_jtv()),
+        );
+
+        check_prefix(
+            ":museun!museun@museun.tmi.trovo.tv JOIN #museun\r\n",
+            |prefix| assert!(!prefix.is_jtv()),
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::maybe_owned::MaybeOwned;
 use crate::{IntoOwned, MaybeOwnedIndex};
+use alloc::{boxed::Box, vec::Vec};
 
 /// Pre-computed tag indices
 ///
@@ -9,8 +10,8 @@ pub struct TagIndices {
     pub(super) map: Box<[(MaybeOwnedIndex, MaybeOwnedIndex)]>,
 }
 
-impl std::fmt::Debug for TagIndices {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for TagIndices {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map()
             .entries(self.map.iter().map(|(k, v)| (k, v)))
             .finish()
@@ -48,11 +49,14 @@ impl TagIndices {
                     value.replace(i);
                 }
                 _ => {
-                    match mode {
+                    let index = match mode {
                         Mode::Head => &mut key,
                         Mode::Tail => &mut value,
+                    };
+                    // the indices are in bytes, not chars
+                    for _ in 0..ch.len_utf8() {
+                        index.bump_tail();
                     }
-                    .bump_tail();
                 }
             }
         }
@@ -80,15 +84,16 @@ impl TagIndices {
 
     // NOTE: this isn't public because they don't verify 'data' is the same as the built-indices data
     pub(crate) fn get_unescaped<'a>(&'a self, key: &str, data: &'a str) -> Option<MaybeOwned<'a>> {
-        self.get(key, data).map(crate::test::unescape_str)
+        self.get(key, data).map(super::tags::unescape_str)
     }
 
     // NOTE: this isn't public because they don't verify 'data' is the same as the built-indices data
     pub(crate) fn get<'a>(&'a self, key: &str, data: &'a str) -> Option<&'a str> {
-        let key = crate::test::escape_str(key);
+        let key = super::tags::escape_str(key);
         self.map
             .iter()
-            .find_map(|(k, v)| if key == data[k] { Some(&data[v]) } else { None })
+            .find(|(k, _)| k.get(data) == Some(&*key))
+            .and_then(|(_, v)| v.get(data))
     }
 }
 
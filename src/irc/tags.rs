@@ -1,5 +1,6 @@
 use crate::{irc::TagIndices, MaybeOwned};
-use std::{borrow::Borrow, str::FromStr};
+use alloc::string::String;
+use core::{borrow::Borrow, str::FromStr};
 
 /// Tags are IRCv3 message tags. Trovo uses them extensively.
 ///
@@ -12,8 +13,8 @@ pub struct Tags<'a> {
     pub(crate) indices: &'a TagIndices,
 }
 
-impl<'a> std::fmt::Debug for Tags<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for Tags<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_map().entries(self.iter()).finish()
     }
 }
@@ -61,7 +62,15 @@ impl<'a> Tags<'a> {
         self.indices.get(key.borrow(), &*self.data)
     }
 
-    /** Tries to get the tag as a parsable [std::str::FromStr] type.
+    /// Returns whether this `key` is present, even if its value is empty
+    pub fn contains<K>(&self, key: &K) -> bool
+    where
+        K: ?Sized + Borrow<str>,
+    {
+        self.get(key).is_some()
+    }
+
+    /** Tries to get the tag as a parsable [core::str::FromStr] type.
 
     This returns None if it cannot parse, or cannot find the tag
 
@@ -144,6 +153,30 @@ impl<'a> Tags<'a> {
         }
     }
 
+    /// Tries to get a `0`/`1` tag as a bool.
+    ///
+    /// Unlike [Tags::get_as_bool()], this returns `None` if the tag is missing
+    /// or isn't `0` or `1`, so those can be told apart from `false`.
+    pub fn get_bool<K>(&self, key: &K) -> Option<bool>
+    where
+        K: ?Sized + Borrow<str>,
+    {
+        match self.get(key)? {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Convert these tags into a JSON object, with all of the values unescaped
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn to_json_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.iter()
+            .map(|(k, v)| (String::from(k), String::from(&*unescape_str(v)).into()))
+            .collect()
+    }
+
     /// Get an iterator over all of the `key, value` pairs of tags
     pub fn iter(&self) -> TagsIter<'_> {
         TagsIter {
@@ -172,8 +205,8 @@ pub struct TagsIter<'a> {
     pos: usize,
 }
 
-impl<'a> std::fmt::Debug for TagsIter<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for TagsIter<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("TagsIter").finish()
     }
 }
@@ -275,7 +308,7 @@ pub fn unescape_str(s: &str) -> MaybeOwned<'_> {
 /// | --          | the character itself |
 ///
 /// [ref]: https://ircv3.net/specs/extensions/message-tags.html#escaping-values
-pub fn escape_str(s: &str) -> std::borrow::Cow<'_, str> {
+pub fn escape_str(s: &str) -> alloc::borrow::Cow<'_, str> {
     const NEEDS_ESCAPE: [char; 5] = [';', ' ', '\\', '\n', '\r'];
     let n = s.chars().filter(|c| NEEDS_ESCAPE.contains(c)).count();
     if n == 0 {
@@ -300,7 +333,7 @@ pub fn escape_str(s: &str) -> std::borrow::Cow<'_, str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
+    use core::str::FromStr;
 
     #[test]
     fn round_trip_escape() {
@@ -341,6 +374,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn contains() {
+        let input = MaybeOwned::Borrowed("@first-msg=1;emotes=;foo\\sbar=baz");
+        let indices = TagIndices::build_indices(&*input);
+
+        let tags = Tags::from_data_indices(&input, &indices);
+        assert!(tags.contains("first-msg"));
+        // an empty value is still present
+        assert!(tags.contains("emotes"));
+        assert!(tags.contains("foo bar"));
+        assert!(!tags.contains("returning-chatter"));
+        assert!(!tags.contains(""));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn get_bool_user_state() {
+        use crate::{messages::UserState, FromIrcMessage as _};
+
+        let input = "@badge-info=;badges=moderator/1;color=#1E90FF;display-name=museun;emote-sets=0;mod=1;subscriber=0;user-type=mod :tmi.trovo.tv USERSTATE #museun\r\n";
+        let msg = crate::irc::parse(input).next().unwrap().unwrap();
+        let msg = UserState::from_irc(msg).unwrap();
+        let tags = msg.tags();
+
+        assert_eq!(tags.get_bool("mod"), Some(true));
+        assert_eq!(tags.get_bool("subscriber"), Some(false));
+        // not a 0 or 1
+        assert_eq!(tags.get_bool("user-type"), None);
+        assert_eq!(tags.get_bool("emote-sets"), Some(false));
+        assert_eq!(tags.get_bool("turbo"), None);
+    }
+
     #[test]
     fn get_parsed() {
         let input = MaybeOwned::Borrowed("@foo=42;badges=broadcaster/1,subscriber/6");
@@ -358,7 +423,7 @@ mod tests {
         struct Badges(std::collections::HashMap<String, usize>);
 
         impl FromStr for Badges {
-            type Err = std::convert::Infallible;
+            type Err = core::convert::Infallible;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
                 let iter = s.split_terminator(',').filter_map(|s| {
                     let mut iter = s.split('/');
@@ -426,6 +491,25 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "json")]
+    fn tags_to_json_map() {
+        let input = "@badges=broadcaster/1;display-name=museun;system-msg=hello\\sworld;empty= :tmi.trovo.tv USERNOTICE #museun\r\n";
+        let msg = crate::irc::parse(input).next().unwrap().unwrap();
+        let data = MaybeOwned::Borrowed(msg.get_tags().unwrap());
+        let indices = TagIndices::build_indices(&data);
+        let tags = Tags::from_data_indices(&data, &indices);
+
+        let json = serde_json::to_string(&tags.to_json_map()).unwrap();
+        let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(map.len(), tags.len());
+        assert_eq!(map["badges"], "broadcaster/1");
+        assert_eq!(map["display-name"], "museun");
+        assert_eq!(map["system-msg"], "hello world");
+        assert_eq!(map["empty"], "");
+    }
+
     #[test]
     fn parse() {
         let input = "@badges=broadcaster/1,subscriber/6;\
@@ -21,6 +21,7 @@
     unused_import_braces,
     unused_qualifications
 )]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(docsrs, feature(doc_alias))]
 #![cfg_attr(docsrs, feature(broken_intra_doc_links))]
@@ -42,6 +43,12 @@ To use the [AsyncRunner] (an async-event loop) and related helpers, you must abl
 ```toml
 trovochat = { version = "0.14", features = ["async"] }
 ```
+
+The `std` feature is enabled by default. For `no_std` targets, turning off the default features builds just the [irc] parsing (and [maybe_owned]) using only `core` and `alloc`.
+
+```toml
+trovochat = { version = "0.14", default-features = false }
+```
 ---
 
 For trovo types:
@@ -63,6 +70,16 @@ For just encoding messages:
 
 */
 
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+            $item
+        )*
+    };
+}
+
 macro_rules! cfg_async {
     ($($item:item)*) => {
         $(
@@ -92,20 +109,28 @@ pub const TROVO_TLS_DOMAIN: &str = "irc.chat.trovo.tv";
 pub const ANONYMOUS_LOGIN: (&str, &str) = (JUSTINFAN1234, JUSTINFAN1234);
 pub(crate) const JUSTINFAN1234: &str = "justinfan1234";
 
+extern crate alloc;
+
 #[macro_use]
 #[allow(unused_macros)]
 mod macros;
 
-pub mod decoder;
-pub use decoder::{DecodeError, Decoder};
+cfg_std! {
+    pub mod decoder;
+    pub use decoder::{DecodeError, Decoder};
+}
 cfg_async! { pub use decoder::AsyncDecoder; }
 
-pub mod encoder;
-pub use encoder::Encoder;
+cfg_std! {
+    pub mod encoder;
+    pub use encoder::Encoder;
+}
 cfg_async! { pub use encoder::AsyncEncoder; }
 
-/// A boxed `Future` that is `Send + Sync`
-pub type BoxedFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + Sync>>;
+cfg_std! {
+    /// A boxed `Future` that is `Send + Sync`
+    pub type BoxedFuture<T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + Sync>>;
+}
 
 cfg_async! {
     /// An AsyncWriter over an MpscWriter
@@ -116,29 +141,38 @@ cfg_async! { pub mod connector; }
 cfg_async! { pub mod writer; }
 cfg_async! { pub mod channel; }
 
-pub mod runner;
-pub use runner::{Error as RunnerError, Status};
+cfg_std! {
+    pub mod runner;
+    pub use runner::{Error as RunnerError, Status, StatusKind};
+}
 cfg_async! { pub use runner::AsyncRunner; }
 
-pub mod rate_limit;
+cfg_std! {
+    pub mod clock;
+    pub mod rate_limit;
 
-pub mod commands;
-pub mod messages;
+    pub mod commands;
+    pub mod messages;
+}
 
 pub mod irc;
 pub use irc::{IrcMessage, MessageError};
 
-/// Helpful testing utilities
-pub mod test;
+cfg_std! {
+    /// Helpful testing utilities
+    pub mod test;
+}
 
 #[doc(inline)]
 pub use irc::{FromIrcMessage, IntoIrcMessage};
 
-pub mod trovo;
-pub use trovo::UserConfig;
+cfg_std! {
+    pub mod trovo;
+    pub use trovo::UserConfig;
 
-mod encodable;
-pub use encodable::Encodable;
+    mod encodable;
+    pub use encodable::Encodable;
+}
 
 pub mod maybe_owned;
 pub use maybe_owned::IntoOwned;
@@ -147,9 +181,12 @@ use maybe_owned::{MaybeOwned, MaybeOwnedIndex};
 mod validator;
 pub use validator::Validator;
 
-mod ext;
+cfg_std! {
+    mod ext;
+    mod util;
+
+    pub use ext::PrivmsgExt;
+}
+
 #[cfg(feature = "serde")]
 mod serde;
-mod util;
-
-pub use ext::PrivmsgExt;
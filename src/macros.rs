@@ -87,6 +87,9 @@ macro_rules! tags {
     };
 }
 
+// required fields are always set by the parser, from this same `raw`, so a bad index
+// can only come from a bug in this crate. these index directly, and panic on one,
+// rather than making every required accessor return an `Option`
 macro_rules! str_field {
     ($(#[$meta:meta])* $name:ident) => {
         $(#[$meta])*
@@ -105,13 +108,13 @@ macro_rules! opt_str_field {
     ($(#[$meta:meta])* $name:ident) => {
         $(#[$meta])*
         pub fn $name(&self) -> Option<&str> {
-            self.$name.map(|index| &self.raw[index])
+            self.$name.and_then(|index| self.raw.get(index))
         }
     };
 
     ($name:ident) => {
         pub fn $name(&self) -> Option<&str> {
-            self.$name.map(|index| &self.raw[index])
+            self.$name.and_then(|index| self.raw.get(index))
         }
     };
 }
@@ -1,5 +1,5 @@
 use super::{MaybeOwned, MaybeOwnedIndex};
-use crate::trovo::{Color, UserConfig};
+use alloc::string::ToString;
 
 /// Converts a **borrowed** type into an owned type. e.g. `'a` to `'static`
 pub trait IntoOwned<'a> {
@@ -26,17 +26,19 @@ impl IntoOwned<'static> for MaybeOwnedIndex {
     }
 }
 
-impl IntoOwned<'static> for Color {
-    type Output = Self;
-    fn into_owned(self) -> Self::Output {
-        self
+cfg_std! {
+    impl IntoOwned<'static> for crate::trovo::Color {
+        type Output = Self;
+        fn into_owned(self) -> Self::Output {
+            self
+        }
     }
-}
 
-impl IntoOwned<'static> for UserConfig {
-    type Output = Self;
-    fn into_owned(self) -> Self::Output {
-        self
+    impl IntoOwned<'static> for crate::UserConfig {
+        type Output = Self;
+        fn into_owned(self) -> Self::Output {
+            self
+        }
     }
 }
 
@@ -1,5 +1,5 @@
 use super::MaybeOwned;
-use std::ops::{Index, Range};
+use core::ops::{Index, Range};
 
 type IndexWidth = u16;
 
@@ -52,7 +52,7 @@ impl MaybeOwnedIndex {
 
     /// Replace this index with a new one start/ending at `pos`, returning the old index
     pub fn replace(&mut self, pos: usize) -> Self {
-        std::mem::replace(self, Self::new(pos))
+        core::mem::replace(self, Self::new(pos))
     }
 
     /// Checks whether this index is empty (e.g. start points to the dn)
@@ -71,6 +71,20 @@ impl MaybeOwnedIndex {
     pub const fn as_range(self) -> Range<usize> {
         (self.start as usize)..(self.end as usize)
     }
+
+    /// Get the slice of `data` at this index, if it is in bounds.
+    ///
+    /// Unlike indexing, this returns `None` instead of panicking if this index is
+    /// past the end of `data`, backwards, or not on a `char` boundary.
+    ///
+    /// ```
+    /// # use trovochat::maybe_owned::MaybeOwnedIndex;
+    /// assert_eq!(MaybeOwnedIndex::raw(0, 5).get("hello world"), Some("hello"));
+    /// assert_eq!(MaybeOwnedIndex::raw(6, 20).get("hello world"), None);
+    /// ```
+    pub fn get(self, data: &str) -> Option<&str> {
+        data.get(self.as_range())
+    }
 }
 
 impl<'a> Index<&MaybeOwnedIndex> for MaybeOwned<'a> {
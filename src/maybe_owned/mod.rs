@@ -1,9 +1,13 @@
-//! This is a [std::borrow::Cow] like type used in this crate.
+//! This is a [alloc::borrow::Cow] like type used in this crate.
 //!
 //! It is read-only unlike the std implementation.
 //!
 //! Its also specialized for just `str`
-use std::{fmt::Debug, ops::Deref};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::{fmt::Debug, ops::Deref};
 
 mod into_owned;
 pub use into_owned::IntoOwned;
@@ -36,6 +40,13 @@ impl<'a> MaybeOwned<'a> {
     pub fn is_borrowed(&self) -> bool {
         matches!(self, Self::Borrowed{..})
     }
+
+    /// Get the slice at this `index`, if it is in bounds.
+    ///
+    /// See [MaybeOwnedIndex::get]
+    pub fn get(&self, index: MaybeOwnedIndex) -> Option<&str> {
+        index.get(self.as_ref())
+    }
 }
 
 impl<'a> Clone for MaybeOwned<'a> {
@@ -48,7 +59,7 @@ impl<'a> Clone for MaybeOwned<'a> {
 }
 
 impl<'a> Debug for MaybeOwned<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.as_ref().fmt(f)
     }
 }
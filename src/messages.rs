@@ -57,6 +57,9 @@ pub use host_target::{HostTarget, HostTargetKind};
 mod join;
 pub use join::Join;
 
+mod names;
+pub use names::{Names, NamesAccumulator};
+
 mod notice;
 pub use notice::{MessageId, Notice};
 
@@ -70,7 +73,7 @@ mod pong;
 pub use pong::Pong;
 
 mod privmsg;
-pub use privmsg::Privmsg;
+pub use privmsg::{Privmsg, ReplyParent};
 
 mod reconnect;
 pub use reconnect::Reconnect;
@@ -78,8 +81,14 @@ pub use reconnect::Reconnect;
 mod room_state;
 pub use room_state::{FollowersOnly, RoomState};
 
+mod server_info;
+pub use server_info::ServerInfo;
+
+mod trovo_tags;
+pub use trovo_tags::TrovoTags;
+
 mod user_notice;
-pub use user_notice::{NoticeType, SubPlan, UserNotice};
+pub use user_notice::{NoticeType, SubPlan, UserNotice, UserNoticeEvent};
 
 mod user_state;
 pub use user_state::UserState;
@@ -28,7 +28,11 @@ impl<'a> Cap<'a> {
     raw!();
 
     /// The parsed capability
+    ///
+    /// If more than one capability was requested at once, this is all of them.
+    /// Use [Cap::capabilities()] to get each one.
     pub fn capability(&self) -> Capability<'_> {
+        // this is a required field, see `str_field!`
         let cap = &self.raw[self.capability];
         if self.acknowledged {
             Capability::Acknowledged(cap)
@@ -36,6 +40,22 @@ impl<'a> Cap<'a> {
             Capability::NotAcknowledged(cap)
         }
     }
+
+    /// Each of the capabilities in this message
+    ///
+    /// Trovo answers a request for multiple capabilities with a single message, which lists all of them.
+    pub fn capabilities(&self) -> impl Iterator<Item = Capability<'_>> + '_ {
+        let acknowledged = self.acknowledged;
+        self.raw[self.capability]
+            .split_whitespace()
+            .map(move |cap| {
+                if acknowledged {
+                    Capability::Acknowledged(cap)
+                } else {
+                    Capability::NotAcknowledged(cap)
+                }
+            })
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for Cap<'a> {
@@ -96,6 +116,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cap_multiple() {
+        let input =
+            ":tmi.trovo.tv CAP * ACK :trovo.tv/membership trovo.tv/tags trovo.tv/commands\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Cap::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.capabilities().collect::<Vec<_>>(),
+                vec![
+                    Capability::Acknowledged("trovo.tv/membership"),
+                    Capability::Acknowledged("trovo.tv/tags"),
+                    Capability::Acknowledged("trovo.tv/commands"),
+                ]
+            );
+        }
+
+        let input = ":tmi.trovo.tv CAP * NAK :trovo.tv/tags foobar\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Cap::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.capabilities().collect::<Vec<_>>(),
+                vec![
+                    Capability::NotAcknowledged("trovo.tv/tags"),
+                    Capability::NotAcknowledged("foobar"),
+                ]
+            );
+        }
+    }
+
     #[test]
     fn cap_failed() {
         let input = ":tmi.trovo.tv CAP * NAK :foobar\r\n";
@@ -104,4 +153,23 @@ mod tests {
             assert_eq!(cap.capability(), Capability::NotAcknowledged("foobar"));
         }
     }
+
+    #[test]
+    fn cap_missing_trailing() {
+        let input = ":tmi.trovo.tv CAP * ACK\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            assert!(matches!(
+                Cap::from_irc(msg).unwrap_err(),
+                MessageError::ExpectedData
+            ));
+        }
+
+        let input = ":tmi.trovo.tv CAP :trovo.tv/tags\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            assert!(matches!(
+                Cap::from_irc(msg).unwrap_err(),
+                MessageError::ExpectedArg { pos: 1 }
+            ));
+        }
+    }
 }
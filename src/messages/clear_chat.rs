@@ -54,6 +54,22 @@ impl<'a> FromIrcMessage<'a> for ClearChat<'a> {
     into_inner_raw!();
 }
 
+impl<'a> std::fmt::Display for ClearChat<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.name(), self.ban_duration()) {
+            (Some(name), Some(duration)) => write!(
+                f,
+                "{} timed out for {}s in {}",
+                name,
+                duration,
+                self.channel()
+            ),
+            (Some(name), None) => write!(f, "{} banned in {}", name, self.channel()),
+            (None, _) => write!(f, "{} chat cleared", self.channel()),
+        }
+    }
+}
+
 into_owned!(ClearChat {
     raw,
     tags,
@@ -108,4 +124,26 @@ mod tests {
             assert!(cc.name().is_none());
         }
     }
+
+    #[test]
+    fn clear_chat_display() {
+        let input =
+            "@ban-duration=600;room-id=23196011 :tmi.trovo.tv CLEARCHAT #museun :shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let cc = ClearChat::from_irc(msg).unwrap();
+            assert_eq!(cc.to_string(), "shaken_bot timed out for 600s in #museun");
+        }
+
+        let input = "@room-id=23196011 :tmi.trovo.tv CLEARCHAT #museun :shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let cc = ClearChat::from_irc(msg).unwrap();
+            assert_eq!(cc.to_string(), "shaken_bot banned in #museun");
+        }
+
+        let input = "@room-id=23196011 :tmi.trovo.tv CLEARCHAT #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let cc = ClearChat::from_irc(msg).unwrap();
+            assert_eq!(cc.to_string(), "#museun chat cleared");
+        }
+    }
 }
@@ -39,6 +39,8 @@ pub enum Commands<'a> {
     Reconnect(Reconnect<'a>),
     /// A RoomState event occured
     RoomState(RoomState<'a>),
+    /// A ServerInfo event occured
+    ServerInfo(ServerInfo<'a>),
     /// A UserNotice event occured
     UserNotice(UserNotice<'a>),
     /// A UserState event occured
@@ -67,11 +69,71 @@ impl<'a> Commands<'a> {
             Self::Privmsg(msg) => msg.raw(),
             Self::Reconnect(msg) => msg.raw(),
             Self::RoomState(msg) => msg.raw(),
+            Self::ServerInfo(msg) => msg.raw(),
             Self::UserNotice(msg) => msg.raw(),
             Self::UserState(msg) => msg.raw(),
             Self::Whisper(msg) => msg.raw(),
         }
     }
+
+    /// Get the channel this message is for, if it has one
+    ///
+    /// For a [HostTarget] this is the channel doing the hosting.
+    pub fn channel(&'a self) -> Option<&'a str> {
+        let channel = match self {
+            Self::ClearChat(msg) => msg.channel(),
+            Self::ClearMsg(msg) => msg.channel(),
+            Self::HostTarget(msg) => msg.source(),
+            Self::Join(msg) => msg.channel(),
+            Self::Notice(msg) => msg.channel(),
+            Self::Part(msg) => msg.channel(),
+            Self::Privmsg(msg) => msg.channel(),
+            Self::RoomState(msg) => msg.channel(),
+            Self::UserNotice(msg) => msg.channel(),
+            Self::UserState(msg) => msg.channel(),
+            _ => return None,
+        };
+        Some(channel)
+    }
+}
+
+macro_rules! as_variant {
+    ($($func:ident => $ident:ident)*) => {
+        impl<'a> Commands<'a> {
+            $(
+                #[doc = concat!("Get this as a [", stringify!($ident), "], if it is one")]
+                pub fn $func(&self) -> Option<&$ident<'a>> {
+                    match self {
+                        Self::$ident(msg) => Some(msg),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+    };
+}
+
+as_variant! {
+    as_raw => Raw
+    as_irc_ready => IrcReady
+    as_ready => Ready
+    as_cap => Cap
+    as_clear_chat => ClearChat
+    as_clear_msg => ClearMsg
+    as_global_user_state => GlobalUserState
+    as_host_target => HostTarget
+    as_join => Join
+    as_notice => Notice
+    as_part => Part
+    as_ping => Ping
+    as_pong => Pong
+    as_privmsg => Privmsg
+    as_reconnect => Reconnect
+    as_room_state => RoomState
+    as_server_info => ServerInfo
+    as_user_notice => UserNotice
+    as_user_state => UserState
+    as_whisper => Whisper
 }
 
 impl<'a> IntoOwned<'a> for Commands<'a> {
@@ -95,6 +157,7 @@ impl<'a> IntoOwned<'a> for Commands<'a> {
             Self::Privmsg(s) => Commands::Privmsg(s.into_owned()),
             Self::Reconnect(s) => Commands::Reconnect(s.into_owned()),
             Self::RoomState(s) => Commands::RoomState(s.into_owned()),
+            Self::ServerInfo(s) => Commands::ServerInfo(s.into_owned()),
             Self::UserNotice(s) => Commands::UserNotice(s.into_owned()),
             Self::UserState(s) => Commands::UserState(s.into_owned()),
             Self::Whisper(s) => Commands::Whisper(s.into_owned()),
@@ -129,6 +192,7 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             M::PRIVMSG => map!(Privmsg),
             M::RECONNECT => map!(Reconnect),
             M::ROOM_STATE => map!(RoomState),
+            M::YOUR_HOST | M::CREATED | M::MY_INFO | M::MOTD_START => map!(ServerInfo),
             M::USER_NOTICE => map!(UserNotice),
             M::USER_STATE => map!(UserState),
             M::WHISPER => map!(Whisper),
@@ -157,6 +221,7 @@ impl<'a> FromIrcMessage<'a> for Commands<'a> {
             Self::Privmsg(msg) => msg.into_inner(),
             Self::Reconnect(msg) => msg.into_inner(),
             Self::RoomState(msg) => msg.into_inner(),
+            Self::ServerInfo(msg) => msg.into_inner(),
             Self::UserNotice(msg) => msg.into_inner(),
             Self::UserState(msg) => msg.into_inner(),
             Self::Whisper(msg) => msg.into_inner(),
@@ -193,6 +258,7 @@ from_other! {
     Privmsg
     Reconnect
     RoomState
+    ServerInfo
     UserNotice
     UserState
     Whisper
@@ -217,4 +283,78 @@ mod tests {
         let all = Commands::from_irc(msg).unwrap();
         assert!(matches!(all, Commands::Privmsg{..}));
     }
+
+    #[test]
+    fn as_privmsg() {
+        let input = ":test!test@test PRIVMSG #museun :this is a test\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let all = Commands::from_irc(msg).unwrap();
+        let pm = all.as_privmsg().unwrap();
+        assert_eq!(pm.data(), "this is a test");
+        assert!(all.as_join().is_none());
+        assert!(all.as_raw().is_none());
+
+        let input = ":test!test@test JOIN #museun\r\n";
+        let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+        let all = Commands::from_irc(msg).unwrap();
+        assert!(all.as_privmsg().is_none());
+        assert_eq!(all.as_join().unwrap().channel(), "#museun");
+    }
+
+    #[test]
+    fn channel() {
+        let tests = &[
+            (
+                ":tmi.trovo.tv CLEARCHAT #museun :shaken_bot\r\n",
+                Some("#museun"),
+            ),
+            (
+                "@login=shaken_bot;target-msg-id=abc :tmi.trovo.tv CLEARMSG #museun :hello\r\n",
+                Some("#museun"),
+            ),
+            (
+                ":tmi.trovo.tv HOSTTARGET #shaken_bot :museun 1024\r\n",
+                Some("#shaken_bot"),
+            ),
+            (":test!test@test JOIN #museun\r\n", Some("#museun")),
+            (
+                ":tmi.trovo.tv NOTICE #museun :This room is now in slow mode.\r\n",
+                Some("#museun"),
+            ),
+            (":test!test@test PART #museun\r\n", Some("#museun")),
+            (
+                ":test!test@test PRIVMSG #museun :this is a test\r\n",
+                Some("#museun"),
+            ),
+            (":tmi.trovo.tv ROOMSTATE #museun\r\n", Some("#museun")),
+            (":tmi.trovo.tv USERNOTICE #museun\r\n", Some("#museun")),
+            (":tmi.trovo.tv USERSTATE #museun\r\n", Some("#museun")),
+            (":tmi.trovo.tv 001 museun :Welcome, GLHF!\r\n", None),
+            (":tmi.trovo.tv 376 museun :>\r\n", None),
+            (":tmi.trovo.tv CAP * ACK :trovo.tv/tags\r\n", None),
+            (":tmi.trovo.tv GLOBALUSERSTATE\r\n", None),
+            ("PING :123456789\r\n", None),
+            ("PONG :123456789\r\n", None),
+            (":tmi.trovo.tv RECONNECT\r\n", None),
+            (":tmi.trovo.tv 002 museun :-\r\n", None),
+            (":test!test@test WHISPER museun :hello\r\n", None),
+            (":tmi.trovo.tv FOO #museun\r\n", None),
+        ];
+
+        for (input, expected) in tests {
+            let msg = IrcMessage::parse(MaybeOwned::Borrowed(input)).unwrap();
+            let all = Commands::from_irc(msg).unwrap();
+            assert_eq!(all.channel(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn server_info_numerics() {
+        for numeric in &["002", "003", "004", "375"] {
+            let input = format!(":tmi.trovo.tv {} museun :-\r\n", numeric);
+            let msg = IrcMessage::parse(MaybeOwned::Borrowed(&input)).unwrap();
+            let all = Commands::from_irc(msg).unwrap();
+            assert!(matches!(all, Commands::ServerInfo{..}));
+        }
+    }
 }
@@ -60,12 +60,15 @@ impl<'a> GlobalUserState<'a> {
     pub fn color(&self) -> Color {
         self.color
     }
-}
 
-impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
-    type Error = MessageError;
+    /// Like [FromIrcMessage::from_irc], but an invalid `color` tag is parsed with [Color::parse_strict]
+    ///
+    /// The [MessageError::CannotParseTag] then says why the color was invalid.
+    pub fn from_irc_strict(msg: IrcMessage<'a>) -> Result<Self, MessageError> {
+        Self::parse(msg, true)
+    }
 
-    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+    fn parse(msg: IrcMessage<'a>, strict: bool) -> Result<Self, MessageError> {
         msg.expect_command(IrcMessage::GLOBAL_USER_STATE)?;
 
         let tag_index = msg.parse_tags();
@@ -87,7 +90,13 @@ impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
         let color = tags
             .get("color")
             .filter(|s| !s.is_empty())
-            .map(std::str::FromStr::from_str)
+            .map(|s| {
+                if strict {
+                    Color::parse_strict(s)
+                } else {
+                    s.parse()
+                }
+            })
             .transpose()
             .map_err(|err| MessageError::CannotParseTag {
                 name: "color".into(),
@@ -105,6 +114,14 @@ impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
 
         Ok(this)
     }
+}
+
+impl<'a> FromIrcMessage<'a> for GlobalUserState<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        Self::parse(msg, false)
+    }
 
     into_inner_raw!();
 }
@@ -176,6 +193,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn global_user_state_invalid_color() {
+        let input = "@badge-info=;badges=;color=#FF69BZ;display-name=shaken_bot;emote-sets=0;user-id=241015868;user-type= :tmi.trovo.tv GLOBALUSERSTATE\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let color_error = |err| match err {
+                MessageError::CannotParseTag { name, error } if name == "color" => error,
+                err => panic!("unexpected error: {}", err),
+            };
+
+            let error = color_error(GlobalUserState::from_irc(msg.clone()).unwrap_err());
+            assert!(matches!(
+                error.downcast_ref(),
+                Some(color::ParseError::InvalidHexString)
+            ));
+
+            let error = color_error(GlobalUserState::from_irc_strict(msg).unwrap_err());
+            assert!(matches!(
+                error.downcast_ref(),
+                Some(color::ParseError::InvalidHexDigit { digit: 'Z' })
+            ));
+        }
+    }
+
     #[test]
     fn global_user_state_empty_color() {
         let input = "@badge-info=;badges=;color=;display-name=shaken_bot;emote-sets=0;user-id=241015868;user-type= :tmi.trovo.tv GLOBALUSERSTATE\r\n";
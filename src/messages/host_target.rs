@@ -1,16 +1,23 @@
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 /// Event kind for determine when a Host event beings or end
-#[derive(Debug, Clone, PartialEq)]
+///
+/// An ending host is sent as `:- <viewers>`, and the viewer count may be missing.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub enum HostTargetKind<'a> {
     /// The host event started
     Start {
         /// Target channel that is being hosted
         target: &'a str,
+        /// How many viewers are going along, if provided
+        viewers: Option<usize>,
     },
     /// The host event ended
-    End,
+    End {
+        /// How many viewers were being hosted, if provided
+        viewers: Option<usize>,
+    },
 }
 
 /// When a channel starts to host another channel
@@ -34,15 +41,39 @@ impl<'a> HostTarget<'a> {
         self.viewers
     }
 
-    /// What kind of event this was. e.g. `Start` or `End`
-    pub fn host_target_kind(&self) -> HostTargetKind<'_> {
+    /// How many viewers are going along, if provided
+    pub fn viewer_count(&self) -> Option<u64> {
+        self.viewers.map(|viewers| viewers as u64)
+    }
+
+    /// The channel being hosted, or `None` if this ends hosting
+    pub fn hosting(&self) -> Option<&str> {
+        self.target.and_then(|index| self.raw.get(index))
+    }
+
+    /// What kind of event this was, e.g. `Start` or `End`, with the viewer count sent with it
+    pub fn kind(&self) -> HostTargetKind<'_> {
+        // the target was parsed from this same `raw`, like the required fields in `str_field!`
         match self.target {
             Some(index) => HostTargetKind::Start {
                 target: &self.raw[index],
+                viewers: self.viewers,
+            },
+            None => HostTargetKind::End {
+                viewers: self.viewers,
             },
-            None => HostTargetKind::End,
         }
     }
+
+    /// Whether this message starts hosting a channel
+    pub fn is_hosting(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Whether this message stops hosting a channel
+    pub fn is_ending(&self) -> bool {
+        self.target.is_none()
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for HostTarget<'a> {
@@ -52,19 +83,27 @@ impl<'a> FromIrcMessage<'a> for HostTarget<'a> {
         msg.expect_command(IrcMessage::HOST_TARGET)?;
 
         // [- | <target>] number?
-
-        let mut data = msg.expect_data()?.splitn(2, char::is_whitespace);
-        let target = match data.next() {
-            Some("-") => None,
-            Some(t) => {
-                let kind = msg.expect_data_index()?.resize(t.len());
-                Some(kind)
+        let (target, viewers) = match msg.get_data() {
+            Some(data) => {
+                let mut data = data.splitn(2, char::is_whitespace);
+                let target = match data.next() {
+                    Some("-") => None,
+                    Some(t) => {
+                        let kind = msg.expect_data_index()?.resize(t.len());
+                        Some(kind)
+                    }
+                    None => return Err(MessageError::ExpectedData),
+                };
+                (target, data.next().and_then(|s| s.trim().parse().ok()))
+            }
+            // without the trailing data, they're sent as args
+            None => {
+                let target = msg.expect_arg_index(1)?;
+                let target = Some(target).filter(|&index| &msg.raw[index] != "-");
+                (target, msg.nth_arg(2).and_then(|s| s.parse().ok()))
             }
-            None => return Err(MessageError::ExpectedData),
         };
 
-        let viewers = data.next().and_then(|s| s.parse().ok());
-
         // TODO assert iterator is empty?
 
         let this = Self {
@@ -91,14 +130,14 @@ impl_custom_debug!(HostTarget {
     raw,
     source,
     viewers,
-    host_target_kind,
+    kind,
 });
 
 serde_struct!(HostTarget {
     raw,
     source,
     viewers,
-    host_target_kind
+    kind
 });
 
 #[cfg(test)]
@@ -121,8 +160,11 @@ mod tests {
             assert_eq!(ht.source(), "#shaken_bot");
             assert_eq!(ht.viewers().unwrap(), 1024);
             assert_eq!(
-                ht.host_target_kind(),
-                HostTargetKind::Start { target: "museun" }
+                ht.kind(),
+                HostTargetKind::Start {
+                    target: "museun",
+                    viewers: Some(1024)
+                }
             );
         }
     }
@@ -135,8 +177,11 @@ mod tests {
             assert_eq!(ht.source(), "#shaken_bot");
             assert!(ht.viewers().is_none());
             assert_eq!(
-                ht.host_target_kind(),
-                HostTargetKind::Start { target: "museun" }
+                ht.kind(),
+                HostTargetKind::Start {
+                    target: "museun",
+                    viewers: None
+                }
             );
         }
     }
@@ -148,7 +193,95 @@ mod tests {
             let ht = HostTarget::from_irc(msg).unwrap();
             assert_eq!(ht.source(), "#shaken_bot");
             assert_eq!(ht.viewers().unwrap(), 1024);
-            assert_eq!(ht.host_target_kind(), HostTargetKind::End);
+            assert_eq!(
+                ht.kind(),
+                HostTargetKind::End {
+                    viewers: Some(1024)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn host_target_shapes() {
+        let tests: &[(&str, Option<&str>, Option<usize>)] = &[
+            (":museun 42", Some("museun"), Some(42)),
+            (":- 42", None, Some(42)),
+            (":museun", Some("museun"), None),
+            (":-", None, None),
+            // the same, but as args instead of the trailing data
+            ("#museun 42", Some("#museun"), Some(42)),
+            ("- 42", None, Some(42)),
+            ("#museun", Some("#museun"), None),
+        ];
+
+        for (tail, hosting, viewers) in tests {
+            let input = format!(":tmi.trovo.tv HOSTTARGET #shaken_bot {}\r\n", tail);
+            for msg in parse(&input).map(|s| s.unwrap()) {
+                let ht = HostTarget::from_irc(msg).unwrap();
+                assert_eq!(ht.source(), "#shaken_bot", "{}", input);
+                assert_eq!(ht.hosting(), *hosting, "{}", input);
+                assert_eq!(ht.viewer_count(), viewers.map(|v| v as u64), "{}", input);
+                let viewers = *viewers;
+                let kind = match hosting {
+                    Some(target) => HostTargetKind::Start { target, viewers },
+                    None => HostTargetKind::End { viewers },
+                };
+                assert_eq!(ht.kind(), kind, "{}", input);
+            }
+        }
+
+        let input = ":tmi.trovo.tv HOSTTARGET #shaken_bot\r\n";
+        let msg = parse(input).next().unwrap().unwrap();
+        assert!(HostTarget::from_irc(msg).is_err());
+    }
+
+    #[test]
+    fn host_target_out_of_range() {
+        let input = ":tmi.trovo.tv HOSTTARGET #shaken_bot :museun 1024\r\n";
+        let msg = parse(input).next().unwrap().unwrap();
+        let mut ht = HostTarget::from_irc(msg).unwrap();
+        ht.target = Some(MaybeOwnedIndex::raw(input.len() - 2, input.len() + 10));
+        assert_eq!(ht.hosting(), None);
+    }
+
+    #[test]
+    fn host_target_kind_start() {
+        let input = ":tmi.trovo.tv HOSTTARGET #shaken_bot :museun 1024\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let ht = HostTarget::from_irc(msg).unwrap();
+            assert!(ht.is_hosting());
+            assert!(!ht.is_ending());
+            assert_eq!(
+                ht.kind(),
+                HostTargetKind::Start {
+                    target: "museun",
+                    viewers: Some(1024)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn host_target_kind_end() {
+        let input = ":tmi.trovo.tv HOSTTARGET #shaken_bot :- 1024\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let ht = HostTarget::from_irc(msg).unwrap();
+            assert!(ht.is_ending());
+            assert!(!ht.is_hosting());
+            assert_eq!(
+                ht.kind(),
+                HostTargetKind::End {
+                    viewers: Some(1024)
+                }
+            );
+        }
+
+        let input = ":tmi.trovo.tv HOSTTARGET #shaken_bot :- \r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let ht = HostTarget::from_irc(msg).unwrap();
+            assert!(ht.is_ending());
+            assert_eq!(ht.kind(), HostTargetKind::End { viewers: None });
         }
     }
 }
@@ -0,0 +1,208 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+use std::collections::HashMap;
+
+/// A list of users on a channel -- either a `353` or the `366` ending the list
+///
+/// Trovo can split the list over several `353`s, use a [NamesAccumulator] to collect them.
+#[derive(Clone, PartialEq)]
+pub struct Names<'a> {
+    raw: MaybeOwned<'a>,
+    name: MaybeOwnedIndex,
+    channel: MaybeOwnedIndex,
+    users: Option<MaybeOwnedIndex>,
+}
+
+impl<'a> Names<'a> {
+    raw!();
+    str_field!(
+        /// Your username
+        name
+    );
+    str_field!(
+        /// The channel the users are on
+        channel
+    );
+
+    /// The users in this part of the list. This is empty for the end of the list.
+    pub fn users(&self) -> Vec<&str> {
+        self.users
+            .and_then(|index| self.raw.get(index))
+            .map(|users| users.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether this is the end of the list (a `366`)
+    pub fn is_end(&self) -> bool {
+        self.users.is_none()
+    }
+}
+
+impl<'a> FromIrcMessage<'a> for Names<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        // :tmi.trovo.tv 353 museun = #museun :shaken_bot museun
+        // :tmi.trovo.tv 366 museun #museun :End of /NAMES list
+        let (channel, users) = match msg.get_command() {
+            IrcMessage::END_OF_NAMES => (msg.expect_arg_index(1)?, None),
+            _ => {
+                msg.expect_command(IrcMessage::NAMES)?;
+                (msg.expect_arg_index(2)?, Some(msg.expect_data_index()?))
+            }
+        };
+
+        let this = Self {
+            name: msg.expect_arg_index(0)?,
+            channel,
+            users,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(Names {
+    raw,
+    name,
+    channel,
+    users,
+});
+
+impl_custom_debug!(Names {
+    raw,
+    name,
+    channel,
+    users,
+    is_end,
+});
+
+serde_struct!(Names {
+    raw,
+    name,
+    channel,
+    users,
+    is_end,
+});
+
+/// Collects the users from [Names] messages until the list for a channel is complete
+///
+/// ```
+/// # use trovochat::{irc, FromIrcMessage as _, messages::{Names, NamesAccumulator}};
+/// let input = ":tmi.trovo.tv 353 museun = #museun :museun shaken_bot\r\n\
+///              :tmi.trovo.tv 366 museun #museun :End of /NAMES list\r\n";
+///
+/// let mut names = NamesAccumulator::default();
+/// let mut done = None;
+/// for msg in irc::parse(input).map(|msg| msg.unwrap()) {
+///     done = names.feed(&Names::from_irc(msg).unwrap());
+/// }
+/// assert_eq!(done.unwrap(), ("#museun".to_string(), vec!["museun".to_string(), "shaken_bot".to_string()]));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct NamesAccumulator {
+    pending: HashMap<String, Vec<String>>,
+}
+
+impl NamesAccumulator {
+    /// Feed a [Names] message to this.
+    ///
+    /// When it ends the list for a channel, this returns the channel and every user on it.
+    pub fn feed(&mut self, msg: &Names<'_>) -> Option<(String, Vec<String>)> {
+        if msg.is_end() {
+            let users = self.pending.remove(msg.channel()).unwrap_or_default();
+            return Some((msg.channel().to_string(), users));
+        }
+
+        self.pending
+            .entry(msg.channel().to_string())
+            .or_default()
+            .extend(msg.users().into_iter().map(ToString::to_string));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn names_serde() {
+        let input = &[
+            ":justinfan1234.tmi.trovo.tv 353 justinfan1234 = #museun :museun shaken_bot\r\n",
+            ":justinfan1234.tmi.trovo.tv 366 justinfan1234 #museun :End of /NAMES list\r\n",
+        ];
+        for input in input {
+            crate::serde::round_trip_json::<Names>(input);
+            crate::serde::round_trip_rmp::<Names>(input);
+        }
+    }
+
+    #[test]
+    fn names() {
+        let input =
+            ":justinfan1234.tmi.trovo.tv 353 justinfan1234 = #museun :museun shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Names::from_irc(msg).unwrap();
+            assert_eq!(msg.name(), "justinfan1234");
+            assert_eq!(msg.channel(), "#museun");
+            assert_eq!(msg.users(), vec!["museun", "shaken_bot"]);
+            assert!(!msg.is_end());
+        }
+
+        let input = ":justinfan1234.tmi.trovo.tv 366 justinfan1234 #museun :End of /NAMES list\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Names::from_irc(msg).unwrap();
+            assert_eq!(msg.channel(), "#museun");
+            assert!(msg.users().is_empty());
+            assert!(msg.is_end());
+        }
+    }
+
+    #[test]
+    fn names_accumulator() {
+        let input =
+            ":justinfan1234.tmi.trovo.tv 353 justinfan1234 = #museun :museun shaken_bot\r\n\
+                     :justinfan1234.tmi.trovo.tv 353 justinfan1234 = #shaken_bot :justinfan1234\r\n\
+                     :justinfan1234.tmi.trovo.tv 353 justinfan1234 = #museun :justinfan1234 foo\r\n\
+                     :justinfan1234.tmi.trovo.tv 366 justinfan1234 #museun :End of /NAMES list\r\n";
+
+        let mut names = NamesAccumulator::default();
+        let done = parse(input)
+            .map(|s| Names::from_irc(s.unwrap()).unwrap())
+            .filter_map(|msg| names.feed(&msg))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            done,
+            vec![(
+                "#museun".to_string(),
+                vec![
+                    "museun".to_string(),
+                    "shaken_bot".to_string(),
+                    "justinfan1234".to_string(),
+                    "foo".to_string()
+                ]
+            )]
+        );
+
+        // #shaken_bot's list hasn't ended yet
+        let input =
+            ":justinfan1234.tmi.trovo.tv 366 justinfan1234 #shaken_bot :End of /NAMES list\r\n";
+        let msg = Names::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            names.feed(&msg).unwrap(),
+            ("#shaken_bot".to_string(), vec!["justinfan1234".to_string()])
+        );
+    }
+
+    #[test]
+    fn names_wrong_command() {
+        let input = ":test!test@test PART #museun\r\n";
+        let msg = parse(input).next().unwrap().unwrap();
+        assert!(Names::from_irc(msg).is_err());
+    }
+}
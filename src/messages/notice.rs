@@ -29,6 +29,13 @@ impl<'a> Notice<'a> {
     pub fn msg_id(&self) -> Option<MessageId<'_>> {
         self.tags().get("msg-id").map(MessageId::parse)
     }
+
+    /// Whether this notice is about a whisper you sent failing
+    ///
+    /// See [MessageId::is_whisper_rate_limit()] for whether you should back off.
+    pub fn is_whisper_error(&self) -> bool {
+        matches!(self.msg_id(), Some(id) if id.is_whisper_error())
+    }
 }
 
 impl<'a> FromIrcMessage<'a> for Notice<'a> {
@@ -432,6 +439,28 @@ pub enum MessageId<'a> {
 }
 
 impl<'a> MessageId<'a> {
+    /// Whether this is an error from sending a whisper
+    pub fn is_whisper_error(&self) -> bool {
+        use MessageId::*;
+        matches!(
+            self,
+            WhisperBanned
+                | WhisperBannedRecipient
+                | WhisperInvalidArgs
+                | WhisperInvalidLogin
+                | WhisperInvalidSelf
+                | WhisperLimitPerMin
+                | WhisperLimitPerSec
+                | WhisperRestricted
+                | WhisperRestrictedRecipient
+        )
+    }
+
+    /// Whether you're sending whispers too fast, and should back off
+    pub fn is_whisper_rate_limit(&self) -> bool {
+        matches!(self, Self::WhisperLimitPerMin | Self::WhisperLimitPerSec)
+    }
+
     pub(super) fn parse(input: &'a str) -> MessageId<'a> {
         use MessageId::*;
         match input {
@@ -605,4 +634,57 @@ mod tests {
             assert_eq!(msg.message(), "This room is no longer in slow mode.");
         }
     }
+
+    #[test]
+    fn notice_msg_id() {
+        let input = "@msg-id=msg_banned :tmi.trovo.tv NOTICE #museun :You are permanently banned from talking in museun.\r\n\
+                     @msg-id=host_on :tmi.trovo.tv NOTICE #museun :Now hosting shaken_bot.\r\n\
+                     @msg-id=already_emote_only_on :tmi.trovo.tv NOTICE #museun :This room is already in emote-only mode.\r\n\
+                     @msg-id=followers_on_zero :tmi.trovo.tv NOTICE #museun :This room is now in followers-only mode.\r\n\
+                     @msg-id=some_new_thing :tmi.trovo.tv NOTICE #museun :Something new.\r\n\
+                     :tmi.trovo.tv NOTICE #museun :No id here.\r\n";
+        let expected = &[
+            Some(MessageId::MsgBanned),
+            Some(MessageId::HostOn),
+            Some(MessageId::AlreadyEmoteOnlyOn),
+            Some(MessageId::FollowersOnZero),
+            Some(MessageId::Unknown("some_new_thing")),
+            None,
+        ];
+
+        let msgs = parse(input).map(|s| s.unwrap()).collect::<Vec<_>>();
+        assert_eq!(msgs.len(), expected.len());
+        for (msg, expected) in msgs.into_iter().zip(expected) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert_eq!(msg.msg_id(), *expected);
+        }
+    }
+
+    #[test]
+    fn notice_whisper_errors() {
+        let input = "@msg-id=whisper_limit_per_sec :tmi.trovo.tv NOTICE #jtv :You are sending whispers too fast. Try again in a second.\r\n\
+                     @msg-id=whisper_limit_per_min :tmi.trovo.tv NOTICE #jtv :You are sending whispers too fast. Try again in a minute.\r\n";
+        let expected = &[MessageId::WhisperLimitPerSec, MessageId::WhisperLimitPerMin];
+
+        for (msg, expected) in parse(input).map(|s| s.unwrap()).zip(expected) {
+            let msg = Notice::from_irc(msg).unwrap();
+            let msg_id = msg.msg_id().unwrap();
+            assert_eq!(msg_id, *expected);
+            assert!(msg_id.is_whisper_rate_limit());
+            assert!(msg.is_whisper_error());
+        }
+
+        let input = "@msg-id=whisper_restricted_recipient :tmi.trovo.tv NOTICE #jtv :That user's settings prevent them from receiving this whisper.\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert!(msg.is_whisper_error());
+            assert!(!msg.msg_id().unwrap().is_whisper_rate_limit());
+        }
+
+        let input = "@msg-id=slow_off :tmi.trovo.tv NOTICE #museun :This room is no longer in slow mode.\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Notice::from_irc(msg).unwrap();
+            assert!(!msg.is_whisper_error());
+        }
+    }
 }
@@ -1,7 +1,8 @@
 use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
 
 use crate::trovo::{
-    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeInfo, BadgeKind, Color, Emotes,
+    parse_badges, parse_badges_iter, parse_emotes, Badge, BadgeInfo, BadgeKind, Color, Emote,
+    Emotes,
 };
 
 /// Some PRIVMSGs are considered 'CTCP' (client-to-client protocol)
@@ -66,6 +67,21 @@ impl<'a> Iterator for EmotesIter<'a> {
     }
 }
 
+/// The message a [Privmsg] is directly replying to
+///
+/// This is built from the `reply-parent-*` tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyParent<'a> {
+    /// The unique id of the parent message
+    pub msg_id: &'a str,
+    /// The login of the user who sent the parent message
+    pub user_login: &'a str,
+    /// The display name of the user who sent the parent message
+    pub display_name: Option<&'a str>,
+    /// The body of the parent message, with the tag escaping removed
+    pub msg_body: MaybeOwned<'a>,
+}
+
 impl<'a> Privmsg<'a> {
     raw!();
     tags!();
@@ -82,6 +98,34 @@ impl<'a> Privmsg<'a> {
         data
     );
 
+    /// Whether this has the same content as `other`: the same channel, name and data.
+    ///
+    /// Unlike `==`, this ignores the tags, which differ between e.g. a sent message and its echo (`id`, `tmi-sent-ts`).
+    pub fn same_content(&self, other: &Privmsg<'_>) -> bool {
+        self.channel() == other.channel()
+            && self.name() == other.name()
+            && self.data() == other.data()
+    }
+
+    /// The usernames `@`-mentioned in the data, without the `@`. e.g. `@museun, hi` yields `museun`
+    ///
+    /// Only an `@` at the start of a word counts, so `user@example.com` isn't a mention.
+    pub fn mentions(&self) -> impl Iterator<Item = &str> + '_ {
+        fn is_name(c: char) -> bool {
+            c.is_ascii_alphanumeric() || c == '_'
+        }
+
+        let data = self.data();
+        data.match_indices('@').filter_map(move |(pos, _)| {
+            if data[..pos].ends_with(is_name) {
+                return None;
+            }
+            let name = &data[pos + 1..];
+            let end = name.find(|c| !is_name(c)).unwrap_or(name.len());
+            Some(&name[..end]).filter(|name| !name.is_empty())
+        })
+    }
+
     /// Iterator alternative to `Privmsg::badges()`
     pub fn iter_badges(&self) -> BadgesIter {
         BadgesIter {
@@ -99,7 +143,7 @@ impl<'a> Privmsg<'a> {
     /// Gets the 'CTCP' kind associated with this message, if any
     pub fn ctcp(&self) -> Option<Ctcp<'_>> {
         const ACTION: &str = "ACTION";
-        let command = &self.raw[self.ctcp?];
+        let command = self.raw.get(self.ctcp?)?;
         if command == ACTION {
             Some(Ctcp::Action)
         } else {
@@ -131,6 +175,15 @@ impl<'a> Privmsg<'a> {
             .unwrap_or_default()
     }
 
+    /// Get the version (the associated data) of the badge of this `kind`, if the user has it
+    ///
+    /// For example, the `subscriber` badge's version is the tier badge for the number of months subscribed.
+    pub fn badge_version(&self, kind: &BadgeKind<'_>) -> Option<&str> {
+        self.iter_badges()
+            .find(|badge| badge.kind == *kind)
+            .map(|badge| badge.data)
+    }
+
     /// How many bits were attached to this message
     pub fn bits(&self) -> Option<u64> {
         self.tags().get_parsed("bits")
@@ -162,6 +215,13 @@ impl<'a> Privmsg<'a> {
             .unwrap_or_default()
     }
 
+    /// Emotes attached to this message, with their string ids and `char` ranges into [Privmsg::data()]
+    ///
+    /// This is empty if there were no emotes. Malformed emotes are skipped.
+    pub fn emote_ranges(&self) -> impl Iterator<Item = Emote<'_>> + '_ {
+        self.tags().get("emotes").into_iter().flat_map(Emote::parse)
+    }
+
     /// Whether the user sending this message was a broadcaster
     pub fn is_broadcaster(&self) -> bool {
         self.contains_badge(BadgeKind::Broadcaster)
@@ -202,6 +262,21 @@ impl<'a> Privmsg<'a> {
         self.tags().get_parsed("room-id")
     }
 
+    /// The id of the room this message originated from, when in a shared chat
+    ///
+    /// This differs from [Privmsg::room_id()] when the message was sent in another channel of the shared chat session.
+    pub fn source_room_id(&self) -> Option<&str> {
+        self.tags().get("source-room-id")
+    }
+
+    /// Badges the user has in the room this message originated from, when in a shared chat
+    pub fn source_badges(&'a self) -> Vec<Badge<'a>> {
+        self.tags()
+            .get("source-badges")
+            .map(parse_badges)
+            .unwrap_or_default()
+    }
+
     /// The timestamp of when this message was received by Trovo
     pub fn tmi_sent_ts(&self) -> Option<u64> {
         self.tags().get_parsed("tmi-sent-ts")
@@ -230,6 +305,53 @@ impl<'a> Privmsg<'a> {
         self.tags().get("msg-id")
     }
 
+    /// The unique id of this message.
+    ///
+    /// This is used for replying to or deleting this message.
+    pub fn id(&self) -> Option<&str> {
+        self.tags().get("id")
+    }
+
+    /// The nonce the sending client attached to this message, if any.
+    pub fn client_nonce(&self) -> Option<&str> {
+        self.tags().get("client-nonce")
+    }
+
+    /// The id of the message that started the reply thread this message is in
+    ///
+    /// This is the root of the thread, which may differ from the message this is directly replying to.
+    pub fn reply_thread_parent_msg_id(&self) -> Option<&str> {
+        self.tags().get("reply-thread-parent-msg-id")
+    }
+
+    /// The login of the user who started the reply thread this message is in
+    pub fn reply_thread_parent_user_login(&self) -> Option<&str> {
+        self.tags().get("reply-thread-parent-user-login")
+    }
+
+    /// The message this is directly replying to, if it is a reply
+    pub fn reply_parent(&self) -> Option<ReplyParent<'_>> {
+        let tags = self.tags();
+        Some(ReplyParent {
+            msg_id: tags.get("reply-parent-msg-id")?,
+            user_login: tags.get("reply-parent-user-login")?,
+            display_name: tags.get("reply-parent-display-name"),
+            msg_body: tags.get_unescaped("reply-parent-msg-body")?,
+        })
+    }
+
+    /// Format this message as a single log line.
+    ///
+    /// This is the same as the [Display][std::fmt::Display] output, prefixed
+    /// with the `tmi-sent-ts` timestamp if it was provided. e.g.
+    /// `1601079032426 [#museun] museun: hello world`
+    pub fn to_log_line(&self) -> String {
+        match self.tmi_sent_ts() {
+            Some(ts) => format!("{} {}", ts, self),
+            None => self.to_string(),
+        }
+    }
+
     fn contains_badge(&self, badge: BadgeKind<'_>) -> bool {
         self.tags()
             .get("badges")
@@ -283,6 +405,16 @@ impl<'a> FromIrcMessage<'a> for Privmsg<'a> {
     into_inner_raw!();
 }
 
+impl<'a> std::fmt::Display for Privmsg<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_action() {
+            write!(f, "[{}] * {} {}", self.channel(), self.name(), self.data())
+        } else {
+            write!(f, "[{}] {}: {}", self.channel(), self.name(), self.data())
+        }
+    }
+}
+
 into_owned!(Privmsg {
     raw,
     tags,
@@ -315,6 +447,8 @@ impl_custom_debug!(Privmsg {
     // is_turbo,
     // is_global_moderator,
     // room_id,
+    // source_room_id,
+    // source_badges,
     // tmi_sent_ts,
     // user_id,
     // custom_reward_id,
@@ -401,6 +535,172 @@ mod tests {
         }
     }
 
+    #[test]
+    fn privmsg_log_line() {
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.to_string(), "[#museun] test: this is a test");
+            assert_eq!(msg.to_log_line(), "[#museun] test: this is a test");
+        }
+
+        let input =
+            "@tmi-sent-ts=1601079032426 :test!user@host PRIVMSG #museun :this is a test\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.to_string(), "[#museun] test: this is a test");
+            assert_eq!(
+                msg.to_log_line(),
+                "1601079032426 [#museun] test: this is a test"
+            );
+        }
+
+        let input = ":test!user@host PRIVMSG #museun :\x01ACTION this is a test\x01\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.to_log_line(), "[#museun] * test this is a test");
+        }
+    }
+
+    #[test]
+    fn privmsg_badge_version() {
+        let input = "@badge-info=subscriber/14;badges=moderator/1,subscriber/12,bits/1000;user-id=23196011 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.badge_version(&BadgeKind::Subscriber), Some("12"));
+            assert_eq!(msg.badge_version(&BadgeKind::Moderator), Some("1"));
+            assert_eq!(msg.badge_version(&BadgeKind::Bits), Some("1000"));
+            assert_eq!(msg.badge_version(&BadgeKind::Broadcaster), None);
+        }
+    }
+
+    #[test]
+    fn privmsg_shared_chat_source() {
+        let input = "@badges=subscriber/12;room-id=23196011;source-badges=moderator/1,subscriber/6;source-room-id=12345678;user-id=1234 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.room_id(), Some(23_196_011));
+            assert_eq!(msg.source_room_id(), Some("12345678"));
+
+            let source_badges = msg.source_badges();
+            assert_eq!(source_badges.len(), 2);
+            assert_eq!(source_badges[0].kind, BadgeKind::Moderator);
+            assert_eq!(source_badges[1].kind, BadgeKind::Subscriber);
+            assert_eq!(source_badges[1].data, "6");
+
+            assert_eq!(msg.badges().len(), 1);
+        }
+
+        let input = ":museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.source_room_id().is_none());
+            assert!(msg.source_badges().is_empty());
+        }
+    }
+
+    #[test]
+    fn privmsg_id_nonce() {
+        let input = "@client-nonce=0fc8b5cc9b4e0b1b7d6a4a1f4f5c0a32;id=b34ccfc7-4977-403a-8a94-33c6bac34fb8 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.id(), Some("b34ccfc7-4977-403a-8a94-33c6bac34fb8"));
+            assert_eq!(msg.client_nonce(), Some("0fc8b5cc9b4e0b1b7d6a4a1f4f5c0a32"));
+        }
+
+        let input = ":museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.id().is_none());
+            assert!(msg.client_nonce().is_none());
+        }
+    }
+
+    #[test]
+    fn privmsg_reply_thread() {
+        let input = "@reply-parent-msg-id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;reply-parent-user-login=shaken_bot;reply-thread-parent-msg-id=6b13e51b-7ecb-43b5-ba5b-2bb5288df696;reply-thread-parent-user-login=museun :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :@shaken_bot same\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.reply_thread_parent_msg_id(),
+                Some("6b13e51b-7ecb-43b5-ba5b-2bb5288df696")
+            );
+            assert_eq!(msg.reply_thread_parent_user_login(), Some("museun"));
+
+            // the immediate parent is a different message
+            assert_eq!(
+                msg.tags().get("reply-parent-msg-id"),
+                Some("b34ccfc7-4977-403a-8a94-33c6bac34fb8")
+            );
+            assert_eq!(
+                msg.tags().get("reply-parent-user-login"),
+                Some("shaken_bot")
+            );
+        }
+
+        let input = ":museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.reply_thread_parent_msg_id().is_none());
+            assert!(msg.reply_thread_parent_user_login().is_none());
+        }
+    }
+
+    #[test]
+    fn privmsg_reply_parent() {
+        let input = "@reply-parent-display-name=Shaken_Bot;reply-parent-msg-body=hello\\sworld\\:\\sfoo;reply-parent-msg-id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;reply-parent-user-login=shaken_bot :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :@shaken_bot same\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let parent = msg.reply_parent().unwrap();
+            assert_eq!(parent.msg_id, "b34ccfc7-4977-403a-8a94-33c6bac34fb8");
+            assert_eq!(parent.user_login, "shaken_bot");
+            assert_eq!(parent.display_name, Some("Shaken_Bot"));
+            assert_eq!(&*parent.msg_body, "hello world; foo");
+        }
+
+        let input = ":museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.reply_parent().is_none());
+        }
+    }
+
+    #[test]
+    fn privmsg_same_content() {
+        let left = "@id=4e160a53-5482-4764-ba28-f224cd59a51f;tmi-sent-ts=1601079032426 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        let right = "@id=bd9a1ad8-0d2a-4ecb-9c88-cd46cbc0ab4d;tmi-sent-ts=1601079036913 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+
+        let privmsg = |input| Privmsg::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        let (left, right) = (privmsg(left), privmsg(right));
+        assert!(left != right);
+        assert!(left.same_content(&right));
+
+        let inputs = &[
+            ":museun!museun@museun.tmi.trovo.tv PRIVMSG #shaken_bot :hello\r\n",
+            ":shaken_bot!shaken_bot@shaken_bot.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+            ":museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :world\r\n",
+        ];
+        for input in inputs {
+            assert!(!left.same_content(&privmsg(input)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn privmsg_mentions() {
+        let input = ":museun!museun@museun PRIVMSG #museun :@shaken_bot, hi @Museun! (@foo_1) me@example.com @ @@bar\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let mentions = msg.mentions().collect::<Vec<_>>();
+            assert_eq!(mentions, vec!["shaken_bot", "Museun", "foo_1", "bar"]);
+        }
+
+        let input = ":museun!museun@museun PRIVMSG #museun :no mentions here\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(msg.mentions().next().is_none());
+        }
+    }
+
     #[test]
     fn privmsg_community_rewards() {
         let input = "@custom-reward-id=abc-123-foo;msg-id=highlighted-message :test!user@host PRIVMSG #museun :Notice me!\r\n";
@@ -431,4 +731,32 @@ mod tests {
             assert_eq!(msg.iter_emotes().count(), 2);
         }
     }
+
+    #[test]
+    fn privmsg_emote_ranges() {
+        let input = "@emotes=25:0-4/emotesv2_dcd06b30a5c24f6eb871e8f5edbd44f7:8-13 :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :Kappa ☃ VoHiYo\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            let emotes = msg.emote_ranges().collect::<Vec<_>>();
+            assert_eq!(emotes.len(), 2);
+            assert_eq!(emotes[0].id, "25");
+            assert_eq!(emotes[1].id, "emotesv2_dcd06b30a5c24f6eb871e8f5edbd44f7");
+
+            // the ranges are char indices, so they can be used with multi-byte messages
+            let (start, end) = emotes[1].ranges[0];
+            let name = msg
+                .data()
+                .chars()
+                .skip(start)
+                .take(end - start + 1)
+                .collect::<String>();
+            assert_eq!(name, "VoHiYo");
+        }
+
+        let input = "@emotes= :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert_eq!(msg.emote_ranges().count(), 0);
+        }
+    }
 }
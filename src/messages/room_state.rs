@@ -13,6 +13,10 @@ pub enum FollowersOnly {
 }
 
 /// Identifies the channel's chat settings (e.g., slow mode duration).
+///
+/// Trovo sends every setting when you join a channel, but afterwards only the
+/// settings that changed. So a missing setting means it is unknown (or unchanged),
+/// the `bool` accessors return `false` for it and the others return `None`.
 #[derive(Clone, PartialEq)]
 pub struct RoomState<'a> {
     raw: MaybeOwned<'a>,
@@ -45,7 +49,7 @@ impl<'a> RoomState<'a> {
         channel
     );
 
-    /// Whether this room is in emote only mode
+    /// Whether this room is in emote only mode, `false` if it wasn't sent
     pub fn is_emote_only(&self) -> bool {
         self.tags().get_as_bool("emote-only")
     }
@@ -61,7 +65,15 @@ impl<'a> RoomState<'a> {
             })
     }
 
-    /// Whether this room is in r9k mode
+    /// The raw followers only setting, if it was sent
+    ///
+    /// This is `-1` if it is disabled, `0` if any follower can chat, otherwise
+    /// how many minutes someone has to follow before they can chat.
+    pub fn follower_only(&self) -> Option<i64> {
+        self.tags().get_parsed("followers-only")
+    }
+
+    /// Whether this room is in r9k mode, `false` if it wasn't sent
     pub fn is_r9k(&self) -> bool {
         self.tags().get_as_bool("r9k")
     }
@@ -78,7 +90,14 @@ impl<'a> RoomState<'a> {
         self.tags().get_parsed("slow").filter(|&s| s > 0)
     }
 
-    /// Whether this room is in subs only mode
+    /// The raw slow mode setting in seconds, if it was sent
+    ///
+    /// Unlike [RoomState::is_slow_mode()], this is `Some(0)` when slow mode was turned off.
+    pub fn slow(&self) -> Option<u64> {
+        self.tags().get_parsed("slow")
+    }
+
+    /// Whether this room is in subs only mode, `false` if it wasn't sent
     pub fn is_subs_only(&self) -> bool {
         self.tags().get_as_bool("subs-only")
     }
@@ -108,4 +127,45 @@ mod tests {
             assert_eq!(msg.channel(), "#museun");
         }
     }
+
+    #[test]
+    fn room_state_settings() {
+        let input = "@emote-only=0;followers-only=10;r9k=1;room-id=23196011;slow=30;subs-only=1 :tmi.trovo.tv ROOMSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = RoomState::from_irc(msg).unwrap();
+            assert!(!msg.is_emote_only());
+            assert_eq!(msg.follower_only(), Some(10));
+            assert_eq!(msg.is_followers_only(), Some(FollowersOnly::Limit(10)));
+            assert!(msg.is_r9k());
+            assert_eq!(msg.slow(), Some(30));
+            assert_eq!(msg.is_slow_mode(), Some(30));
+            assert!(msg.is_subs_only());
+            assert_eq!(msg.room_id(), Some(23_196_011));
+        }
+    }
+
+    #[test]
+    fn room_state_partial() {
+        let input = "@room-id=23196011;slow=0 :tmi.trovo.tv ROOMSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = RoomState::from_irc(msg).unwrap();
+            assert_eq!(msg.slow(), Some(0));
+            assert_eq!(msg.is_slow_mode(), None);
+
+            // the rest weren't sent
+            assert!(!msg.is_emote_only());
+            assert_eq!(msg.follower_only(), None);
+            assert_eq!(msg.is_followers_only(), None);
+            assert!(!msg.is_r9k());
+            assert!(!msg.is_subs_only());
+        }
+
+        let input = "@followers-only=-1;room-id=23196011 :tmi.trovo.tv ROOMSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = RoomState::from_irc(msg).unwrap();
+            assert_eq!(msg.follower_only(), Some(-1));
+            assert_eq!(msg.is_followers_only(), Some(FollowersOnly::Disabled));
+            assert_eq!(msg.slow(), None);
+        }
+    }
 }
@@ -0,0 +1,119 @@
+use crate::{irc::*, MaybeOwned, MaybeOwnedIndex, Validator};
+
+/// Informational lines sent by the server when you connect
+///
+/// These are the `002` (your host), `003` (created), `004` (my info) and `375` (start of the MOTD) numerics.
+#[derive(Clone, PartialEq)]
+pub struct ServerInfo<'a> {
+    raw: MaybeOwned<'a>,
+    numeric: MaybeOwnedIndex,
+    name: MaybeOwnedIndex,
+    text: MaybeOwnedIndex,
+}
+
+impl<'a> ServerInfo<'a> {
+    raw!();
+    str_field!(
+        /// The numeric of this line, e.g. `002`
+        numeric
+    );
+    str_field!(
+        /// Your username
+        name
+    );
+    str_field!(
+        /// The text the server sent
+        text
+    );
+}
+
+impl<'a> FromIrcMessage<'a> for ServerInfo<'a> {
+    type Error = MessageError;
+
+    fn from_irc(msg: IrcMessage<'a>) -> Result<Self, Self::Error> {
+        // :tmi.trovo.tv 002 museun :Your host is tmi.trovo.tv
+        match msg.get_command() {
+            IrcMessage::YOUR_HOST
+            | IrcMessage::CREATED
+            | IrcMessage::MY_INFO
+            | IrcMessage::MOTD_START => {}
+            _ => msg.expect_command(IrcMessage::YOUR_HOST)?,
+        }
+
+        let this = Self {
+            numeric: msg.command,
+            name: msg.expect_arg_index(0)?,
+            text: msg.expect_data_index()?,
+            raw: msg.raw,
+        };
+
+        Ok(this)
+    }
+
+    into_inner_raw!();
+}
+
+into_owned!(ServerInfo {
+    raw,
+    numeric,
+    name,
+    text,
+});
+
+impl_custom_debug!(ServerInfo {
+    raw,
+    numeric,
+    name,
+    text,
+});
+
+serde_struct!(ServerInfo {
+    raw,
+    numeric,
+    name,
+    text,
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn server_info_serde() {
+        let input = ":tmi.trovo.tv 002 museun :Your host is tmi.trovo.tv\r\n";
+        crate::serde::round_trip_json::<ServerInfo>(input);
+        crate::serde::round_trip_rmp::<ServerInfo>(input);
+    }
+
+    #[test]
+    fn server_info() {
+        let inputs = &[
+            ("002", "Your host is tmi.trovo.tv"),
+            ("003", "This server is rather new"),
+            ("004", "-"),
+            ("375", "-"),
+        ];
+
+        for (numeric, text) in inputs {
+            let input = format!(":tmi.trovo.tv {} museun :{}\r\n", numeric, text);
+            for irc in parse(&input).map(|s| s.unwrap()) {
+                let msg = ServerInfo::from_irc(irc).unwrap();
+                assert_eq!(msg.numeric(), *numeric);
+                assert_eq!(msg.name(), "museun");
+                assert_eq!(msg.text(), *text);
+            }
+        }
+    }
+
+    #[test]
+    fn server_info_wrong_numeric() {
+        let input = ":tmi.trovo.tv 001 museun :Welcome, GLHF!\r\n";
+        for irc in parse(input).map(|s| s.unwrap()) {
+            assert!(matches!(
+                ServerInfo::from_irc(irc).unwrap_err(),
+                MessageError::InvalidCommand { .. }
+            ));
+        }
+    }
+}
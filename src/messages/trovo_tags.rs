@@ -0,0 +1,101 @@
+use crate::irc::Tags;
+use crate::trovo::{parse_badges, Badge, Color};
+
+/// Accessors for the tags Trovo attaches to messages sent on behalf of a user.
+///
+/// This allows code to be generic over the messages that carry user metadata,
+/// such as [Privmsg][super::Privmsg] or [Whisper][super::Whisper].
+///
+/// The provided methods read directly from the tags, so they'll return `None`
+/// (or an empty `Vec`) if you don't have the **TAGS** capability enabled.
+pub trait TrovoTags {
+    /// Get a view of parsable tags
+    fn tags(&self) -> Tags<'_>;
+
+    /// The color of the user, if set
+    fn color(&self) -> Option<Color> {
+        self.tags().get_parsed("color")
+    }
+
+    /// The display name of the user, if set
+    fn display_name(&self) -> Option<&str> {
+        self.tags().get("display-name")
+    }
+
+    /// Badges attached to the user
+    fn badges(&self) -> Vec<Badge<'_>> {
+        self.tags()
+            .get("badges")
+            .map(parse_badges)
+            .unwrap_or_default()
+    }
+
+    /// The id of the user
+    fn user_id(&self) -> Option<u64> {
+        self.tags().get_parsed("user-id")
+    }
+}
+
+macro_rules! impl_trovo_tags {
+    ($($ty:ident)*) => {
+        $(
+            impl<'a> TrovoTags for super::$ty<'a> {
+                fn tags(&self) -> Tags<'_> {
+                    Self::tags(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_trovo_tags! {
+    GlobalUserState
+    Privmsg
+    UserNotice
+    UserState
+    Whisper
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::*, messages::*, trovo::BadgeKind};
+
+    fn check<'a, T>(input: &'a str)
+    where
+        T: FromIrcMessage<'a> + TrovoTags,
+        T::Error: std::fmt::Debug,
+    {
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = T::from_irc(msg).unwrap();
+            assert_eq!(msg.color().unwrap().to_string(), "#FF69B4");
+            assert_eq!(msg.display_name().unwrap(), "museun");
+            assert_eq!(msg.user_id().unwrap(), 23196011);
+
+            let badges = msg.badges();
+            assert_eq!(badges.len(), 1);
+            assert_eq!(badges[0].kind, BadgeKind::Broadcaster);
+        }
+    }
+
+    #[test]
+    fn trovo_tags_generic() {
+        const TAGS: &str =
+            "@badges=broadcaster/1;color=#FF69B4;display-name=museun;user-id=23196011";
+
+        check::<Privmsg>(&format!(
+            "{} :museun!museun@museun.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+            TAGS
+        ));
+        check::<Whisper>(&format!(
+            "{} :museun!museun@museun.tmi.trovo.tv WHISPER shaken_bot :hello\r\n",
+            TAGS
+        ));
+        check::<UserState>(&format!("{} :tmi.trovo.tv USERSTATE #museun\r\n", TAGS));
+        check::<UserNotice>(&format!(
+            "{};msg-id=raid :tmi.trovo.tv USERNOTICE #museun\r\n",
+            TAGS
+        ));
+        check::<GlobalUserState>(&format!("{} :tmi.trovo.tv GLOBALUSERSTATE\r\n", TAGS));
+    }
+}
@@ -51,6 +51,115 @@ pub enum NoticeType<'a> {
     Unknown(&'a str),
 }
 
+/// A [UserNotice] event with its `msg-param-*` tags, retrieved via [UserNotice::event()]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum UserNoticeEvent<'a> {
+    /// A user subscribed to the channel
+    Sub {
+        /// The total number of months the user has subscribed
+        months: u64,
+        /// The number of consecutive months, if the user shares their streak
+        streak_months: Option<u64>,
+        /// The subscription plan
+        plan: SubPlan<'a>,
+        /// The display name of the subscription plan
+        plan_name: Option<&'a str>,
+    },
+    /// A user re-subscribed to the channel
+    Resub {
+        /// The total number of months the user has subscribed
+        months: u64,
+        /// The number of consecutive months, if the user shares their streak
+        streak_months: Option<u64>,
+        /// The subscription plan
+        plan: SubPlan<'a>,
+        /// The display name of the subscription plan
+        plan_name: Option<&'a str>,
+    },
+    /// A user gifted a subscription to another user
+    SubGift {
+        /// The login of the recipient
+        recipient: &'a str,
+        /// The display name of the recipient
+        recipient_display_name: Option<&'a str>,
+        /// The user id of the recipient
+        recipient_id: Option<u64>,
+        /// The total number of months the recipient has subscribed
+        months: Option<u64>,
+        /// The subscription plan
+        plan: SubPlan<'a>,
+        /// The display name of the subscription plan
+        plan_name: Option<&'a str>,
+    },
+    /// An anonymous user gifted a subscription to another user
+    AnonSubGift {
+        /// The login of the recipient
+        recipient: &'a str,
+        /// The display name of the recipient
+        recipient_display_name: Option<&'a str>,
+        /// The user id of the recipient
+        recipient_id: Option<u64>,
+        /// The total number of months the recipient has subscribed
+        months: Option<u64>,
+        /// The subscription plan
+        plan: SubPlan<'a>,
+        /// The display name of the subscription plan
+        plan_name: Option<&'a str>,
+    },
+    /// A user gifted subscriptions to random users in the channel
+    SubMysteryGift {
+        /// How many subscriptions were gifted
+        count: u64,
+        /// The subscription plan
+        plan: SubPlan<'a>,
+    },
+    /// A user continued a subscription they were gifted
+    GiftPaidUpgrade {
+        /// The login of the user who gifted the subscription
+        sender: &'a str,
+        /// The display name of the user who gifted the subscription
+        sender_display_name: Option<&'a str>,
+        /// The ongoing subscription promo, if any
+        promo_name: Option<&'a str>,
+        /// The number of gifts the gifter has given during the promo
+        promo_gift_total: Option<u64>,
+    },
+    /// A user continued a subscription they were gifted by an anonymous user
+    AnonGiftPaidUpgrade {
+        /// The ongoing subscription promo, if any
+        promo_name: Option<&'a str>,
+        /// The number of gifts the gifter has given during the promo
+        promo_gift_total: Option<u64>,
+    },
+    /// A reward gift
+    RewardGift,
+    /// Another channel raided this channel
+    Raid {
+        /// The login of the raiding channel
+        from: &'a str,
+        /// The display name of the raiding channel
+        from_display_name: Option<&'a str>,
+        /// The number of viewers in the raid
+        viewers: u64,
+    },
+    /// A raid was canceled
+    Unraid,
+    /// A ritual, e.g. `new_chatter`
+    Ritual {
+        /// The name of the ritual
+        name: &'a str,
+    },
+    /// A user earned a new bits badge tier
+    BitsBadgeTier {
+        /// The tier of the badge, e.g. `100` or `1000`
+        threshold: u64,
+    },
+    /// An unknown `msg-id`, or a known one that was missing its required tags
+    Unknown(&'a str),
+}
+
 /// Announces Trovo-specific events to the channel (e.g., a user's subscription notification).
 #[derive(Clone, PartialEq)]
 pub struct UserNotice<'a> {
@@ -146,6 +255,111 @@ impl<'a> UserNotice<'a> {
         .into()
     }
 
+    /// The raw `msg-id` of this notice, e.g. `sub` or `raid`
+    ///
+    /// Use [UserNotice::msg_id()] for the parsed kind.
+    pub fn msg_id_raw(&self) -> Option<&str> {
+        self.tags().get("msg-id")
+    }
+
+    /// The event this notice is for, with its `msg-param-*` tags decoded
+    ///
+    /// ```
+    /// # use trovochat::{messages::{UserNotice, UserNoticeEvent}, FromIrcMessage as _};
+    /// let input = "@msg-id=raid;msg-param-login=museun;msg-param-viewerCount=42 :tmi.trovo.tv USERNOTICE #shaken_bot\r\n";
+    /// let msg = trovochat::irc::parse(input).next().unwrap().unwrap();
+    /// let msg = UserNotice::from_irc(msg).unwrap();
+    ///
+    /// assert_eq!(
+    ///     msg.event(),
+    ///     UserNoticeEvent::Raid { from: "museun", from_display_name: None, viewers: 42 }
+    /// );
+    /// ```
+    pub fn event(&'a self) -> UserNoticeEvent<'a> {
+        let kind = self.msg_id_raw().unwrap_or_default();
+        self.parse_event(kind)
+            .unwrap_or(UserNoticeEvent::Unknown(kind))
+    }
+
+    fn parse_event(&'a self, kind: &'a str) -> Option<UserNoticeEvent<'a>> {
+        // the streak is only sent if the user wants it shared
+        let streak_months = || {
+            self.msg_param_should_share_streak()
+                .filter(|&share| share)
+                .and_then(|_| self.msg_param_streak_months())
+        };
+
+        let event = match kind {
+            "sub" => UserNoticeEvent::Sub {
+                months: self.msg_param_cumulative_months()?,
+                streak_months: streak_months(),
+                plan: self.msg_param_sub_plan()?,
+                plan_name: self.msg_param_sub_plan_name(),
+            },
+            "resub" => UserNoticeEvent::Resub {
+                months: self.msg_param_cumulative_months()?,
+                streak_months: streak_months(),
+                plan: self.msg_param_sub_plan()?,
+                plan_name: self.msg_param_sub_plan_name(),
+            },
+            "subgift" => UserNoticeEvent::SubGift {
+                recipient: self.msg_param_recipient_user_name()?,
+                recipient_display_name: self.msg_param_recipient_display_name(),
+                recipient_id: self.msg_param_recipient_id(),
+                months: self.msg_param_months(),
+                plan: self.msg_param_sub_plan()?,
+                plan_name: self.msg_param_sub_plan_name(),
+            },
+            "anonsubgift" => UserNoticeEvent::AnonSubGift {
+                recipient: self.msg_param_recipient_user_name()?,
+                recipient_display_name: self.msg_param_recipient_display_name(),
+                recipient_id: self.msg_param_recipient_id(),
+                months: self.msg_param_months(),
+                plan: self.msg_param_sub_plan()?,
+                plan_name: self.msg_param_sub_plan_name(),
+            },
+            "submysterygift" => UserNoticeEvent::SubMysteryGift {
+                count: self.tags().get_parsed("msg-param-mass-gift-count")?,
+                plan: self.msg_param_sub_plan()?,
+            },
+            "giftpaidupgrade" => UserNoticeEvent::GiftPaidUpgrade {
+                sender: self.msg_param_sender_login()?,
+                sender_display_name: self.msg_param_sender_name(),
+                promo_name: self.msg_param_promo_name(),
+                promo_gift_total: self.msg_param_promo_gift_total(),
+            },
+            "anongiftpaidupgrade" => UserNoticeEvent::AnonGiftPaidUpgrade {
+                promo_name: self.msg_param_promo_name(),
+                promo_gift_total: self.msg_param_promo_gift_total(),
+            },
+            "rewardgift" => UserNoticeEvent::RewardGift,
+            "raid" => UserNoticeEvent::Raid {
+                from: self.msg_param_login()?,
+                from_display_name: self.msg_param_display_name(),
+                viewers: self.msg_param_viewer_count()?,
+            },
+            "unraid" => UserNoticeEvent::Unraid,
+            "ritual" => UserNoticeEvent::Ritual {
+                name: self.msg_param_ritual_name()?,
+            },
+            "bitsbadgetier" => UserNoticeEvent::BitsBadgeTier {
+                threshold: self.msg_param_threshold()?,
+            },
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// Whether this notice is for a subscription event
+    ///
+    /// This is true for `sub`, `resub`, `subgift`, `anonsubgift` and `resubgift`.
+    pub fn is_sub_event(&self) -> bool {
+        matches!(
+            self.msg_id_raw(),
+            Some("sub") | Some("resub") | Some("subgift") | Some("anonsubgift") | Some("resubgift")
+        )
+    }
+
     /// The id of the room for this notice
     pub fn room_id(&self) -> Option<u64> {
         self.tags().get_parsed("room-id")
@@ -248,7 +462,7 @@ impl<'a> UserNotice<'a> {
     /// (Sent only on sub, resub) Boolean indicating whether users want their
     /// streaks to be shared.
     pub fn msg_param_should_share_streak(&self) -> Option<bool> {
-        self.tags().get_parsed("msg-param-should-share-streak")
+        self.tags().get_bool("msg-param-should-share-streak")
     }
 
     /// (Sent only on sub, resub) The number of consecutive months the user has
@@ -269,9 +483,9 @@ impl<'a> UserNotice<'a> {
         self.tags().get("msg-param-sub-plan").and_then(|s| {
             match s {
                 "Prime" => SubPlan::Prime,
-                "Tier1" => SubPlan::Tier1,
-                "Tier2" => SubPlan::Tier2,
-                "Tier3" => SubPlan::Tier3,
+                "1000" | "Tier1" => SubPlan::Tier1,
+                "2000" | "Tier2" => SubPlan::Tier2,
+                "3000" | "Tier3" => SubPlan::Tier3,
                 s => SubPlan::Unknown(s),
             }
             .into()
@@ -325,6 +539,26 @@ impl<'a> FromIrcMessage<'a> for UserNotice<'a> {
     into_inner_raw!();
 }
 
+impl<'a> std::fmt::Display for UserNotice<'a> {
+    /// Prints the `system-msg` if it was sent, otherwise a summary of the `msg-id`.
+    ///
+    /// e.g. `resub from museun in #museun`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(system_msg) = self.system_msg() {
+            return f.write_str(&system_msg);
+        }
+
+        match (self.msg_id_raw(), self.login(), self.message()) {
+            (Some(kind), Some(login), ..) => {
+                write!(f, "{} from {} in {}", kind, login, self.channel())
+            }
+            (Some(kind), None, ..) => write!(f, "{} in {}", kind, self.channel()),
+            (None, .., Some(message)) => write!(f, "[{}] {}", self.channel(), message),
+            (None, .., None) => write!(f, "user notice in {}", self.channel()),
+        }
+    }
+}
+
 into_owned!(UserNotice {
     raw,
     tags,
@@ -398,4 +632,168 @@ mod tests {
             assert_eq!(msg.tags().is_empty(), false);
         }
     }
+
+    #[test]
+    fn user_notice_sub_event() {
+        let inputs = &[
+            ("sub", true),
+            ("resub", true),
+            ("subgift", true),
+            ("anonsubgift", true),
+            ("resubgift", true),
+            ("submysterygift", false),
+            ("raid", false),
+            ("ritual", false),
+        ];
+
+        for (msg_id, expected) in inputs {
+            let input = format!("@msg-id={} :tmi.trovo.tv USERNOTICE #museun\r\n", msg_id);
+            for msg in parse(&input).map(|s| s.unwrap()) {
+                let msg = UserNotice::from_irc(msg).unwrap();
+                assert_eq!(msg.msg_id_raw(), Some(*msg_id));
+                assert_eq!(msg.is_sub_event(), *expected, "{}", msg_id);
+            }
+        }
+
+        let input = ":tmi.trovo.tv USERNOTICE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(msg.msg_id_raw(), None);
+            assert!(!msg.is_sub_event());
+        }
+    }
+
+    #[test]
+    fn user_notice_event() {
+        let inputs = vec![
+            (
+                "msg-id=sub;msg-param-cumulative-months=1;msg-param-should-share-streak=0;msg-param-streak-months=0;msg-param-sub-plan=Prime;msg-param-sub-plan-name=Channel\\sSubscription",
+                UserNoticeEvent::Sub {
+                    months: 1,
+                    streak_months: None,
+                    plan: SubPlan::Prime,
+                    plan_name: Some("Channel\\sSubscription"),
+                },
+            ),
+            (
+                "msg-id=resub;msg-param-cumulative-months=8;msg-param-should-share-streak=1;msg-param-streak-months=3;msg-param-sub-plan=1000",
+                UserNoticeEvent::Resub {
+                    months: 8,
+                    streak_months: Some(3),
+                    plan: SubPlan::Tier1,
+                    plan_name: None,
+                },
+            ),
+            (
+                "msg-id=subgift;msg-param-months=2;msg-param-recipient-display-name=Museun;msg-param-recipient-id=23196011;msg-param-recipient-user-name=museun;msg-param-sub-plan=2000",
+                UserNoticeEvent::SubGift {
+                    recipient: "museun",
+                    recipient_display_name: Some("Museun"),
+                    recipient_id: Some(23_196_011),
+                    months: Some(2),
+                    plan: SubPlan::Tier2,
+                    plan_name: None,
+                },
+            ),
+            (
+                "msg-id=anonsubgift;msg-param-recipient-user-name=museun;msg-param-sub-plan=3000",
+                UserNoticeEvent::AnonSubGift {
+                    recipient: "museun",
+                    recipient_display_name: None,
+                    recipient_id: None,
+                    months: None,
+                    plan: SubPlan::Tier3,
+                    plan_name: None,
+                },
+            ),
+            (
+                "msg-id=submysterygift;msg-param-mass-gift-count=5;msg-param-sub-plan=4000",
+                UserNoticeEvent::SubMysteryGift {
+                    count: 5,
+                    plan: SubPlan::Unknown("4000"),
+                },
+            ),
+            (
+                "msg-id=giftpaidupgrade;msg-param-promo-gift-total=3;msg-param-promo-name=Subtember;msg-param-sender-login=museun;msg-param-sender-name=Museun",
+                UserNoticeEvent::GiftPaidUpgrade {
+                    sender: "museun",
+                    sender_display_name: Some("Museun"),
+                    promo_name: Some("Subtember"),
+                    promo_gift_total: Some(3),
+                },
+            ),
+            (
+                "msg-id=anongiftpaidupgrade",
+                UserNoticeEvent::AnonGiftPaidUpgrade {
+                    promo_name: None,
+                    promo_gift_total: None,
+                },
+            ),
+            ("msg-id=rewardgift", UserNoticeEvent::RewardGift),
+            (
+                "msg-id=raid;msg-param-displayName=Museun;msg-param-login=museun;msg-param-viewerCount=42",
+                UserNoticeEvent::Raid {
+                    from: "museun",
+                    from_display_name: Some("Museun"),
+                    viewers: 42,
+                },
+            ),
+            ("msg-id=unraid", UserNoticeEvent::Unraid),
+            (
+                "msg-id=ritual;msg-param-ritual-name=new_chatter",
+                UserNoticeEvent::Ritual {
+                    name: "new_chatter",
+                },
+            ),
+            (
+                "msg-id=bitsbadgetier;msg-param-threshold=1000",
+                UserNoticeEvent::BitsBadgeTier { threshold: 1000 },
+            ),
+            // missing the required tags
+            ("msg-id=raid;msg-param-login=museun", UserNoticeEvent::Unknown("raid")),
+            ("msg-id=sub", UserNoticeEvent::Unknown("sub")),
+            ("msg-id=charity", UserNoticeEvent::Unknown("charity")),
+        ];
+
+        for (tags, expected) in inputs {
+            let input = format!("@{} :tmi.trovo.tv USERNOTICE #museun\r\n", tags);
+            for msg in parse(&input).map(|s| s.unwrap()) {
+                let msg = UserNotice::from_irc(msg).unwrap();
+                assert_eq!(msg.event(), expected, "{}", tags);
+            }
+        }
+
+        let input = ":tmi.trovo.tv USERNOTICE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(msg.event(), UserNoticeEvent::Unknown(""));
+        }
+    }
+
+    #[test]
+    fn user_notice_display() {
+        let input = "@badge-info=subscriber/8;badges=subscriber/6,bits/100;color=#59517B;display-name=lllAirJordanlll;emotes=;flags=;id=3198b02c-eaf4-4904-9b07-eb1b2b12ba50;login=lllairjordanlll;mod=0;msg-id=resub;msg-param-cumulative-months=8;msg-param-months=0;msg-param-should-share-streak=0;msg-param-sub-plan-name=Channel\\sSubscription\\s(giantwaffle);msg-param-sub-plan=1000;room-id=22552479;subscriber=1;system-msg=lllAirJordanlll\\ssubscribed\\sat\\sTier\\s1.\\sThey\'ve\\ssubscribed\\sfor\\s8\\smonths!;tmi-sent-ts=1580932171144;user-id=44979519;user-type= :tmi.trovo.tv USERNOTICE #giantwaffle\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.to_string(),
+                "lllAirJordanlll subscribed at Tier 1. They've subscribed for 8 months!"
+            );
+        }
+
+        let input = "@login=museun;msg-id=raid :tmi.trovo.tv USERNOTICE #shaken_bot\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(msg.to_string(), "raid from museun in #shaken_bot");
+        }
+
+        let input = ":tmi.trovo.tv USERNOTICE #museun :This room is no longer in slow mode.\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserNotice::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.to_string(),
+                "[#museun] This room is no longer in slow mode."
+            );
+        }
+    }
 }
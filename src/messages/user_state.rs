@@ -54,6 +54,24 @@ impl<'a> UserState<'a> {
             .unwrap_or_default()
     }
 
+    /// The emote sets you can use in this channel, always contains atleast '0'
+    pub fn emote_sets(&self) -> Vec<&str> {
+        self.tags()
+            .get("emote-sets")
+            .map(|s| s.split(',').collect())
+            .unwrap_or_else(|| vec!["0"])
+    }
+
+    /// The emote sets you can use in this channel, as numbers
+    ///
+    /// Any set that isn't a number is skipped.
+    pub fn emote_sets_parsed(&self) -> Vec<u64> {
+        self.emote_sets()
+            .into_iter()
+            .filter_map(|s| s.parse().ok())
+            .collect()
+    }
+
     /// Whether this user is a moderator
     pub fn is_moderator(&self) -> bool {
         self.tags().get_as_bool("mod")
@@ -100,6 +118,31 @@ mod tests {
         for msg in parse(input).map(|s| s.unwrap()) {
             let msg = UserState::from_irc(msg).unwrap();
             assert_eq!(msg.channel(), "#museun");
+            assert_eq!(msg.emote_sets(), vec!["0"]);
+            assert_eq!(msg.emote_sets_parsed(), vec![0]);
+        }
+    }
+
+    #[test]
+    fn user_state_emote_sets() {
+        let input = "@badges=;color=;display-name=shaken_bot;emote-sets=0,33,50,237,793,2126,3517;mod=0;subscriber=0;user-type= :tmi.trovo.tv USERSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserState::from_irc(msg).unwrap();
+            assert_eq!(
+                msg.emote_sets(),
+                vec!["0", "33", "50", "237", "793", "2126", "3517"]
+            );
+            assert_eq!(
+                msg.emote_sets_parsed(),
+                vec![0, 33, 50, 237, 793, 2126, 3517]
+            );
+        }
+
+        let input = "@emote-sets=0,a1b2c3 :tmi.trovo.tv USERSTATE #museun\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = UserState::from_irc(msg).unwrap();
+            assert_eq!(msg.emote_sets(), vec!["0", "a1b2c3"]);
+            assert_eq!(msg.emote_sets_parsed(), vec![0]);
         }
     }
 }
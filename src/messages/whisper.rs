@@ -9,6 +9,7 @@ pub struct Whisper<'a> {
     raw: MaybeOwned<'a>,
     tags: TagIndices,
     name: MaybeOwnedIndex,
+    to: MaybeOwnedIndex,
     data: MaybeOwnedIndex,
 }
 
@@ -19,11 +20,22 @@ impl<'a> Whisper<'a> {
         /// User who sent this messages
         name
     );
+    str_field!(
+        /// User who received this message (you)
+        to
+    );
     str_field!(
         /// Data that the user provided
         data
     );
 
+    /// User who sent this message
+    ///
+    /// This is the same as [Whisper::name()]
+    pub fn from(&self) -> &str {
+        self.name()
+    }
+
     /// The color of the user who sent this message, if set
     pub fn color(&self) -> Option<Color> {
         self.tags().get_parsed("color")
@@ -99,10 +111,9 @@ impl<'a> FromIrcMessage<'a> for Whisper<'a> {
         msg.expect_command(IrcMessage::WHISPER)?;
 
         // :sender WHISPER target :data
-        // we're the target, so ignore it
-
         let this = Self {
             name: msg.expect_nick()?,
+            to: msg.expect_arg_index(0)?,
             data: msg.expect_data_index()?,
             tags: msg.parse_tags(),
             raw: msg.raw,
@@ -118,18 +129,21 @@ into_owned!(Whisper {
     raw,
     tags,
     name,
+    to,
     data,
 });
 impl_custom_debug!(Whisper {
     raw,
     tags,
     name,
+    to,
     data,
 });
 serde_struct!(Whisper {
     raw,
     tags,
     name,
+    to,
     data,
 });
 
@@ -155,4 +169,19 @@ mod tests {
             assert_eq!(msg.data(), "this is a test");
         }
     }
+
+    #[test]
+    fn whisper_from_to() {
+        let input = "@badges=staff/1,turbo/1;color=#FF0000;display-name=Sender :sender!sender@sender.tmi.trovo.tv WHISPER recipient :hello\r\n";
+        for msg in parse(input).map(|s| s.unwrap()) {
+            let msg = Whisper::from_irc(msg).unwrap();
+
+            assert_eq!(msg.from(), "sender");
+            assert_eq!(msg.to(), "recipient");
+            assert_eq!(msg.data(), "hello");
+            assert_eq!(msg.tags().get("display-name"), Some("Sender"));
+            assert_eq!(msg.badges().len(), 2);
+            assert!(msg.is_staff());
+        }
+    }
 }
@@ -4,7 +4,11 @@
 A simple leaky-bucket style token-based rate limiter
 */
 
-use std::time::{Duration, Instant};
+use crate::clock::Clock;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// A preset number of tokens as described by Trovo
 #[non_exhaustive]
@@ -48,6 +52,7 @@ impl RateClass {
 pub struct RateLimit {
     cap: u64,
     bucket: Bucket,
+    clock: Arc<dyn Clock>,
 }
 
 impl Default for RateLimit {
@@ -82,13 +87,41 @@ impl RateLimit {
         Self::full(rate_class.tickets(), RateClass::period())
     }
 
+    /// The limit for a regular user: `20` messages per `30` seconds
+    pub fn trovo_user() -> Self {
+        Self::from_class(RateClass::Regular)
+    }
+
+    /// The limit for a moderator (or the broadcaster): `100` messages per `30` seconds
+    pub fn trovo_mod() -> Self {
+        Self::from_class(RateClass::Moderator)
+    }
+
+    /// The limit for a verified bot: `7500` messages per `30` seconds
+    pub fn trovo_verified() -> Self {
+        Self::from_class(RateClass::Verified)
+    }
+
+    /// The limit for joining channels: `20` JOINs per `10` seconds
+    pub fn trovo_join() -> Self {
+        Self::full(20, Duration::from_secs(10))
+    }
+
+    /// The limit for whispers: `3` per second, up to `100` per minute
+    pub fn trovo_whisper() -> Self {
+        // a burst of 3, then one every 600ms
+        Self::with_bucket(3, 1, 3, Duration::from_millis(600))
+    }
+
+    /// Create a builder for a custom rate limit
+    pub fn builder() -> RateLimitBuilder {
+        RateLimitBuilder::default()
+    }
+
     /// Create a new rate limiter of `capacity` with an `initial` number of
     /// token and the `period` between refills
     pub fn new(cap: u64, initial: u64, period: Duration) -> Self {
-        Self {
-            cap,
-            bucket: Bucket::new(cap, initial, period),
-        }
+        Self::with_bucket(cap, cap, initial, period)
     }
 
     /// Create a new rate limiter that is pre-filled
@@ -97,10 +130,7 @@ impl RateLimit {
     ///
     /// `period` is how long it'll take to refill all of the tokens
     pub fn full(cap: u64, period: Duration) -> Self {
-        Self {
-            cap,
-            bucket: Bucket::new(cap, cap, period),
-        }
+        Self::with_bucket(cap, cap, cap, period)
     }
 
     /// Create am empty rate limiter
@@ -111,9 +141,30 @@ impl RateLimit {
     ///
     /// This will block, at first, atleast one `period` until its filled
     pub fn empty(cap: u64, period: Duration) -> Self {
+        Self::with_bucket(cap, cap, 0, period)
+    }
+
+    /// Use this [Clock] to keep track of the refills, instead of the [SystemClock](crate::clock::SystemClock)
+    ///
+    /// The current refill period starts over at the clock's current time.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.set_clock(Arc::new(clock));
+        self
+    }
+
+    pub(crate) fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let now = clock.now();
+        self.bucket.last = now;
+        self.bucket.next = now + self.bucket.period;
+        self.clock = clock;
+    }
+
+    fn with_bucket(cap: u64, quantum: u64, initial: u64, period: Duration) -> Self {
+        let clock = crate::clock::system();
         Self {
             cap,
-            bucket: Bucket::new(cap, 0, period),
+            bucket: Bucket::new(quantum, initial, period, clock.now()),
+            clock,
         }
     }
 
@@ -146,7 +197,7 @@ impl RateLimit {
     pub fn consume(&mut self, tokens: u64) -> Result<u64, Duration> {
         let Self { bucket, .. } = self;
 
-        let now = Instant::now();
+        let now = self.clock.now();
         if let Some(n) = bucket.refill(now) {
             bucket.tokens = std::cmp::min(bucket.tokens + n, self.cap);
         }
@@ -162,6 +213,68 @@ impl RateLimit {
     }
 }
 
+/// A builder for a custom [RateLimit]
+///
+/// This defaults to [RateClass::Regular], `20` tokens refilled every `30` seconds.
+///
+/// ```
+/// # use trovochat::rate_limit::RateLimit;
+/// # use std::time::Duration;
+/// // 100 tokens, refilling 10 of them every 3 seconds
+/// let rate = RateLimit::builder()
+///     .capacity(100)
+///     .refill_amount(10)
+///     .refill_period(Duration::from_secs(3))
+///     .build();
+/// assert_eq!(rate.get_cap(), 100);
+/// assert_eq!(rate.get_available_tokens(), 100);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct RateLimitBuilder {
+    capacity: u64,
+    refill_amount: Option<u64>,
+    refill_period: Duration,
+}
+
+impl Default for RateLimitBuilder {
+    fn default() -> Self {
+        Self {
+            capacity: RateClass::Regular.tickets(),
+            refill_amount: None,
+            refill_period: RateClass::period(),
+        }
+    }
+}
+
+impl RateLimitBuilder {
+    /// The total number of tokens the bucket holds. It starts out full.
+    pub fn capacity(mut self, capacity: u64) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// How many tokens are added each `refill_period`.
+    ///
+    /// This defaults to the `capacity`.
+    pub fn refill_amount(mut self, refill_amount: u64) -> Self {
+        self.refill_amount.replace(refill_amount);
+        self
+    }
+
+    /// How often tokens are added
+    pub fn refill_period(mut self, refill_period: Duration) -> Self {
+        self.refill_period = refill_period;
+        self
+    }
+
+    /// Build the [RateLimit]
+    pub fn build(self) -> RateLimit {
+        // a quantum of 0 would never refill (and can't estimate the wait)
+        let quantum = self.refill_amount.unwrap_or(self.capacity).max(1);
+        RateLimit::with_bucket(self.capacity, quantum, self.capacity, self.refill_period)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Bucket {
     tokens: u64,
@@ -173,8 +286,7 @@ struct Bucket {
 }
 
 impl Bucket {
-    fn new(tokens: u64, initial: u64, period: Duration) -> Self {
-        let now = Instant::now();
+    fn new(tokens: u64, initial: u64, period: Duration, now: Instant) -> Self {
         Self {
             tokens: initial,
             backoff: 0,
@@ -203,3 +315,93 @@ impl Bucket {
         until + self.period * (periods as u32 - 1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        let tests = vec![
+            (RateLimit::trovo_user(), 20, 30),
+            (RateLimit::trovo_mod(), 100, 30),
+            (RateLimit::trovo_verified(), 7500, 30),
+            (RateLimit::trovo_join(), 20, 10),
+        ];
+
+        for (rate, cap, period) in tests {
+            assert_eq!(rate.get_cap(), cap);
+            assert_eq!(rate.get_period(), Duration::from_secs(period));
+            // they start out full
+            assert_eq!(rate.get_available_tokens(), cap);
+        }
+    }
+
+    #[test]
+    fn presets_rate_class() {
+        assert!(matches!(
+            RateLimit::trovo_user().get_current_rate_class(),
+            Some(RateClass::Regular)
+        ));
+        assert!(matches!(
+            RateLimit::trovo_mod().get_current_rate_class(),
+            Some(RateClass::Moderator)
+        ));
+        assert!(matches!(
+            RateLimit::trovo_verified().get_current_rate_class(),
+            Some(RateClass::Verified)
+        ));
+        assert!(RateLimit::trovo_join().get_current_rate_class().is_none());
+    }
+
+    #[test]
+    fn builder() {
+        let clock = crate::test::TestClock::new();
+        let mut rate = RateLimit::builder()
+            .capacity(4)
+            .refill_amount(2)
+            .refill_period(Duration::from_secs(30))
+            .build()
+            .with_clock(clock.clone());
+        assert_eq!(rate.get_cap(), 4);
+        assert_eq!(rate.get_period(), Duration::from_secs(30));
+
+        assert_eq!(rate.consume(4), Ok(0));
+        // needs 2 refills
+        let wait = rate.consume(3).unwrap_err();
+        assert!(wait > Duration::from_secs(30));
+
+        clock.advance(Duration::from_secs(30));
+        // only 2 were refilled
+        assert_eq!(rate.consume(2), Ok(0));
+        assert!(rate.consume(1).is_err());
+    }
+
+    #[test]
+    fn test_clock_refill() {
+        let clock = crate::test::TestClock::new();
+        let mut rate = RateLimit::full(2, Duration::from_secs(30)).with_clock(clock.clone());
+
+        assert_eq!(rate.consume(2), Ok(0));
+        assert_eq!(rate.consume(1), Err(Duration::from_secs(30)));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(rate.consume(1), Err(Duration::from_secs(20)));
+
+        // a full period has passed, so its refilled
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(rate.consume(1), Ok(1));
+        assert_eq!(rate.consume(1), Ok(0));
+        assert!(rate.consume(1).is_err());
+    }
+
+    #[test]
+    fn builder_defaults() {
+        let rate = RateLimit::builder().build();
+        assert!(matches!(
+            rate.get_current_rate_class(),
+            Some(RateClass::Regular)
+        ));
+        assert_eq!(rate.get_available_tokens(), 20);
+    }
+}
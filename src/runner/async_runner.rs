@@ -1,28 +1,36 @@
 cfg_async! {
 use crate::{
-    channel::Receiver,
+    channel::{Receiver, Sender, TrySendError},
+    clock::Clock,
     commands,
-    connector::Connector,
+    connector::{BoxedConnector, Connector},
     encoder::AsyncEncoder,
-    messages::{Capability, Commands, MessageId},
+    messages::{Capability, Commands, MessageId, Privmsg, RoomState, UserState},
     rate_limit::{RateClass, RateLimit},
-    trovo::UserConfig,
+    trovo::{BadgeKind, Color, UserConfig},
     util::{Notify, NotifyHandle},
     writer::{AsyncWriter, MpscWriter},
-    AsyncDecoder, DecodeError, Encodable, FromIrcMessage, IrcMessage,
+    AsyncDecoder, DecodeError, Encodable, FromIrcMessage, IntoIrcMessage as _, IrcMessage,
+    MessageError, Validator as _,
 };
 
 use super::{
     channel::Channels,
-    timeout::{TimeoutState, RATE_LIMIT_WINDOW, TIMEOUT, WINDOW},
-    Capabilities, Channel, Error, Identity, Status, StepResult,
+    timeout::{
+        TimeoutState, COMMAND_RESULT_TIMEOUT, DRAIN_TIMEOUT, JOIN_TIMEOUT, RATE_LIMIT_WINDOW,
+        TIMEOUT, WAIT_TIMEOUT, WINDOW,
+    },
+    AsyncRunnerBuilder, Capabilities, Channel, CommandResult, ConnectionState, Error, Filter,
+    Identity, RetryStrategy, Status, StepResult,
 };
 
 use futures_lite::{AsyncRead, AsyncWrite, AsyncWriteExt, Stream};
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 /// An asynchronous runner
@@ -40,16 +48,44 @@ pub struct AsyncRunner {
     notify_handle: NotifyHandle,
 
     timeout_state: TimeoutState,
+    clock: Arc<dyn Clock>,
 
-    decoder: AsyncDecoder<Box<dyn AsyncRead + Send + Sync + Unpin>>,
-    encoder: AsyncEncoder<Box<dyn AsyncWrite + Send + Sync + Unpin>>,
+    decoder: AsyncDecoder<BoxedRead>,
+    encoder: AsyncEncoder<BoxedWrite>,
 
     writer: AsyncWriter<MpscWriter>,
     global_rate_limit: RateLimit,
+    join_rate_limit: RateLimit,
+    join_timeout: Duration,
+    drain_timeout: Duration,
+    wait_timeout: Duration,
 
     missed_messages: VecDeque<Commands<'static>>,
+
+    connector: BoxedConnector,
+    user_config: UserConfig,
+
+    last_raw: String,
+
+    queued_joins: VecDeque<String>,
+
+    command_result_tx: Option<Sender<CommandResult>>,
+    pending_commands: VecDeque<(String, String, Instant)>,
+
+    sent_nonces: VecDeque<String>,
+    dedupe_window: usize,
+
+    connection_state: ConnectionState,
+    connection_state_tx: Option<Sender<ConnectionState>>,
+
+    outbound_filter: Option<OutboundFilter>,
 }
 
+type OutboundFilter = Box<dyn FnMut(&[u8]) -> Filter + Send + Sync>;
+
+type BoxedRead = Box<dyn AsyncRead + Send + Sync + Unpin>;
+type BoxedWrite = Box<dyn AsyncWrite + Send + Sync + Unpin>;
+
 impl std::fmt::Debug for AsyncRunner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AsyncRunner { .. }").finish()
@@ -60,14 +96,105 @@ impl AsyncRunner {
     /// Connect with the provided connector and the provided UserConfig
     ///
     /// This returns the Runner with your identity set.
+    ///
+    /// If the connection isn't ready within 5 seconds, this returns [Error::RegistrationTimeout].
+    /// Use [AsyncRunner::connect_with_timeout()] to change this.
     pub async fn connect<C>(connector: C, user_config: &UserConfig) -> Result<Self, Error>
     where
-        C: Connector,
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        Self::builder().connect(connector, user_config).await
+    }
+
+    /// Make a builder to change the runner's buffer sizes (and other settings) before connecting
+    pub fn builder() -> AsyncRunnerBuilder {
+        AsyncRunnerBuilder::default()
+    }
+
+    /// Connect with the provided connector and the provided UserConfig, waiting
+    /// at most `timeout` for the connection to be ready.
+    ///
+    /// This returns [Error::RegistrationTimeout] if Trovo didn't finish the
+    /// registration (e.g. acknowledging the capabilities) in time.
+    pub async fn connect_with_timeout<C>(
+        connector: C,
+        user_config: &UserConfig,
+        timeout: Duration,
+    ) -> Result<Self, Error>
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        Self::builder()
+            .registration_timeout(timeout)
+            .connect(connector, user_config)
+            .await
+    }
+
+    /// Connect with the provided connector and the provided UserConfig, sending
+    /// each [ConnectionState] it goes through to `states`.
+    ///
+    /// This starts with [ConnectionState::Connecting], and the runner keeps
+    /// sending any later transitions to `states` as well.
+    /// See [AsyncRunner::connection_states()].
+    pub async fn connect_with_states<C>(
+        connector: C,
+        user_config: &UserConfig,
+        states: Sender<ConnectionState>,
+    ) -> Result<Self, Error>
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        let mut builder = Self::builder();
+        builder.state_tx.replace(states);
+        builder.connect(connector, user_config).await
+    }
+
+    pub(super) async fn connect_inner<C>(
+        mut connector: C,
+        user_config: &UserConfig,
+        builder: AsyncRunnerBuilder,
+    ) -> Result<Self, Error>
+    where
+        C: Connector + 'static,
         for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
     {
-        log::debug!("connecting");
-        let mut stream = { connector }.connect().await?;
-        log::debug!("connection established");
+        use crate::util::{Either::*, FutExt as _};
+
+        let AsyncRunnerBuilder {
+            timeout,
+            read_buffer_size,
+            max_message_size,
+            writer_capacity,
+            dedupe_window,
+            state_tx,
+        } = builder;
+
+        let send_state = |state| {
+            if let Some(tx) = &state_tx {
+                let _ = tx.try_send(state);
+            }
+        };
+
+        let result = async {
+            send_state(ConnectionState::Connecting);
+            log::debug!("connecting");
+            let stream = connector.connect().await?;
+            log::debug!("connection established");
+            send_state(ConnectionState::Registering);
+            Ok::<_, Error>(stream)
+        }
+        .await;
+
+        let mut stream = match result {
+            Ok(stream) => stream,
+            Err(err) => {
+                send_state(ConnectionState::Closed);
+                return Err(err);
+            }
+        };
 
         log::debug!("registering");
         let mut buf = vec![];
@@ -78,31 +205,50 @@ impl AsyncRunner {
         let read = async_dup::Arc::new(stream);
         let write = read.clone();
 
-        let read: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(read);
-        let write: Box<dyn AsyncWrite + Send + Sync + Unpin> = Box::new(write);
+        let read: BoxedRead = Box::new(read);
+        let write: BoxedWrite = Box::new(write);
 
-        let mut decoder = AsyncDecoder::new(read);
+        let mut decoder = AsyncDecoder::with_capacity(read, read_buffer_size)
+            .with_max_message_size(max_message_size);
         let mut encoder = AsyncEncoder::new(write);
 
         log::debug!("waiting for the connection to be ready");
         let mut missed_messages = VecDeque::new();
-        let identity = Self::wait_for_ready(
+        let ready = Self::wait_for_ready(
             &mut decoder,
             &mut encoder,
             user_config,
             &mut missed_messages,
         )
-        .await?;
+        .first(futures_timer::Delay::new(timeout));
+
+        let identity = match ready.await {
+            Left(Ok(identity)) => identity,
+            Left(Err(err)) => {
+                send_state(ConnectionState::Closed);
+                return Err(err);
+            }
+            Right(..) => {
+                log::warn!("connection was not ready after {:?}", timeout);
+                send_state(ConnectionState::Closed);
+                return Err(Error::RegistrationTimeout);
+            }
+        };
+        send_state(ConnectionState::Connected);
         log::debug!("connection is ready: {:?}", identity);
 
-        let (writer_tx, writer_rx) = crate::channel::unbounded();
+        let (writer_tx, writer_rx) = match writer_capacity {
+            Some(capacity) => crate::channel::bounded(capacity),
+            None => crate::channel::unbounded(),
+        };
         let (notify, notify_handle) = Notify::new();
         let (activity_tx, activity_rx) = crate::channel::bounded(32);
 
         let writer = AsyncWriter::new(MpscWriter::new(writer_tx), activity_tx);
 
         let timeout_state = TimeoutState::Start;
-        let channels = Channels::default();
+        let clock = crate::clock::system();
+        let channels = Channels::new(clock.clone());
 
         let global_rate_limit = RateLimit::from_class(RateClass::Regular);
 
@@ -117,17 +263,215 @@ impl AsyncRunner {
             notify_handle,
 
             timeout_state,
+            clock,
 
             decoder,
             encoder,
 
             writer,
             global_rate_limit,
+            join_rate_limit: RateLimit::trovo_join(),
+            join_timeout: JOIN_TIMEOUT,
+            drain_timeout: DRAIN_TIMEOUT,
+            wait_timeout: WAIT_TIMEOUT,
 
             missed_messages,
+
+            connector: BoxedConnector::new(connector),
+            user_config: user_config.clone(),
+
+            last_raw: String::new(),
+
+            queued_joins: VecDeque::new(),
+
+            command_result_tx: None,
+            pending_commands: VecDeque::new(),
+
+            sent_nonces: VecDeque::new(),
+            dedupe_window,
+
+            connection_state: ConnectionState::Connected,
+            connection_state_tx: state_tx,
+
+            outbound_filter: None,
         })
     }
 
+    /// Tear down this runner, returning the connector, the [UserConfig] and the channels you were on.
+    ///
+    /// These can be used to build a new runner with the same state, e.g. after a reconnect.
+    /// The channels are the ones you joined, not ones you've only sent messages (or whispers) to.
+    pub fn into_parts(self) -> (BoxedConnector, UserConfig, HashSet<String>) {
+        let channels = self.channels.joined().map(ToString::to_string).collect();
+        (self.connector, self.user_config, channels)
+    }
+
+    /// Connect and run until you quit, reconnecting when the connection is lost
+    ///
+    /// Each message is given to `on_message`, along with the current runner so
+    /// you can use its [writer](AsyncRunner::writer()) or [quit handle](AsyncRunner::quit_handle()).
+    ///
+    /// When the connection ends (an EOF, an I/O error, a timeout or a `RECONNECT`),
+    /// or connecting fails, `on_retry` is called with the attempt number (starting at `1`)
+    /// and the error, then this waits as long as `strategy` says before connecting again.
+    /// The attempts start over once a connection is ready, and the channels you were
+    /// on (or were still joining) are joined again, paced by the JOIN rate limit.
+    ///
+    /// This returns `Ok(())` once you quit. It returns the last error if `strategy`
+    /// gives up, or right away for any other error.
+    pub async fn run_with_retry<C, M, R>(
+        connector: C,
+        user_config: &UserConfig,
+        strategy: RetryStrategy,
+        mut on_message: M,
+        mut on_retry: R,
+    ) -> Result<(), Error>
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+        M: FnMut(&mut Self, Commands<'static>) + Send,
+        R: FnMut(usize, &Error) + Send,
+    {
+        // the channels to join once connected. these are kept until they're joined,
+        // so the ones that weren't joined before the connection was lost aren't forgotten
+        let mut channels = HashSet::<String>::new();
+        // keep the JOIN rate limit between connections, so reconnecting doesn't refill it
+        let mut join_rate_limit = None;
+        let mut attempt = 0;
+
+        loop {
+            let err = match Self::connect(connector.clone(), user_config).await {
+                Ok(mut runner) => {
+                    attempt = 0;
+                    if let Some(rate_limit) = join_rate_limit.take() {
+                        runner.join_rate_limit = rate_limit;
+                    }
+
+                    // these are paced by the JOIN rate limit
+                    for channel in &channels {
+                        runner.queue_join(channel);
+                    }
+
+                    let err = loop {
+                        match runner.next_message().await {
+                            Ok(Status::Message(msg)) => {
+                                if let Commands::Join(msg) = &msg {
+                                    if msg.name() == runner.identity.username() {
+                                        channels.remove(msg.channel());
+                                    }
+                                }
+                                on_message(&mut runner, msg)
+                            }
+                            Ok(Status::Quit) => return Ok(()),
+                            Ok(Status::Eof) => break Error::UnexpectedEof,
+                            Err(err) => break err,
+                        }
+                    };
+
+                    // rejoin the ones we're on, and the ones we haven't joined yet
+                    channels.extend(runner.channels.joined().map(ToString::to_string));
+                    channels.extend(runner.queued_joins);
+                    join_rate_limit.replace(runner.join_rate_limit);
+                    err
+                }
+                Err(err) => err,
+            };
+
+            if !matches!(
+                err,
+                Error::Io(..)
+                    | Error::UnexpectedEof
+                    | Error::TimedOut
+                    | Error::RegistrationTimeout
+                    | Error::ShouldReconnect
+            ) {
+                return Err(err);
+            }
+
+            attempt += 1;
+            let delay = match strategy.delay_for(attempt) {
+                Some(delay) => delay,
+                None => return Err(err),
+            };
+
+            log::warn!("connection lost: {}. reconnecting in {:?}", err, delay);
+            on_retry(attempt, &err);
+            futures_timer::Delay::new(delay).await;
+        }
+    }
+
+    /// Get the most recent line read from the connection, if any.
+    ///
+    /// This is kept even if the line could not be decoded, so it can be used to
+    /// inspect the offending line after a decode error.
+    pub fn last_raw(&self) -> Option<&str> {
+        Some(&*self.last_raw).filter(|s| !s.is_empty())
+    }
+
+    /// Get a channel of [CommandResult]s, pairing the slash commands (e.g. `/ban`) you
+    /// send with the `NOTICE` Trovo replies with.
+    ///
+    /// This is opt-in, only commands sent after calling this are tracked. The notices
+    /// are still returned from [AsyncRunner::next_message()] as well.
+    ///
+    /// Calling this again replaces the previous channel.
+    pub fn command_result(&mut self) -> Receiver<CommandResult> {
+        let (tx, rx) = crate::channel::unbounded();
+        self.command_result_tx.replace(tx);
+        self.pending_commands.clear();
+        rx
+    }
+
+    /// The current state of the connection
+    pub fn connection_state(&self) -> ConnectionState {
+        self.connection_state
+    }
+
+    /// Get a channel of the [ConnectionState]s this runner transitions to,
+    /// e.g. to drive a connection indicator.
+    ///
+    /// Only transitions after calling this are sent. To also get the
+    /// states while connecting, use [AsyncRunner::connect_with_states()].
+    ///
+    /// Calling this again replaces the previous channel.
+    pub fn connection_states(&mut self) -> Receiver<ConnectionState> {
+        let (tx, rx) = crate::channel::unbounded();
+        self.connection_state_tx.replace(tx);
+        rx
+    }
+
+    fn set_connection_state(&mut self, state: ConnectionState) {
+        if self.connection_state == state {
+            return;
+        }
+        self.connection_state = state;
+        if let Some(tx) = &self.connection_state_tx {
+            let _ = tx.try_send(state);
+        }
+    }
+
+    /// Whether this message is an echo of one you sent.
+    ///
+    /// If you sent the `PRIVMSG` with a `client-nonce` tag, Trovo attaches the same
+    /// nonce to the echo, so it can be matched to one of your recently sent messages.
+    /// Otherwise, this checks whether it was sent by your [Identity].
+    pub fn is_own_echo(&self, msg: &Privmsg<'_>) -> bool {
+        match msg.tags().get("client-nonce") {
+            Some(nonce) if self.sent_nonces.iter().any(|sent| sent == nonce) => true,
+            _ => msg.name() == self.identity.username(),
+        }
+    }
+
+    /// Update the color of your cached [Identity].
+    ///
+    /// Sending a [color](crate::commands::color()) command does this for you.
+    /// Only a [Full](Identity::Full) identity has a color, so this does nothing otherwise.
+    pub fn set_identity_color_locally(&mut self, color: Color) {
+        if let Identity::Full { color: old, .. } = &mut self.identity {
+            *old = color;
+        }
+    }
+
     /// Check whether you're on this channel
     pub fn is_on_channel(&self, channel: &str) -> bool {
         self.channels.is_on(channel)
@@ -152,7 +496,192 @@ impl AsyncRunner {
         self.notify_handle.clone()
     }
 
+    /// Join each of the `channels`, waiting for all of them to complete
+    ///
+    /// This is [AsyncRunner::join_multiple()], returning the first error instead
+    /// of a result for each channel. Like [AsyncRunner::join()], these are paced
+    /// by the JOIN rate limit.
+    pub async fn join_many<'a, I>(&mut self, channels: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = &'a str> + Send + Sync,
+        I::IntoIter: Send + Sync,
+    {
+        let channels = channels.into_iter().collect::<Vec<_>>();
+        self.join_multiple(&channels).await.into_iter().collect()
+    }
+
+    /// Replace the rate limit used to pace joining channels.
+    ///
+    /// This defaults to [RateLimit::trovo_join()].
+    pub fn set_join_rate_limit(&mut self, rate_limit: RateLimit) {
+        self.join_rate_limit = rate_limit;
+        self.join_rate_limit.set_clock(self.clock.clone());
+    }
+
+    /// Replace how long [AsyncRunner::join_multiple()] waits for each channel to be joined.
+    ///
+    /// This defaults to `10` seconds.
+    pub fn set_join_timeout(&mut self, timeout: Duration) {
+        self.join_timeout = timeout;
+    }
+
+    /// Use this [Clock] for the rate limits and timeouts, instead of the [SystemClock](crate::clock::SystemClock)
+    ///
+    /// This is meant for tests, e.g. with a [TestClock](crate::test::TestClock).
+    /// It replaces the clock of the global, join and channel rate limits.
+    /// Waiting (e.g. for a rate limit to refill) still uses real time.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        let clock: Arc<dyn Clock> = Arc::new(clock);
+        self.global_rate_limit.set_clock(clock.clone());
+        self.join_rate_limit.set_clock(clock.clone());
+        for channel in self.channels.map.values_mut() {
+            channel.rate_limited.rate_limit.set_clock(clock.clone());
+            channel.clock = clock.clone();
+        }
+        self.channels.clock = clock.clone();
+        self.clock = clock;
+    }
+
+    /// Replace how long quitting waits for [pending writes](AsyncRunner::drain_pending_writes()) to be sent.
+    ///
+    /// This defaults to `5` seconds.
+    pub fn set_drain_timeout(&mut self, timeout: Duration) {
+        self.drain_timeout = timeout;
+    }
+
+    /// Replace how long [AsyncRunner::send_and_wait()] waits for a matching message.
+    ///
+    /// This defaults to `10` seconds.
+    pub fn set_wait_timeout(&mut self, timeout: Duration) {
+        self.wait_timeout = timeout;
+    }
+
+    /// Inspect each message from the [writer](AsyncRunner::writer()) before it is written.
+    ///
+    /// The `filter` gets the encoded message (including its `\r\n`), and decides whether to
+    /// [allow](Filter::Allow), [replace](Filter::Replace) or [drop](Filter::Drop) it.
+    /// This happens before the message is queued on its channel's rate limit, so a dropped
+    /// message doesn't count against it.
+    pub fn set_outbound_filter(&mut self, filter: OutboundFilter) {
+        self.outbound_filter.replace(filter);
+    }
+
+    /// Join all of these `channels`, sending all of the `JOIN`s before waiting for them to complete
+    ///
+    /// This returns a result for each channel, in the same order. A channel that
+    /// isn't joined within the [join timeout](AsyncRunner::set_join_timeout())
+    /// gets an [Error::JoinTimeout], the other channels are unaffected.
+    ///
+    /// The channels are normalized first (e.g. `Foo` is `#foo`), and each one is only
+    /// joined once. Any later entries for the same channel get an [Error::AlreadyOnChannel].
+    ///
+    /// The `JOIN`s are still paced by the [join rate limit](AsyncRunner::set_join_rate_limit()).
+    pub async fn join_multiple(&mut self, channels: &[&str]) -> Vec<Result<(), Error>> {
+        use crate::util::*;
+
+        let channels = channels
+            .iter()
+            .map(|channel| commands::Channel::new(channel).to_string())
+            .collect::<Vec<_>>();
+
+        let mut results = HashMap::<String, Result<(), Error>>::new();
+        let mut pending = Vec::with_capacity(channels.len());
+
+        for channel in &channels {
+            if self.is_on_channel(channel)
+                || pending.contains(channel)
+                || results.contains_key(channel)
+            {
+                continue;
+            }
+
+            while let Err(wait) = self.join_rate_limit.consume(1) {
+                log::debug!("join rate limit hit, waiting {:?} to join '{}'", wait, channel);
+                futures_timer::Delay::new(wait).await;
+            }
+
+            log::debug!("joining '{}'", channel);
+            if let Err(err) = self.encoder.encode(commands::join(channel)).await {
+                results.insert(channel.clone(), Err(err.into()));
+                continue;
+            }
+
+            pending.push(channel.clone());
+        }
+
+        let deadline = self.clock.now() + self.join_timeout;
+        let mut queue = VecDeque::new();
+
+        while !pending.is_empty() {
+            let timeout = deadline.saturating_duration_since(self.clock.now());
+            let msg = match self.step().first(futures_timer::Delay::new(timeout)).await {
+                Left(Ok(StepResult::Status(Status::Message(msg)))) => msg,
+                Left(Ok(StepResult::Nothing)) => continue,
+                Left(Ok(StepResult::Status(..))) => {
+                    for channel in pending.drain(..) {
+                        results.insert(channel, Err(Error::UnexpectedEof));
+                    }
+                    break;
+                }
+                Left(Err(err)) => {
+                    // the error can only be given once, the rest are left without a connection
+                    let mut err = Some(err);
+                    for channel in pending.drain(..) {
+                        let err = err.take().unwrap_or(Error::UnexpectedEof);
+                        results.insert(channel, Err(err));
+                    }
+                    break;
+                }
+                Right(..) => break,
+            };
+
+            let (channel, result) = match &msg {
+                // check to see if it was us that joined the channel
+                Commands::Join(msg) if msg.name() == self.identity.username() => {
+                    (msg.channel(), Ok(()))
+                }
+                // check to see if we were banned
+                Commands::Notice(msg) if matches!(msg.msg_id(), Some(MessageId::MsgBanned)) => {
+                    let channel = msg.channel().to_string();
+                    (msg.channel(), Err(Error::BannedFromChannel { channel }))
+                }
+                _ => {
+                    queue.push_back(msg);
+                    continue;
+                }
+            };
+
+            match pending.iter().position(|pending| pending == channel) {
+                Some(pos) => {
+                    let channel = pending.remove(pos);
+                    log::debug!("joined '{}'", channel);
+                    results.insert(channel, result);
+                }
+                None => queue.push_back(msg),
+            }
+        }
+
+        for channel in pending {
+            log::warn!("timed out waiting to join '{}'", channel);
+            results.insert(channel.clone(), Err(Error::JoinTimeout { channel }));
+        }
+
+        self.missed_messages.extend(queue);
+
+        // only the first entry for a channel gets its result
+        channels
+            .into_iter()
+            .map(|channel| match results.remove(&channel) {
+                Some(result) => result,
+                None => Err(Error::AlreadyOnChannel { channel }),
+            })
+            .collect()
+    }
+
     /// Join `channel` and wait for it to complete
+    ///
+    /// Trovo limits how quickly you can join channels, so this will wait if you've
+    /// hit that limit. See [AsyncRunner::set_join_rate_limit()].
     pub async fn join(&mut self, channel: &str) -> Result<(), Error> {
         if self.is_on_channel(channel) {
             return Err(Error::AlreadyOnChannel {
@@ -160,6 +689,11 @@ impl AsyncRunner {
             });
         }
 
+        while let Err(wait) = self.join_rate_limit.consume(1) {
+            log::debug!("join rate limit hit, waiting {:?} to join '{}'", wait, channel);
+            futures_timer::Delay::new(wait).await;
+        }
+
         log::debug!("joining '{}'", channel);
         self.encoder.encode(commands::join(channel)).await?;
 
@@ -186,11 +720,9 @@ impl AsyncRunner {
             })
             .await?;
 
-        if let Some(status) = status {
-            match status {
-                Status::Quit | Status::Eof => return Err(Error::UnexpectedEof),
-                _ => unimplemented!(),
-            }
+        // wait_for only gives up on a Quit or an Eof
+        if status.is_some() {
+            return Err(Error::UnexpectedEof);
         }
 
         self.missed_messages.extend(queue);
@@ -200,6 +732,58 @@ impl AsyncRunner {
         Ok(())
     }
 
+    /// Join `channel` and wait for the `USERSTATE` Trovo sends for it
+    ///
+    /// The `USERSTATE` is your identity in that channel, e.g. whether you're a
+    /// moderator there. Trovo only sends it to a logged in user that has both the
+    /// `Tags` and `Commands` capabilities, otherwise this returns `None` once the
+    /// channel is joined.
+    ///
+    /// Other messages received while waiting will be returned by
+    /// [AsyncRunner::next_message()] afterwards.
+    pub async fn join_with_userstate(
+        &mut self,
+        channel: &str,
+    ) -> Result<Option<UserState<'static>>, Error> {
+        let channel = commands::Channel::new(channel).to_string();
+
+        // the USERSTATE may show up while we're waiting for the JOIN
+        let start = self.missed_messages.len();
+        self.join(&channel).await?;
+
+        let caps = self.identity.capabilities();
+        if matches!(self.identity, Identity::Anonymous { .. }) || !(caps.tags && caps.commands) {
+            return Ok(None);
+        }
+
+        log::debug!("waiting for the user state of '{}'", channel);
+        self.take_or_wait_for(start, |msg| match msg {
+            Commands::UserState(msg) if msg.channel() == channel => Ok(msg),
+            msg => Err(msg),
+        })
+        .await
+        .map(Some)
+    }
+
+    /// Queue `channel` to be joined, without waiting for it to complete.
+    ///
+    /// The connection is ready once [AsyncRunner::connect()] returns, so any
+    /// queued channels are joined at the start of the next [AsyncRunner::step()].
+    /// Unlike [AsyncRunner::join()], this doesn't wait for Trovo to confirm the join,
+    /// but the joins are still paced by the JOIN rate limit.
+    ///
+    /// The channel is normalized first, so queueing a channel you're already on
+    /// (or have already queued) does nothing.
+    pub fn queue_join(&mut self, channel: &str) {
+        let channel = commands::Channel::new(channel).to_string();
+        if self.is_on_channel(&channel) || self.queued_joins.contains(&channel) {
+            return;
+        }
+
+        log::debug!("queueing a join for '{}'", channel);
+        self.queued_joins.push_back(channel);
+    }
+
     /// Part `channel` and wait for it to complete
     pub async fn part(&mut self, channel: &str) -> Result<(), Error> {
         if !self.is_on_channel(channel) {
@@ -226,11 +810,9 @@ impl AsyncRunner {
             })
             .await?;
 
-        if let Some(status) = status {
-            match status {
-                Status::Quit | Status::Eof => return Err(Error::UnexpectedEof),
-                _ => unimplemented!(),
-            }
+        // wait_for only gives up on a Quit or an Eof
+        if status.is_some() {
+            return Err(Error::UnexpectedEof);
         }
         log::debug!("left '{}'", channel);
 
@@ -239,143 +821,499 @@ impl AsyncRunner {
         Ok(())
     }
 
-    /// Get the next message. You'll usually want to call this in a loop
-    pub async fn next_message(&mut self) -> Result<Status<'static>, Error> {
-        use crate::util::{Either::*, FutExt as _};
+    /// Part and rejoin `channel`, returning the fresh `ROOMSTATE` Trovo sends on join
+    ///
+    /// There isn't a command to ask for the room state, so this has side effects:
+    /// other users in the channel will see you leave and join again, and any
+    /// messages sent to the channel while you were gone are missed.
+    ///
+    /// Other messages received while waiting will be returned by
+    /// [AsyncRunner::next_message()] afterwards.
+    ///
+    /// If the rejoin fails because of the connection (or it timed out) after the part
+    /// went through, the channel is queued with [AsyncRunner::queue_join()] so the
+    /// runner doesn't stay parted. Errors that another attempt won't fix, like
+    /// [Error::BannedFromChannel], are only returned.
+    pub async fn refresh_room_state(&mut self, channel: &str) -> Result<RoomState<'static>, Error> {
+        let channel = commands::Channel::new(channel).to_string();
+        self.part(&channel).await?;
+
+        // the ROOMSTATE may show up while we're waiting for the JOIN
+        let start = self.missed_messages.len();
+        if let Err(err) = self.join(&channel).await {
+            if matches!(
+                err,
+                Error::Io(..)
+                    | Error::UnexpectedEof
+                    | Error::TimedOut
+                    | Error::JoinTimeout { .. }
+                    | Error::ShouldReconnect
+            ) {
+                log::warn!("cannot rejoin '{}', queueing it: {}", channel, err);
+                self.queue_join(&channel);
+            }
+            return Err(err);
+        }
 
-        loop {
-            match self.step().await? {
-                StepResult::Nothing => continue,
-                StepResult::Status(Status::Quit) => {
-                    if let Left(_notified) = self.notify.wait().now_or_never().await {
-                        // close everything
-                        self.writer_rx.close();
-                        self.activity_rx.close();
+        log::debug!("waiting for the room state of '{}'", channel);
+        self.take_or_wait_for(start, |msg| match msg {
+            Commands::RoomState(msg) if msg.channel() == channel => Ok(msg),
+            msg => Err(msg),
+        })
+        .await
+    }
 
-                        // and then drain any remaining items
-                        while self.available_queued_messages() > 0 {
-                            self.drain_queued_messages().await?;
-                            futures_lite::future::yield_now().await;
-                        }
+    // take the first message that `take` accepts, from the messages missed since `start`
+    // or otherwise from the connection. the rest are kept for `next_message`
+    async fn take_or_wait_for<T, F>(&mut self, start: usize, take: F) -> Result<T, Error>
+    where
+        F: Fn(Commands<'static>) -> Result<T, Commands<'static>> + Send + Sync,
+    {
+        // set aside what we've already seen, so we only wait on the connection
+        let mut missed = std::mem::take(&mut self.missed_messages);
+
+        let mut seen = missed.split_off(std::cmp::min(start, missed.len()));
+        let mut found = None;
+        while let Some(msg) = seen.pop_front() {
+            match take(msg) {
+                Ok(msg) => {
+                    found.replace(msg);
+                    break;
+                }
+                Err(msg) => missed.push_back(msg),
+            }
+        }
+        missed.extend(seen);
+
+        let result = match found {
+            Some(msg) => Ok(msg),
+            None => loop {
+                match self.step().await {
+                    Ok(StepResult::Status(Status::Message(msg))) => match take(msg) {
+                        Ok(msg) => break Ok(msg),
+                        Err(msg) => missed.push_back(msg),
+                    },
+                    Ok(StepResult::Nothing) => continue,
+                    Ok(StepResult::Status(..)) => break Err(Error::UnexpectedEof),
+                    Err(err) => break Err(err),
+                }
+            },
+        };
 
-                        // and finally send the quit
-                        self.encoder.encode(commands::raw("QUIT\r\n")).await?;
+        self.missed_messages = missed;
+        result
+    }
 
-                        // and signal that we've quit
-                        break Ok(Status::Quit);
-                    }
+    /// Send a `PING` with a unique token and wait for the matching `PONG`,
+    /// returning the round-trip time.
+    ///
+    /// Any other messages received while waiting will be returned by
+    /// [AsyncRunner::next_message()] afterwards.
+    pub async fn ping(&mut self) -> Result<Duration, Error> {
+        let token = format!("{}-{}", crate::util::timestamp(), fastrand::u32(..));
+
+        log::debug!("sending a ping with token '{}'", token);
+        let start = Instant::now();
+        self.encoder.encode(commands::ping(&token)).await?;
+
+        // other messages that show up while waiting are kept for `next_message`
+        let mut queue = VecDeque::new();
+        let result = loop {
+            match self.step().await {
+                Ok(StepResult::Status(Status::Message(Commands::Pong(msg))))
+                    if msg.token() == token =>
+                {
+                    break Ok(start.elapsed())
                 }
-                StepResult::Status(status) => break Ok(status),
+                Ok(StepResult::Status(Status::Message(msg))) => queue.push_back(msg),
+                Ok(StepResult::Status(Status::Quit)) | Ok(StepResult::Status(Status::Eof)) => {
+                    break Err(Error::UnexpectedEof)
+                }
+                Ok(StepResult::Nothing) => continue,
+                Err(err) => break Err(err),
             }
-        }
+        };
+
+        self.missed_messages.extend(queue);
+
+        let elapsed = result?;
+        log::debug!("got a pong for '{}' after {:?}", token, elapsed);
+        Ok(elapsed)
     }
 
-    /// Single step the loop. This is useful for testing.
-    pub async fn step(&mut self) -> Result<StepResult<'static>, Error> {
+    /// Send `cmd` and wait for a message of type `T` that satisfies `predicate`
+    ///
+    /// This is useful for commands whose effect shows up as another message,
+    /// e.g. `/slow` is answered with a `ROOMSTATE`. If no matching message
+    /// arrives within the [wait timeout](AsyncRunner::set_wait_timeout()) an
+    /// [Error::WaitTimeout] is returned.
+    ///
+    /// The command is written immediately, it isn't queued on the channel's rate limit.
+    ///
+    /// Other messages received while waiting will be returned by
+    /// [AsyncRunner::next_message()] afterwards.
+    pub async fn send_and_wait<C, T, F>(&mut self, cmd: C, predicate: F) -> Result<T, Error>
+    where
+        C: Encodable + Send + Sync,
+        T: FromIrcMessage<'static> + Send,
+        F: Fn(&T) -> bool + Send + Sync,
+    {
         use crate::util::*;
-        use crate::IntoOwned as _;
 
-        if let Some(msg) = self.missed_messages.pop_front() {
-            return Ok(StepResult::Status(Status::Message(msg)));
-        }
+        self.encoder.encode(cmd).await?;
 
-        let select = self
-            .decoder
-            .read_message()
-            .either(self.activity_rx.recv())
+        let deadline = self.clock.now() + self.wait_timeout;
+        let mut queue = VecDeque::new();
+
+        let result = loop {
+            let timeout = deadline.saturating_duration_since(self.clock.now());
+            let msg = match self.step().first(futures_timer::Delay::new(timeout)).await {
+                Left(Ok(StepResult::Status(Status::Message(msg)))) => msg,
+                Left(Ok(StepResult::Nothing)) => continue,
+                Left(Ok(StepResult::Status(..))) => break Err(Error::UnexpectedEof),
+                Left(Err(err)) => break Err(err),
+                Right(..) => {
+                    log::warn!("timed out waiting for a reply");
+                    break Err(Error::WaitTimeout);
+                }
+            };
+
+            match T::from_irc(msg.clone().into_irc()) {
+                Ok(reply) if predicate(&reply) => break Ok(reply),
+                _ => queue.push_back(msg),
+            }
+        };
+
+        self.missed_messages.extend(queue);
+
+        result
+    }
+
+    /// Write everything still waiting in the [writer](AsyncRunner::writer()) to the connection
+    ///
+    /// This is best-effort: the messages are still paced by the rate limits, so
+    /// this gives up after `timeout`. It returns whether everything was written.
+    ///
+    /// Quitting does this for you (see [AsyncRunner::set_drain_timeout()]), so
+    /// something like a goodbye message sent just before quitting isn't lost.
+    pub async fn drain_pending_writes(&mut self, timeout: Duration) -> Result<bool, Error> {
+        while let Some(write_data) = self.writer_rx.try_recv() {
+            self.handle_write(write_data)?;
+        }
+
+        let deadline = self.clock.now() + timeout;
+        while self.available_queued_messages() > 0 {
+            self.drain_queued_messages().await?;
+            if self.available_queued_messages() == 0 {
+                break;
+            }
+
+            let left = deadline.saturating_duration_since(self.clock.now());
+            if left == Duration::from_secs(0) {
+                log::warn!(
+                    "gave up on {} pending message(s) after {:?}",
+                    self.available_queued_messages(),
+                    timeout
+                );
+                return Ok(false);
+            }
+
+            // wait for the rate limits to refill a bit
+            futures_timer::Delay::new(std::cmp::min(left, Duration::from_millis(50))).await;
+        }
+
+        self.encoder.flush().await?;
+        Ok(true)
+    }
+
+    /// Get the next message. You'll usually want to call this in a loop
+    ///
+    /// Heartbeats are still forwarded: a `PING` is answered automatically, but
+    /// is also returned as [Commands::Ping], as is any [Commands::Pong].
+    pub async fn next_message(&mut self) -> Result<Status<'static>, Error> {
+        let status = self.next_status(None).await?;
+        Ok(status.expect("there is no deadline to give up at"))
+    }
+
+    /// Get the next message, waiting up to `dur` for it.
+    ///
+    /// This returns `Ok(None)` if nothing arrived in time, so you can do other
+    /// work (e.g. a periodic task) and then call this again. Nothing is lost when
+    /// this gives up, a partially read message will be finished by the next call.
+    pub async fn next_message_timeout(
+        &mut self,
+        dur: Duration,
+    ) -> Result<Option<Status<'static>>, Error> {
+        let mut deadline = futures_timer::Delay::new(dur);
+        self.next_status(Some(&mut deadline)).await
+    }
+
+    async fn next_status(
+        &mut self,
+        deadline: Option<&mut futures_timer::Delay>,
+    ) -> Result<Option<Status<'static>>, Error> {
+        let result = self.wait_for_status(deadline).await;
+        match &result {
+            Ok(Some(Status::Quit))
+            | Ok(Some(Status::Eof))
+            | Err(Error::Io(..))
+            | Err(Error::UnexpectedEof)
+            | Err(Error::TimedOut) => self.set_connection_state(ConnectionState::Closed),
+            Err(Error::ShouldReconnect) => self.set_connection_state(ConnectionState::Reconnecting),
+            _ => {}
+        }
+        result
+    }
+
+    async fn wait_for_status(
+        &mut self,
+        mut deadline: Option<&mut futures_timer::Delay>,
+    ) -> Result<Option<Status<'static>>, Error> {
+        use crate::util::{Either::*, FutExt as _};
+
+        loop {
+            let step = match self.step_until(deadline.as_deref_mut()).await? {
+                Some(step) => step,
+                None => break Ok(None),
+            };
+
+            match step {
+                StepResult::Nothing => continue,
+                StepResult::Status(Status::Quit) => {
+                    if let Left(_notified) = self.notify.wait().now_or_never().await {
+                        // close everything
+                        self.writer_rx.close();
+                        self.activity_rx.close();
+
+                        // and then drain any remaining items
+                        self.drain_pending_writes(self.drain_timeout).await?;
+
+                        // and finally send the quit
+                        self.encoder.encode(commands::raw("QUIT")).await?;
+
+                        // and signal that we've quit
+                        break Ok(Some(Status::Quit));
+                    }
+                }
+                StepResult::Status(status) => break Ok(Some(status)),
+            }
+        }
+    }
+
+    /// Single step the loop. This is useful for testing.
+    pub async fn step(&mut self) -> Result<StepResult<'static>, Error> {
+        let step = self.step_until(None).await?;
+        Ok(step.expect("there is no deadline to give up at"))
+    }
+
+    // sends as many queued joins as the JOIN rate limit allows, returning how
+    // long to wait for the next one if any are left
+    async fn send_queued_joins(&mut self) -> Result<Option<Duration>, Error> {
+        while let Some(channel) = self.queued_joins.pop_front() {
+            if self.is_on_channel(&channel) {
+                continue;
+            }
+
+            if let Err(wait) = self.join_rate_limit.consume(1) {
+                log::debug!("join rate limit hit, waiting {:?} to join '{}'", wait, channel);
+                self.queued_joins.push_front(channel);
+                return Ok(Some(wait));
+            }
+
+            log::debug!("joining queued channel '{}'", channel);
+            self.encoder.encode(commands::join(&channel)).await?;
+        }
+        Ok(None)
+    }
+
+    // single step the loop, but stop waiting for the connection once `deadline` fires.
+    // this returns `None` if it did
+    async fn step_until(
+        &mut self,
+        deadline: Option<&mut futures_timer::Delay>,
+    ) -> Result<Option<StepResult<'static>>, Error> {
+        use crate::util::*;
+        use crate::IntoOwned as _;
+
+        // if the JOIN rate limit was hit, wake up when it allows the next one
+        let join_wait = self.send_queued_joins().await?;
+        let join_wait = async move {
+            match join_wait {
+                Some(wait) => futures_timer::Delay::new(wait).await,
+                None => futures_lite::future::pending().await,
+            }
+        };
+
+        if let Some(msg) = self.missed_messages.pop_front() {
+            return Ok(Some(StepResult::Status(Status::Message(msg))));
+        }
+
+        let select = self
+            .decoder
+            .read_message()
+            .either(self.activity_rx.recv())
             .either(self.writer_rx.recv())
             .either(self.notify.wait())
-            .either(super::timeout::next_delay())
-            .await;
+            .either(super::timeout::next_delay().either(join_wait));
+
+        // only give up while waiting, so nothing that was read is lost
+        let select = match deadline {
+            Some(deadline) => match select.either(deadline).await {
+                Left(select) => select,
+                Right(()) => return Ok(None),
+            },
+            None => select.await,
+        };
 
         match select {
             Left(Left(Left(Left(msg)))) => {
                 let msg = match msg {
                     Err(DecodeError::Eof) => {
                         log::info!("got an EOF, exiting main loop");
-                        return Ok(StepResult::Status(Status::Eof));
+                        return Ok(Some(StepResult::Status(Status::Eof)));
                     }
                     Err(err) => {
-                        log::warn!("read an error: {}", err);
+                        self.last_raw = self.decoder.last_line().to_string();
+                        log::warn!("read an error: {} (line: {:?})", err, self.last_raw);
                         return Err(err.into());
                     }
                     Ok(msg) => msg,
                 };
 
-                self.timeout_state = TimeoutState::activity();
+                self.last_raw.clear();
+                self.last_raw.push_str(msg.get_raw());
 
-                let all = Commands::from_irc(msg) //
-                    .expect("msg identity conversion should be upheld")
-                    .into_owned();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
+
+                // a malformed line from the server shouldn't take down the runner,
+                // so just give it to the user as-is
+                let all = match Commands::from_irc(msg.clone()) {
+                    Ok(all) => all,
+                    Err(err) => {
+                        log::warn!("could not parse: {} (line: {:?})", err, self.last_raw);
+                        Commands::Raw(msg)
+                    }
+                }
+                .into_owned();
 
                 self.check_messages(&all).await?;
 
-                return Ok(StepResult::Status(Status::Message(all)));
+                return Ok(Some(StepResult::Status(Status::Message(all))));
             }
 
             Left(Left(Left(Right(Some(_activity))))) => {
-                self.timeout_state = TimeoutState::activity();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
             }
 
-            Left(Left(Right(Some(write_data)))) => {
-                // TODO provide a 'bytes' flavored parser
-                let msg = std::str::from_utf8(&*write_data).map_err(Error::InvalidUtf8)?;
-                let res = crate::irc::parse_one(msg) //
-                    .expect("encoder should produce valid IRC messages");
-                let msg = res.1;
+            Left(Left(Right(Some(write_data)))) => self.handle_write(write_data)?,
 
-                if let crate::irc::IrcMessage::PRIVMSG = msg.get_command() {
-                    if let Some(ch) = msg.nth_arg(0) {
-                        if !self.channels.is_on(ch) {
-                            self.channels.add(ch)
-                        }
+            Left(Right(_notified)) => return Ok(Some(StepResult::Status(Status::Quit))),
 
-                        let ch = self.channels.get_mut(ch).unwrap();
-                        if ch.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
-                            ch.reset_rate_limit();
-                        }
+            Right(Right(_join_wait)) => return Ok(Some(StepResult::Nothing)),
 
-                        ch.rate_limited.enqueue(write_data)
-                    }
-                }
-            }
-
-            Left(Right(_notified)) => return Ok(StepResult::Status(Status::Quit)),
-
-            Right(_timeout) => {
+            Right(Left(_timeout)) => {
                 log::info!("idle connection detected, sending a ping");
                 let ts = timestamp().to_string();
                 self.encoder.encode(commands::ping(&ts)).await?;
-                self.timeout_state = TimeoutState::waiting_for_pong();
+                self.timeout_state = TimeoutState::waiting_for_pong(self.clock.now());
             }
 
             _ => {
-                return Ok(StepResult::Status(Status::Eof));
+                return Ok(Some(StepResult::Status(Status::Eof)));
             }
         }
 
         match self.timeout_state {
             TimeoutState::WaitingForPong(dt) => {
-                if dt.elapsed() > TIMEOUT {
+                if self.clock.now().saturating_duration_since(dt) > TIMEOUT {
                     log::warn!("PING timeout detected, exiting");
                     return Err(Error::TimedOut);
                 }
             }
             TimeoutState::Activity(dt) => {
-                if dt.elapsed() > WINDOW {
+                if self.clock.now().saturating_duration_since(dt) > WINDOW {
                     log::warn!("idle connectiond detected, sending a PING");
                     let ts = timestamp().to_string();
                     self.encoder.encode(crate::commands::ping(&ts)).await?;
-                    self.timeout_state = TimeoutState::waiting_for_pong();
+                    self.timeout_state = TimeoutState::waiting_for_pong(self.clock.now());
                 }
             }
             TimeoutState::Start => {}
         }
 
+        // a NOTICE may never come, so don't only forget about these when one does
+        self.prune_pending_commands();
+
         log::trace!("draining messages");
         self.drain_queued_messages().await?;
 
-        Ok(StepResult::Nothing)
+        Ok(Some(StepResult::Nothing))
+    }
+
+    fn check_command_result(&mut self, msg: &crate::messages::Notice<'static>) {
+        self.prune_pending_commands();
+
+        let tx = match &self.command_result_tx {
+            Some(tx) => tx,
+            None => return,
+        };
+        let pending = &mut self.pending_commands;
+
+        let msg_id = match msg.tags().get("msg-id") {
+            Some(msg_id) => msg_id,
+            None => return,
+        };
+
+        let pos = pending.iter().position(|(channel, command, _)| {
+            channel == msg.channel() && CommandResult::is_response(command, msg_id)
+        });
+
+        let (channel, command, _) = match pos.and_then(|pos| pending.remove(pos)) {
+            Some(cmd) => cmd,
+            None => return,
+        };
+
+        log::debug!("got a '{}' response for '{}' on '{}'", msg_id, command, channel);
+        let result = CommandResult {
+            channel,
+            command,
+            notice: msg.clone(),
+        };
+
+        if let Err(TrySendError::Closed(..)) = tx.try_send(result) {
+            log::debug!("command result receiver was dropped, no longer tracking commands");
+            self.command_result_tx.take();
+            self.pending_commands.clear();
+        }
+    }
+
+    // forget about any commands that Trovo didn't respond to
+    fn prune_pending_commands(&mut self) {
+        let now = self.clock.now();
+        while let Some((.., sent)) = self.pending_commands.front() {
+            if now.saturating_duration_since(*sent) <= COMMAND_RESULT_TIMEOUT {
+                break;
+            }
+            self.pending_commands.pop_front();
+        }
+    }
+
+    // let a writer waiting in `flush_and_confirm` know its message was accepted
+    fn confirm_nonce(&self, msg: &Commands<'_>) {
+        let nonce = match msg {
+            Commands::Privmsg(msg) => msg.client_nonce(),
+            Commands::UserState(msg) => msg.tags().get("client-nonce"),
+            _ => None,
+        };
+
+        if let Some(nonce) = nonce {
+            let mut confirmations = self.writer.confirmations().lock().unwrap();
+            if let Some(pos) = confirmations.iter().position(|(pending, _)| pending == nonce) {
+                log::debug!("confirmed message with nonce '{}'", nonce);
+                let (_, tx) = confirmations.remove(pos);
+                let _ = tx.try_send(());
+            }
+        }
     }
 
     async fn check_messages(&mut self, all: &Commands<'static>) -> Result<(), Error> {
@@ -383,6 +1321,8 @@ impl AsyncRunner {
 
         log::trace!("< {}", all.raw().escape_debug());
 
+        self.confirm_nonce(all);
+
         match &all {
             Ping(msg) => {
                 let token = msg.token();
@@ -391,16 +1331,16 @@ impl AsyncRunner {
                     token
                 );
                 self.encoder.encode(commands::pong(token)).await?;
-                self.timeout_state = TimeoutState::activity();
+                self.timeout_state = TimeoutState::activity(self.clock.now());
             }
 
             Pong(..) if matches!(self.timeout_state, WaitingForPong {..}) => {
-                self.timeout_state = TimeoutState::activity()
+                self.timeout_state = TimeoutState::activity(self.clock.now())
             }
 
             Join(msg) if msg.name() == self.identity.username() => {
                 log::debug!("starting tracking channel for '{}'", msg.channel());
-                self.channels.add(msg.channel());
+                self.channels.join(msg.channel());
             }
 
             Part(msg) if msg.name() == self.identity.username() => {
@@ -408,6 +1348,31 @@ impl AsyncRunner {
                 self.channels.remove(msg.channel());
             }
 
+            // moderators (and the broadcaster) get a higher rate limit
+            UserState(msg) => {
+                // leave a custom rate limit alone
+                if let Some(ch) = self.channels.get_mut(msg.channel()).filter(|ch| !ch.custom) {
+                    let is_mod = msg.is_moderator()
+                        || msg
+                            .badges()
+                            .iter()
+                            .any(|badge| badge.kind == BadgeKind::Broadcaster);
+
+                    let class = if is_mod {
+                        RateClass::Moderator
+                    } else {
+                        RateClass::Regular
+                    };
+
+                    // and a slow mode one
+                    match ch.rate_limited.rate_limit.get_current_rate_class() {
+                        Some(RateClass::Regular) if is_mod => ch.set_rate_class(class),
+                        Some(RateClass::Moderator) if !is_mod => ch.set_rate_class(class),
+                        _ => {}
+                    }
+                }
+            }
+
             RoomState(msg) => {
                 if let Some(dur) = msg.is_slow_mode() {
                     if let Some(ch) = self.channels.get_mut(msg.channel()) {
@@ -429,6 +1394,7 @@ impl AsyncRunner {
                     (Some(MessageId::MsgBanned), ..) => self.channels.remove(msg.channel()),
                     _ => {}
                 }
+                self.check_command_result(msg);
             }
 
             Reconnect(_) => return Err(Error::ShouldReconnect),
@@ -463,6 +1429,69 @@ impl AsyncRunner {
         }
     }
 
+    // route a message from the writer, PRIVMSGs are queued on their channel's rate limit
+    fn handle_write(&mut self, mut write_data: Box<[u8]>) -> Result<(), Error> {
+        if let Some(filter) = self.outbound_filter.as_mut() {
+            match filter(&write_data) {
+                Filter::Allow => {}
+                Filter::Replace(data) => write_data = data.into_boxed_slice(),
+                Filter::Drop => return Ok(()),
+            }
+        }
+
+        // TODO provide a 'bytes' flavored parser
+        let msg = std::str::from_utf8(&*write_data).map_err(Error::InvalidUtf8)?;
+        let msg = match crate::irc::parse_one(msg) {
+            Ok((_, msg)) => msg,
+            // the encoder produces valid messages, but a filter might not
+            Err(err) => {
+                log::warn!("dropping an invalid outbound message: {} ({:?})", err, msg);
+                return Ok(());
+            }
+        };
+
+        if let IrcMessage::PRIVMSG = msg.get_command() {
+            if let Some(ch) = msg.nth_arg(0) {
+                if let (Some(..), Some(data)) = (&self.command_result_tx, msg.get_data()) {
+                    if data.starts_with('/') {
+                        let cmd = (ch.to_string(), data.to_string(), self.clock.now());
+                        self.pending_commands.push_back(cmd);
+                    }
+                }
+
+                // Trovo won't send a new GLOBALUSERSTATE, so update it ourselves
+                let color = msg.get_data().and_then(|data| data.strip_prefix("/color "));
+                if let Some(Ok(color)) = color.map(str::parse) {
+                    self.set_identity_color_locally(color);
+                }
+
+                let indices = msg.parse_tags();
+                let tags = crate::irc::Tags::from_data_indices(&msg.raw, &indices);
+                if let Some(nonce) = tags.get("client-nonce") {
+                    self.sent_nonces.push_back(nonce.to_string());
+                    while self.sent_nonces.len() > self.dedupe_window {
+                        self.sent_nonces.pop_front();
+                    }
+                }
+
+                if !self.channels.is_on(ch) {
+                    self.channels.add(ch)
+                }
+
+                let now = self.clock.now();
+                let ch = self.channels.get_mut(ch).unwrap();
+                let limited_for = ch.rated_limited_at.map(|s| now.saturating_duration_since(s));
+                if limited_for > Some(RATE_LIMIT_WINDOW) {
+                    ch.reset_rate_limit();
+                }
+
+                ch.rate_limited.enqueue(write_data)
+            }
+        }
+
+        Ok(())
+    }
+
     fn available_queued_messages(&self) -> usize {
         self.channels
             .map
@@ -476,10 +1505,14 @@ impl AsyncRunner {
         let limit = &mut self.global_rate_limit.get_available_tokens();
 
         let start = *limit;
+        let now = self.clock.now();
 
         // for each channel, try to take up to 'limit' tokens
         for channel in self.channels.map.values_mut() {
-            if channel.rated_limited_at.map(|s| s.elapsed()) > Some(RATE_LIMIT_WINDOW) {
+            let limited_for = channel
+                .rated_limited_at
+                .map(|s| now.saturating_duration_since(s));
+            if limited_for > Some(RATE_LIMIT_WINDOW) {
                 channel.reset_rate_limit();
             }
 
@@ -562,6 +1595,12 @@ impl AsyncRunner {
             missed_messages.push_back(commands.clone().into_owned());
 
             match commands {
+                // anonymous connections never get a GlobalUserState, so
+                // don't wait any longer than we have to
+                IrcReady(..) if is_anonymous && looking_for.is_empty() => {
+                    break Identity::Anonymous { caps };
+                }
+
                 Ready(msg) => {
                     our_name.replace(msg.username().to_string());
 
@@ -585,40 +1624,82 @@ impl AsyncRunner {
                     }
                 }
 
-                Cap(msg) => match msg.capability() {
-                    Capability::Acknowledged(name) => {
-                        use crate::trovo::Capability as Cap;
-
-                        let cap = match Cap::maybe_from_str(name) {
-                            Some(cap) => cap,
-                            // Trovo sent us an unknown capability
-                            None => {
-                                caps.unknown.insert(name.to_string());
-                                continue;
+                // a request for multiple capabilities is answered all at once
+                Cap(msg) => {
+                    for capability in msg.capabilities() {
+                        match capability {
+                            Capability::Acknowledged(name) => {
+                                use crate::trovo::Capability as Cap;
+
+                                let cap = match Cap::maybe_from_str(name) {
+                                    Some(cap) => cap,
+                                    // Trovo sent us an unknown capability
+                                    None => {
+                                        caps.unknown.insert(name.to_string());
+                                        continue;
+                                    }
+                                };
+
+                                *match cap {
+                                    Cap::Tags => &mut caps.tags,
+                                    Cap::Membership => &mut caps.membership,
+                                    Cap::Commands => &mut caps.commands,
+                                } = true;
+
+                                caps.acknowledged.push(cap);
+                                looking_for.remove(&cap);
                             }
-                        };
-
-                        *match cap {
-                            Cap::Tags => &mut caps.tags,
-                            Cap::Membership => &mut caps.membership,
-                            Cap::Commands => &mut caps.commands,
-                        } = true;
 
-                        looking_for.remove(&cap);
-                    }
-
-                    Capability::NotAcknowledged(name) => {
-                        return Err(Error::InvalidCap {
-                            cap: name.to_string(),
-                        })
+                            Capability::NotAcknowledged(name) => {
+                                use crate::trovo::Capability as Cap;
+
+                                let cap = match Cap::maybe_from_str(name) {
+                                    Some(cap) => cap,
+                                    None => {
+                                        return Err(Error::InvalidCap {
+                                            cap: name.to_string(),
+                                        })
+                                    }
+                                };
+
+                                // without Tags or Commands we can't build the
+                                // identity (and tag fields would just be missing)
+                                // so fail early. Membership is optional, and it's
+                                // requested on its own so it can be rejected alone
+                                if cap.is_required() {
+                                    return Err(Error::CapabilityRejected(cap));
+                                }
+
+                                caps.rejected.push(cap);
+                                looking_for.remove(&cap);
+                            }
+                        }
                     }
-                },
+                }
 
                 // NOTE: This will only be sent when there's both Commands and atleast one other CAP requested
                 GlobalUserState(msg) => {
+                    // keep the badges as Trovo sent them, they're parsed on demand
+                    let badges = msg
+                        .tags()
+                        .get("badges")
+                        .map(|s| {
+                            s.split(',')
+                                .filter(|s| !s.is_empty())
+                                .map(ToString::to_string)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let emote_sets = msg.emote_sets().into_iter().map(ToString::to_string).collect();
+
                     // TODO: this is so shitty.
                     let id = match msg.user_id {
-                        Some(id) => id.parse().unwrap(),
+                        Some(id) => id.parse().map_err(|err| {
+                            Error::ParsingFailure(MessageError::CannotParseTag {
+                                name: "user-id".to_string(),
+                                error: Box::new(err),
+                            })
+                        })?,
                         // XXX: we can get this message without any tags
                         None => {
                             break Identity::Basic {
@@ -634,6 +1715,8 @@ impl AsyncRunner {
                         user_id: id,
                         display_name: msg.display_name.map(|s| s.to_string()),
                         color: msg.color,
+                        badges,
+                        emote_sets,
                         caps,
                     };
 
@@ -678,4 +1761,1610 @@ impl Stream for AsyncRunner {
         }
     }
 }
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::test::{TestConn, TestConnector};
+    use crate::trovo::Capability as TrovoCapability;
+    use std::sync::{Arc, Mutex};
+    use std::task::Waker;
+
+    // a connection that answers any PING written to it with a PONG, and any
+    // line starting with a prefix in `replies` with its reply. if `ack_nonces` is
+    // set, a line with a client-nonce is acknowledged with a USERSTATE carrying it.
+    // otherwise it waits forever for more data. every line written is kept in `sent`
+    #[derive(Default)]
+    struct State {
+        read: VecDeque<u8>,
+        write: Vec<u8>,
+        waker: Option<Waker>,
+        replies: Vec<(&'static [u8], &'static [u8])>,
+        ack_nonces: bool,
+        sent: Vec<String>,
+    }
+
+    struct EchoConn(Arc<Mutex<State>>);
+
+    #[derive(Default, Clone)]
+    struct EchoConnector(Arc<Mutex<State>>);
+
+    impl Connector for EchoConnector {
+        type Output = EchoConn;
+        fn connect(&mut self) -> crate::BoxedFuture<std::io::Result<Self::Output>> {
+            let conn = EchoConn(self.0.clone());
+            Box::pin(async move { Ok(conn) })
+        }
+    }
+
+    impl AsyncRead for &EchoConn {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let mut state = self.0.lock().unwrap();
+            if state.read.is_empty() {
+                state.waker.replace(cx.waker().clone());
+                return Poll::Pending;
+            }
+            let n = std::cmp::min(buf.len(), state.read.len());
+            for (d, s) in buf.iter_mut().zip(state.read.drain(..n)) {
+                *d = s;
+            }
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl AsyncWrite for &EchoConn {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let mut state = self.0.lock().unwrap();
+            let state = &mut *state;
+            state.write.extend_from_slice(buf);
+            while let Some(pos) = state.write.iter().position(|&c| c == b'\n') {
+                let line = state.write.drain(..=pos).collect::<Vec<_>>();
+                state.sent.push(String::from_utf8_lossy(&line).into_owned());
+                if let Some(token) = line.strip_prefix(b"PING ") {
+                    state.read.extend(b":tmi.trovo.tv PONG tmi.trovo.tv :");
+                    state.read.extend(token);
+                } else if let Some((_, reply)) =
+                    state.replies.iter().find(|(prefix, _)| line.starts_with(prefix))
+                {
+                    state.read.extend(*reply);
+                } else if let (true, Some(rest)) =
+                    (state.ack_nonces, line.strip_prefix(b"@client-nonce="))
+                {
+                    // acknowledge it like Trovo does, with a USERSTATE with the same nonce
+                    let nonce = rest.split(|&c| c == b' ').next().unwrap_or_default();
+                    state.read.extend(b"@client-nonce=");
+                    state.read.extend(nonce);
+                    state.read.extend(b" :tmi.trovo.tv USERSTATE #museun\r\n");
+                } else {
+                    continue;
+                }
+                if let Some(waker) = state.waker.take() {
+                    waker.wake()
+                }
+            }
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncRead for EchoConn {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_read(cx, buf)
+        }
+    }
+
+    impl AsyncWrite for EchoConn {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Pin::new(&mut &*self).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Pin::new(&mut &*self).poll_close(cx)
+        }
+    }
+
+    // connect an anonymous runner, the connection starts with the end of the MOTD
+    // and then whatever the test put in `read`
+    async fn connect_test_runner(connector: &EchoConnector) -> AsyncRunner {
+        {
+            let mut state = connector.0.lock().unwrap();
+            for &b in b":tmi.trovo.tv 376 justinfan1234 :>\r\n".iter().rev() {
+                state.read.push_front(b);
+            }
+        }
+
+        let user_config = UserConfig::builder().anonymous().build().unwrap();
+        AsyncRunner::connect(connector.clone(), &user_config)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn into_parts_keeps_channels() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #museun\r\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+            runner.join("museun").await.unwrap();
+
+            // whispers and messages to channels we didn't join aren't channels we're on
+            let mut writer = runner.writer();
+            writer.encode(commands::whisper("museun", "hello")).await.unwrap();
+            writer.encode(commands::privmsg("#shaken_bot", "hello")).await.unwrap();
+            while let Some(data) = runner.writer_rx.try_recv() {
+                runner.handle_write(data).unwrap();
+            }
+            assert!(runner.get_channel_mut("jtv").is_some());
+            assert!(runner.get_channel_mut("#shaken_bot").is_some());
+
+            let (boxed, config, channels) = runner.into_parts();
+            assert_eq!(config, user_config);
+            assert_eq!(channels, vec!["#museun".to_string()].into_iter().collect());
+
+            // and they can be used to connect a new runner
+            connector.conn.read_all_lines().await.unwrap();
+            connector
+                .conn
+                .write_data(":tmi.trovo.tv 376 justinfan1234 :>\r\n")
+                .await;
+            let mut runner = AsyncRunner::connect(boxed, &config).await.unwrap();
+            assert!(!runner.is_on_channel("#museun"));
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert!(lines.iter().any(|line| line.starts_with("NICK justinfan")));
+
+            for channel in &channels {
+                runner.queue_join(channel);
+            }
+            assert_eq!(runner.queued_joins, vec!["#museun"]);
+        });
+    }
+
+    #[test]
+    fn connection_state_transitions() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":tmi.trovo.tv RECONNECT\r\n",
+                ))
+                .await;
+
+            let (tx, rx) = crate::channel::unbounded();
+            let user_config = UserConfig::anonymous();
+            let mut runner = AsyncRunner::connect_with_states(connector, &user_config, tx)
+                .await
+                .unwrap();
+            assert_eq!(runner.connection_state(), ConnectionState::Connected);
+
+            // the missed 376
+            assert!(matches!(
+                runner.next_message().await.unwrap(),
+                Status::Message(Commands::Ready(..))
+            ));
+            assert!(matches!(
+                runner.next_message().await.unwrap_err(),
+                Error::ShouldReconnect
+            ));
+            assert_eq!(runner.connection_state(), ConnectionState::Reconnecting);
+
+            assert!(matches!(runner.next_message().await.unwrap(), Status::Eof));
+            assert_eq!(runner.connection_state(), ConnectionState::Closed);
+
+            let states = std::iter::from_fn(|| rx.try_recv()).collect::<Vec<_>>();
+            assert_eq!(
+                states,
+                vec![
+                    ConnectionState::Connecting,
+                    ConnectionState::Registering,
+                    ConnectionState::Connected,
+                    ConnectionState::Reconnecting,
+                    ConnectionState::Closed,
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn anonymous_ready_on_irc_ready() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv CAP * ACK :trovo.tv/tags\r\n",
+                    ":tmi.trovo.tv CAP * ACK :trovo.tv/commands\r\n",
+                    ":tmi.trovo.tv 001 justinfan1234 :Welcome, GLHF!\r\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::anonymous();
+            let user_config = UserConfig {
+                capabilities: vec![TrovoCapability::Tags, TrovoCapability::Commands],
+                ..user_config
+            };
+
+            let runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+            assert!(matches!(runner.identity, Identity::Anonymous { .. }));
+        });
+    }
+
+    #[test]
+    fn run_with_retry_rejoins() {
+        // a connector that hands out each of these connections in turn
+        #[derive(Clone)]
+        struct Reconnector(Arc<Mutex<VecDeque<TestConn>>>);
+
+        impl Connector for Reconnector {
+            type Output = TestConn;
+            fn connect(&mut self) -> crate::BoxedFuture<std::io::Result<Self::Output>> {
+                let conn = self.0.lock().unwrap().pop_front().ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "no more")
+                });
+                Box::pin(async move { conn })
+            }
+        }
+
+        futures_lite::future::block_on(async move {
+            let first = TestConn::new();
+            first
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #museun\r\n",
+                    ":test!test@test PRIVMSG #museun :first\r\n",
+                ))
+                .await;
+
+            // the connection is lost before the JOIN is confirmed
+            let second = TestConn::new();
+            second
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":test!test@test PRIVMSG #museun :second\r\n",
+                ))
+                .await;
+
+            let third = TestConn::new();
+            third.write_data(":tmi.trovo.tv 376 justinfan1234 :>\r\n").await;
+
+            let connector = Reconnector(Arc::new(Mutex::new(
+                vec![first, second.clone(), third.clone()].into_iter().collect(),
+            )));
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let strategy = RetryStrategy::constant(Duration::from_millis(1)).max_attempts(1);
+
+            let mut messages = vec![];
+            let mut retries = vec![];
+            let err = AsyncRunner::run_with_retry(
+                connector,
+                &user_config,
+                strategy,
+                |runner, msg| {
+                    if let Commands::Privmsg(pm) = msg {
+                        messages.push(pm.data().to_string())
+                    }
+
+                    // a whisper and a message to a channel we aren't on aren't rejoined
+                    let mut writer = runner.writer();
+                    let whisper = commands::whisper("museun", "hello");
+                    futures_lite::future::block_on(writer.encode(whisper)).unwrap();
+                    let privmsg = commands::privmsg("#shaken_bot", "hello");
+                    futures_lite::future::block_on(writer.encode(privmsg)).unwrap();
+                    while let Some(data) = runner.writer_rx.try_recv() {
+                        runner.handle_write(data).unwrap();
+                    }
+                },
+                |attempt, err| retries.push((attempt, err.to_string())),
+            )
+            .await
+            .unwrap_err();
+
+            // the fourth connection is refused, and the strategy gives up
+            assert!(matches!(err, Error::Io(..)));
+            assert_eq!(messages, vec!["first", "second"]);
+            // it started over after each connection was ready
+            let attempts = retries.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+            assert_eq!(attempts, vec![1, 1, 1]);
+
+            // it rejoined the channel
+            let joins = |lines: Vec<String>| {
+                lines
+                    .into_iter()
+                    .filter(|line| line.starts_with("JOIN "))
+                    .collect::<Vec<_>>()
+            };
+            let lines = second.read_all_lines().await.unwrap();
+            assert_eq!(joins(lines), vec!["JOIN #museun\r\n"]);
+
+            // and tried again, because the join wasn't confirmed
+            let lines = third.read_all_lines().await.unwrap();
+            assert_eq!(joins(lines), vec!["JOIN #museun\r\n"]);
+        });
+    }
+
+    #[test]
+    fn run_with_retry_quits() {
+        futures_lite::future::block_on(async move {
+            // this never reaches an EOF
+            let connector = EchoConnector::default();
+            let data = concat!(
+                ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                ":test!test@test PRIVMSG #museun :!quit\r\n",
+            );
+            connector.0.lock().unwrap().read.extend(data.as_bytes());
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            AsyncRunner::run_with_retry(
+                connector,
+                &user_config,
+                RetryStrategy::default(),
+                |runner, msg| {
+                    if let Commands::Privmsg(..) = msg {
+                        let quit = runner.quit_handle();
+                        assert!(futures_lite::future::block_on(quit.notify()));
+                    }
+                },
+                |_, err| panic!("unexpected retry: {}", err),
+            )
+            .await
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn ping_is_forwarded_and_answered() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    "PING :1234567890\r\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let ping = loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Ping(msg)) => break msg,
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            };
+            assert_eq!(ping.token(), "1234567890");
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(lines.last().unwrap(), "PONG :1234567890\r\n");
+        });
+    }
+
+    #[test]
+    fn last_raw_after_decode_error() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":tmi.trovo.tv PING :1234567890\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+            assert!(runner.last_raw().is_none());
+
+            let err = loop {
+                match runner.next_message().await {
+                    Ok(Status::Message(..)) => continue,
+                    Ok(status) => panic!("unexpected status: {:?}", status),
+                    Err(err) => break err,
+                }
+            };
+            assert!(matches!(err, Error::BareNewline));
+            assert_eq!(runner.last_raw().unwrap(), ":tmi.trovo.tv PING :1234567890\n");
+        });
+    }
+
+    #[test]
+    fn ping_round_trip() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+
+            let rtt = runner.ping().await.unwrap();
+            assert!(rtt < TIMEOUT);
+
+            // the ready message is still delivered after the ping
+            match runner.next_message().await.unwrap() {
+                Status::Message(Commands::Ready(..)) => {}
+                status => panic!("unexpected status: {:?}", status),
+            }
+        });
+    }
+
+    #[test]
+    fn ping_keeps_chat_messages() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.read.extend(b":museun!museun@museun PRIVMSG #museun :hello\r\n");
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            runner.ping().await.unwrap();
+
+            // the chat message read while waiting for the pong isn't lost
+            match runner.next_message().await.unwrap() {
+                Status::Message(Commands::Ready(..)) => {}
+                status => panic!("unexpected status: {:?}", status),
+            }
+            match runner.next_message().await.unwrap() {
+                Status::Message(Commands::Privmsg(msg)) => assert_eq!(msg.data(), "hello"),
+                status => panic!("unexpected status: {:?}", status),
+            }
+        });
+    }
+
+    #[test]
+    fn malformed_message_is_raw() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                // a PRIVMSG without a user
+                state.read.extend(b":tmi.trovo.tv PRIVMSG #museun :hello\r\n");
+                state.read.extend(b":tmi.trovo.tv PING :1234\r\n");
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Ready(..)) => continue,
+                    Status::Message(Commands::Raw(msg)) => {
+                        assert_eq!(msg.get_command(), "PRIVMSG");
+                        break;
+                    }
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            // and it keeps going
+            match runner.next_message().await.unwrap() {
+                Status::Message(Commands::Ping(msg)) => assert_eq!(msg.token(), "1234"),
+                status => panic!("unexpected status: {:?}", status),
+            }
+        });
+    }
+
+    #[test]
+    fn registration_timeout() {
+        futures_lite::future::block_on(async move {
+            // the server never says anything after we connect
+            let connector = EchoConnector::default();
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let err = AsyncRunner::connect_with_timeout(
+                connector,
+                &user_config,
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap_err();
+            assert!(matches!(err, Error::RegistrationTimeout));
+        });
+    }
+
+    #[test]
+    fn optional_capability_rejected() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                // each request is rejected or acknowledged as a whole
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/membership\r\n",
+                    b":tmi.trovo.tv CAP * NAK :trovo.tv/membership\r\n",
+                ));
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/commands\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/commands\r\n",
+                ));
+                state
+                    .replies
+                    .push((b"NICK museun", b":tmi.trovo.tv 376 museun :>\r\n"));
+            }
+
+            let user_config = UserConfig::builder()
+                .name("museun")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[TrovoCapability::Membership, TrovoCapability::Commands])
+                .build()
+                .unwrap();
+
+            let runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let caps = runner.identity.capabilities();
+            assert_eq!(caps.acknowledged, vec![TrovoCapability::Commands]);
+            assert_eq!(caps.rejected, vec![TrovoCapability::Membership]);
+            assert!(caps.commands);
+            assert!(!caps.membership);
+        });
+    }
+
+    #[test]
+    fn combined_capability_request() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/membership\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/membership\r\n",
+                ));
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/tags trovo.tv/commands\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/tags trovo.tv/commands\r\n",
+                ));
+                state.replies.push((
+                    b"NICK museun",
+                    b":tmi.trovo.tv 376 museun :>\r\n\
+                      @badge-info=;badges=;color=#FF69B4;display-name=museun;emote-sets=0;user-id=23196011;user-type= :tmi.trovo.tv GLOBALUSERSTATE\r\n",
+                ));
+            }
+
+            let user_config = UserConfig::builder()
+                .name("museun")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .enable_all_capabilities()
+                .build()
+                .unwrap();
+
+            let runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            // the required capabilities were requested at once, apart from the optional one
+            let sent = connector.0.lock().unwrap().sent.clone();
+            let requests = sent.iter().filter(|s| s.starts_with("CAP REQ")).collect::<Vec<_>>();
+            assert_eq!(
+                requests,
+                vec![
+                    "CAP REQ :trovo.tv/membership\r\n",
+                    "CAP REQ :trovo.tv/tags trovo.tv/commands\r\n"
+                ]
+            );
+
+            let caps = runner.identity.capabilities();
+            assert_eq!(caps.acknowledged, TrovoCapability::all());
+            assert!(caps.rejected.is_empty());
+            assert!(caps.membership && caps.tags && caps.commands);
+            assert!(matches!(runner.identity, Identity::Full { .. }));
+        });
+    }
+
+    #[test]
+    fn invalid_user_id_is_an_error() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/membership\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/membership\r\n",
+                ));
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/tags trovo.tv/commands\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/tags trovo.tv/commands\r\n",
+                ));
+                state.replies.push((
+                    b"NICK museun",
+                    b":tmi.trovo.tv 376 museun :>\r\n\
+                      @badges=;color=#FF69B4;display-name=museun;emote-sets=0;user-id=museun :tmi.trovo.tv GLOBALUSERSTATE\r\n",
+                ));
+            }
+
+            let user_config = UserConfig::builder()
+                .name("museun")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .enable_all_capabilities()
+                .build()
+                .unwrap();
+
+            let err = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::ParsingFailure(MessageError::CannotParseTag { name, .. })
+                    if name == "user-id"
+            ));
+        });
+    }
+
+    #[test]
+    fn join_with_userstate() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/membership\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/membership\r\n",
+                ));
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/tags trovo.tv/commands\r\n",
+                    b":tmi.trovo.tv CAP * ACK :trovo.tv/tags trovo.tv/commands\r\n",
+                ));
+                state.replies.push((
+                    b"NICK museun",
+                    b":tmi.trovo.tv 376 museun :>\r\n\
+                      @badge-info=;badges=;color=#FF69B4;display-name=museun;emote-sets=0;user-id=23196011;user-type= :tmi.trovo.tv GLOBALUSERSTATE\r\n",
+                ));
+                state.replies.push((
+                    b"JOIN #shaken_bot",
+                    b":museun!museun@museun.tmi.trovo.tv JOIN #shaken_bot\r\n\
+                      @badge-info=;badges=moderator/1;color=#FF69B4;display-name=museun;emote-sets=0;mod=1;subscriber=0;user-type=mod :tmi.trovo.tv USERSTATE #shaken_bot\r\n\
+                      @emote-only=0;room-id=241015868 :tmi.trovo.tv ROOMSTATE #shaken_bot\r\n",
+                ));
+            }
+
+            let user_config = UserConfig::builder()
+                .name("museun")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .enable_all_capabilities()
+                .build()
+                .unwrap();
+
+            let mut runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let user_state = runner.join_with_userstate("shaken_bot").await.unwrap();
+            let user_state = user_state.unwrap();
+            assert_eq!(user_state.channel(), "#shaken_bot");
+            assert!(user_state.is_moderator());
+            assert!(runner.is_on_channel("#shaken_bot"));
+
+            // the other messages are still there
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::RoomState(msg)) => {
+                        assert_eq!(msg.channel(), "#shaken_bot");
+                        break;
+                    }
+                    Status::Message(Commands::UserState(..)) => panic!("user state was taken"),
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn join_with_userstate_anonymous() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"JOIN #museun",
+                    b":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #museun\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            let user_state = runner.join_with_userstate("#museun").await.unwrap();
+            assert!(user_state.is_none());
+            assert!(runner.is_on_channel("#museun"));
+        });
+    }
+
+    #[test]
+    fn next_message_timeout() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+
+            let dur = Duration::from_millis(20);
+            // the messages from connecting are still queued up
+            while runner.next_message_timeout(dur).await.unwrap().is_some() {}
+
+            // the connection stays silent
+            for _ in 0..3 {
+                assert!(runner.next_message_timeout(dur).await.unwrap().is_none());
+            }
+
+            let send = |data: &[u8]| {
+                let mut state = connector.0.lock().unwrap();
+                state.read.extend(data);
+                if let Some(waker) = state.waker.take() {
+                    waker.wake()
+                }
+            };
+
+            // giving up part way through a message doesn't lose it
+            send(b"PING :12");
+            assert!(runner.next_message_timeout(dur).await.unwrap().is_none());
+            send(b"34\r\n");
+
+            match runner.next_message_timeout(dur).await.unwrap() {
+                Some(Status::Message(Commands::Ping(msg))) => assert_eq!(msg.token(), "1234"),
+                status => panic!("unexpected status: {:?}", status),
+            }
+        });
+    }
+
+    #[test]
+    fn required_capability_rejected() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"CAP REQ :trovo.tv/tags trovo.tv/commands\r\n",
+                    b":tmi.trovo.tv CAP * NAK :trovo.tv/tags trovo.tv/commands\r\n",
+                ));
+            }
+
+            let user_config = UserConfig::builder()
+                .name("museun")
+                .token(format!("oauth:{}", "a".repeat(30)))
+                .capabilities(&[TrovoCapability::Tags, TrovoCapability::Commands])
+                .build()
+                .unwrap();
+
+            let err = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::CapabilityRejected(TrovoCapability::Tags)
+            ));
+        });
+    }
+
+    #[test]
+    fn command_result_for_failed_ban() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"PRIVMSG #museun :/ban nobody",
+                    b"@msg-id=bad_ban_no_user :tmi.trovo.tv NOTICE #museun :Invalid username: nobody\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            let results = runner.command_result();
+            runner
+                .writer()
+                .encode(commands::ban("museun", "nobody", None))
+                .await
+                .unwrap();
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Notice(..)) => break,
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            let result = results.try_recv().unwrap();
+            assert_eq!(result.channel, "#museun");
+            assert_eq!(result.command, "/ban nobody");
+            assert_eq!(result.msg_id(), "bad_ban_no_user");
+            assert!(!result.is_success());
+            assert_eq!(result.notice.message(), "Invalid username: nobody");
+
+            assert!(results.try_recv().is_none());
+        });
+    }
+
+    #[test]
+    fn unanswered_commands_are_forgotten() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+
+            let clock = crate::test::TestClock::new();
+            runner.set_clock(clock.clone());
+
+            let _results = runner.command_result();
+            runner
+                .writer()
+                .encode(commands::ban("museun", "nobody", None))
+                .await
+                .unwrap();
+            while let Some(data) = runner.writer_rx.try_recv() {
+                runner.handle_write(data).unwrap();
+            }
+            assert_eq!(runner.pending_commands.len(), 1);
+
+            // Trovo never sends a NOTICE for it, but the runner keeps going
+            clock.advance(COMMAND_RESULT_TIMEOUT + Duration::from_secs(1));
+            runner
+                .writer()
+                .encode(commands::privmsg("#museun", "hello"))
+                .await
+                .unwrap();
+            // messages are returned first, so skip the ones left over from connecting
+            while let StepResult::Status(..) = runner.step().await.unwrap() {}
+            assert!(runner.pending_commands.is_empty());
+        });
+    }
+
+    #[test]
+    fn color_updates_identity() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"PRIVMSG jtv :/color Red",
+                    b"@msg-id=color_changed :tmi.trovo.tv NOTICE #museun :Your color has been changed.\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            runner.identity = Identity::Full {
+                name: "museun".into(),
+                user_id: 23_196_011,
+                display_name: None,
+                color: "Blue".parse().unwrap(),
+                badges: vec![],
+                emote_sets: vec![],
+                caps: Capabilities::default(),
+            };
+
+            runner
+                .writer()
+                .encode(commands::color("Red").unwrap())
+                .await
+                .unwrap();
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Notice(..)) => break,
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            match &runner.identity {
+                Identity::Full { color, .. } => assert_eq!(color.to_string(), "Red"),
+                identity => panic!("unexpected identity: {:?}", identity),
+            }
+        });
+    }
+
+    #[test]
+    fn own_echo_by_nonce() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"@client-nonce=abc123 PRIVMSG #museun :hello",
+                    b"@client-nonce=abc123 :shaken_bot!shaken_bot@shaken_bot.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            runner
+                .writer()
+                .encode(commands::raw("@client-nonce=abc123 PRIVMSG #museun :hello\r\n"))
+                .await
+                .unwrap();
+
+            let msg = loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Privmsg(msg)) => break msg,
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            };
+            assert!(runner.is_own_echo(&msg));
+
+            let others = &[
+                "@client-nonce=def456 :shaken_bot!shaken_bot@shaken_bot.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+                ":shaken_bot!shaken_bot@shaken_bot.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+            ];
+            for input in others {
+                let msg = crate::irc::parse(input).next().unwrap().unwrap();
+                let msg = Privmsg::from_irc(msg).unwrap();
+                assert!(!runner.is_own_echo(&msg), "{}", input);
+            }
+
+            // without a nonce, it falls back to the identity
+            let input = ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv PRIVMSG #museun :hello\r\n";
+            let msg = crate::irc::parse(input).next().unwrap().unwrap();
+            let msg = Privmsg::from_irc(msg).unwrap();
+            assert!(runner.is_own_echo(&msg));
+        });
+    }
+
+    #[test]
+    fn builder_settings() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            connector
+                .0
+                .lock()
+                .unwrap()
+                .read
+                .extend(b":tmi.trovo.tv 376 justinfan1234 :>\r\n");
+
+            // a read buffer smaller than a line still reads whole lines
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::builder()
+                .read_buffer_size(8)
+                .writer_capacity(1)
+                .dedupe_window(1)
+                .connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let mut writer = runner.writer();
+            writer
+                .encode(commands::raw("@client-nonce=abc123 PRIVMSG #museun :hello"))
+                .await
+                .unwrap();
+
+            // the writer channel is full, so this waits for the runner
+            let encode =
+                writer.encode(commands::raw("@client-nonce=def456 PRIVMSG #museun :hello"));
+            futures_lite::pin!(encode);
+            assert!(futures_lite::future::poll_once(&mut encode).await.is_none());
+
+            let encode = async { encode.await.unwrap() };
+            let run = async {
+                loop {
+                    runner.step().await.unwrap();
+                }
+            };
+            futures_lite::future::or(encode, run).await;
+
+            while let Some(data) = runner.writer_rx.try_recv() {
+                runner.handle_write(data).unwrap();
+            }
+
+            // only the last nonce is remembered
+            for (nonce, expected) in &[("abc123", false), ("def456", true)] {
+                let input = format!(
+                    "@client-nonce={} :shaken_bot!shaken_bot@shaken_bot.tmi.trovo.tv PRIVMSG #museun :hello\r\n",
+                    nonce
+                );
+                let msg = crate::irc::parse(&input).next().unwrap().unwrap();
+                let msg = Privmsg::from_irc(msg).unwrap();
+                assert_eq!(runner.is_own_echo(&msg), *expected, "{}", nonce);
+            }
+        });
+    }
+
+    #[test]
+    fn moderator_rate_class() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.read.extend(b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n");
+                state.read.extend(b":justinfan1234!justinfan1234@justinfan1234 JOIN #shaken_bot\r\n");
+                state.read.extend(b"@badges=moderator/1;mod=1 :tmi.trovo.tv USERSTATE #museun\r\n");
+                state.read.extend(b"@badges=;mod=0 :tmi.trovo.tv USERSTATE #shaken_bot\r\n");
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            let mut user_states = 0;
+            while user_states < 2 {
+                let status = runner.next_message().await.unwrap();
+                if let Status::Message(Commands::UserState(..)) = status {
+                    user_states += 1;
+                }
+            }
+
+            let cap = |runner: &mut AsyncRunner, channel| {
+                let ch = runner.get_channel_mut(channel).unwrap();
+                ch.rate_limited.rate_limit.get_cap()
+            };
+            assert_eq!(cap(&mut runner, "#museun"), 100);
+            assert_eq!(cap(&mut runner, "#shaken_bot"), 20);
+        });
+    }
+
+    #[test]
+    fn rate_limits_use_the_runner_clock() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector.conn.write_data(":tmi.trovo.tv 376 justinfan1234 :>\r\n").await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector, &user_config)
+                .await
+                .unwrap();
+
+            let clock = crate::test::TestClock::new();
+            runner.set_clock(clock.clone());
+            runner.channels.add("#museun");
+
+            let period = Duration::from_secs(30);
+            runner.set_join_rate_limit(RateLimit::full(1, period));
+            let ch = runner.get_channel_mut("#museun").unwrap();
+            ch.set_rate_limit(RateLimit::full(1, period));
+
+            assert_eq!(ch.rate_limited.rate_limit.consume(1), Ok(0));
+            assert!(ch.rate_limited.rate_limit.consume(1).is_err());
+            assert_eq!(runner.join_rate_limit.consume(1), Ok(0));
+            assert!(runner.join_rate_limit.consume(1).is_err());
+
+            // only the test clock can refill them this quickly
+            clock.advance(period);
+
+            let ch = runner.get_channel_mut("#museun").unwrap();
+            assert_eq!(ch.rate_limited.rate_limit.consume(1), Ok(0));
+            assert_eq!(runner.join_rate_limit.consume(1), Ok(0));
+        });
+    }
+
+    #[test]
+    fn custom_rate_limit_keeps_its_class() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.read.extend(b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n");
+                state.read.extend(b"@badges=moderator/1;mod=1 :tmi.trovo.tv USERSTATE #museun\r\n");
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            loop {
+                if let Status::Message(Commands::Join(..)) = runner.next_message().await.unwrap() {
+                    break;
+                }
+            }
+
+            // this looks like the regular class, but it was set by the user
+            let ch = runner.get_channel_mut("#museun").unwrap();
+            ch.set_rate_limit(RateLimit::from_class(RateClass::Regular));
+
+            loop {
+                let status = runner.next_message().await.unwrap();
+                if let Status::Message(Commands::UserState(..)) = status {
+                    break;
+                }
+            }
+
+            let ch = runner.get_channel_mut("#museun").unwrap();
+            assert_eq!(ch.rate_limited.rate_limit.get_cap(), 20);
+
+            // going back to a rate class lets it switch again
+            ch.set_rate_class(RateClass::Regular);
+            assert!(!ch.custom);
+        });
+    }
+
+    #[test]
+    fn join_multiple_with_timeout() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"JOIN #museun",
+                    b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n",
+                ));
+                state.replies.push((
+                    b"JOIN #banned",
+                    b"@msg-id=msg_banned :tmi.trovo.tv NOTICE #banned :You are permanently banned from talking in banned.\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+            runner.set_join_timeout(Duration::from_millis(100));
+
+            // #shaken_bot never confirms
+            let results = runner
+                .join_multiple(&["museun", "shaken_bot", "#banned"])
+                .await;
+            assert_eq!(results.len(), 3);
+            assert!(results[0].is_ok());
+            assert!(matches!(
+                &results[1],
+                Err(Error::JoinTimeout { channel }) if channel == "#shaken_bot"
+            ));
+            assert!(matches!(
+                &results[2],
+                Err(Error::BannedFromChannel { channel }) if channel == "#banned"
+            ));
+
+            assert!(runner.is_on_channel("#museun"));
+            assert!(!runner.is_on_channel("#shaken_bot"));
+
+            let results = runner.join_multiple(&["museun"]).await;
+            assert!(matches!(&results[0], Err(Error::AlreadyOnChannel { .. })));
+        });
+    }
+
+    #[test]
+    fn join_multiple_dedupes_channels() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"JOIN #foo",
+                    b":justinfan1234!justinfan1234@justinfan1234 JOIN #foo\r\n",
+                ));
+                state.replies.push((
+                    b"JOIN #museun",
+                    b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            let results = runner.join_multiple(&["Foo", "#foo", "museun"]).await;
+            assert_eq!(results.len(), 3);
+            assert!(results[0].is_ok());
+            assert!(matches!(
+                &results[1],
+                Err(Error::AlreadyOnChannel { channel }) if channel == "#foo"
+            ));
+            assert!(results[2].is_ok());
+
+            let sent = connector.0.lock().unwrap().sent.clone();
+            let joins = sent.iter().filter(|s| s.starts_with("JOIN ")).collect::<Vec<_>>();
+            assert_eq!(joins, vec!["JOIN #foo\r\n", "JOIN #museun\r\n"]);
+        });
+    }
+
+    #[test]
+    fn refresh_room_state() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"JOIN #museun",
+                    b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n\
+                      @room-id=1;slow=0 :tmi.trovo.tv ROOMSTATE #museun\r\n",
+                ));
+                state.replies.push((
+                    b"PART #museun",
+                    b":justinfan1234!justinfan1234@justinfan1234 PART #museun\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            assert!(matches!(
+                runner.refresh_room_state("#museun").await,
+                Err(Error::NotOnChannel { .. })
+            ));
+
+            runner.join("#museun").await.unwrap();
+
+            // the slow mode changed while we weren't looking
+            connector.0.lock().unwrap().replies[0].1 =
+                b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n\
+                  @room-id=1;slow=30 :tmi.trovo.tv ROOMSTATE #museun\r\n";
+
+            let room_state = runner.refresh_room_state("museun").await.unwrap();
+            assert_eq!(room_state.channel(), "#museun");
+            assert_eq!(room_state.is_slow_mode(), Some(30));
+            assert!(runner.is_on_channel("#museun"));
+
+            // the server restarted during the rejoin, so the channel is queued to be joined again
+            connector.0.lock().unwrap().replies[0].1 = b":tmi.trovo.tv RECONNECT\r\n";
+
+            assert!(matches!(
+                runner.refresh_room_state("#museun").await,
+                Err(Error::ShouldReconnect)
+            ));
+            assert!(!runner.is_on_channel("#museun"));
+            assert_eq!(runner.queued_joins, vec!["#museun"]);
+
+            runner.queued_joins.clear();
+            connector.0.lock().unwrap().replies[0].1 =
+                b":justinfan1234!justinfan1234@justinfan1234 JOIN #museun\r\n";
+            runner.join("#museun").await.unwrap();
+
+            // we got banned while we were gone, joining again won't help
+            connector.0.lock().unwrap().replies[0].1 =
+                b"@msg-id=msg_banned :tmi.trovo.tv NOTICE #museun :You are banned.\r\n";
+
+            assert!(matches!(
+                runner.refresh_room_state("#museun").await,
+                Err(Error::BannedFromChannel { .. })
+            ));
+            assert!(!runner.is_on_channel("#museun"));
+            assert!(runner.queued_joins.is_empty());
+        });
+    }
+
+    #[test]
+    fn send_and_wait_for_room_state() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            {
+                let mut state = connector.0.lock().unwrap();
+                state.replies.push((
+                    b"PRIVMSG #museun :/slow 30",
+                    b"@msg-id=slow_on :tmi.trovo.tv NOTICE #museun :This room is now in slow mode.\r\n\
+                      @room-id=1;slow=30 :tmi.trovo.tv ROOMSTATE #museun\r\n",
+                ));
+            }
+
+            let mut runner = connect_test_runner(&connector).await;
+
+            let room_state: RoomState<'static> = runner
+                .send_and_wait(commands::slow("#museun", Some(30)), |msg: &RoomState<'_>| {
+                    msg.channel() == "#museun" && msg.is_slow_mode().is_some()
+                })
+                .await
+                .unwrap();
+            assert_eq!(room_state.is_slow_mode(), Some(30));
+
+            // the notice is kept for later
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Notice(msg)) => {
+                        assert_eq!(msg.channel(), "#museun");
+                        break;
+                    }
+                    Status::Message(Commands::RoomState(..)) => panic!("room state was kept"),
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            // nothing answers this one
+            runner.set_wait_timeout(Duration::from_millis(100));
+            let err = runner
+                .send_and_wait(commands::slow("#museun", Some(60)), |_: &RoomState<'_>| true)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::WaitTimeout));
+        });
+    }
+
+    #[test]
+    fn flush_and_confirm() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            connector.0.lock().unwrap().ack_nonces = true;
+            let mut runner = connect_test_runner(&connector).await;
+
+            let mut writer = runner.writer();
+            let confirm = async {
+                writer
+                    .flush_and_confirm("museun", "hello", Duration::from_secs(5))
+                    .await
+                    .unwrap()
+            };
+            let run = async {
+                loop {
+                    runner.next_message().await.unwrap();
+                }
+            };
+            futures_lite::future::or(confirm, run).await;
+
+            assert!(writer.confirmations().lock().unwrap().is_empty());
+            let sent = connector.0.lock().unwrap().sent.clone();
+            let line = sent.iter().find(|s| s.contains("PRIVMSG")).unwrap();
+            assert!(line.starts_with("@client-nonce="));
+            assert!(line.ends_with(" PRIVMSG #museun :hello\r\n"));
+        });
+    }
+
+    #[test]
+    fn quit_drains_pending_writes() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+            connector.0.lock().unwrap().sent.clear();
+
+            let mut writer = runner.writer();
+            for msg in &["hello", "world", "goodbye"] {
+                writer.encode(commands::privmsg("#museun", msg)).await.unwrap();
+            }
+            runner.quit_handle().notify().await;
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Quit => break,
+                    Status::Message(..) => continue,
+                    Status::Eof => panic!("unexpected eof"),
+                }
+            }
+
+            let sent = std::mem::take(&mut connector.0.lock().unwrap().sent);
+            assert_eq!(
+                sent,
+                vec![
+                    "PRIVMSG #museun :hello\r\n",
+                    "PRIVMSG #museun :world\r\n",
+                    "PRIVMSG #museun :goodbye\r\n",
+                    "QUIT\r\n",
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn join_many_is_rate_limited() {
+        futures_lite::future::block_on(async move {
+            let channels = (0..30).map(|n| format!("#test{}", n)).collect::<Vec<_>>();
+
+            let mut data = String::from(":tmi.trovo.tv 376 justinfan1234 :>\r\n");
+            for channel in &channels {
+                data.push_str(&format!(
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN {}\r\n",
+                    channel
+                ));
+            }
+
+            let connector = TestConnector::default();
+            connector.conn.write_data(data).await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            let clock = crate::test::TestClock::new();
+            runner.set_clock(clock.clone());
+
+            let period = Duration::from_millis(100);
+            runner.set_join_rate_limit(RateLimit::full(20, period));
+
+            // the first 20 fit in the bucket
+            runner
+                .join_many(channels[..20].iter().map(String::as_str))
+                .await
+                .unwrap();
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            let joins = lines.iter().filter(|s| s.starts_with("JOIN ")).count();
+            assert_eq!(joins, 20);
+
+            // the rest have to wait for it to refill, which only happens once the clock moves
+            let join = runner.join_many(channels[20..].iter().map(String::as_str));
+            let refill = async {
+                futures_timer::Delay::new(Duration::from_millis(50)).await;
+                let lines = connector.conn.read_all_lines().await.unwrap();
+                assert!(lines.iter().all(|s| !s.starts_with("JOIN ")));
+                clock.advance(period);
+            };
+            let (joined, _) = futures_lite::future::zip(join, refill).await;
+            joined.unwrap();
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            let joins = lines.iter().filter(|s| s.starts_with("JOIN ")).count();
+            assert_eq!(joins, 10);
+            assert!(channels.iter().all(|ch| runner.is_on_channel(ch)));
+        });
+    }
+
+    #[test]
+    fn whisper_many_is_paced() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+
+            let usernames = ["a", "b", "c", "d", "e"];
+            let mut writer = runner.writer();
+            for whisper in commands::whisper_many(&usernames, "hello") {
+                writer.encode(whisper).await.unwrap();
+            }
+
+            while let Some(data) = runner.writer_rx.try_recv() {
+                runner.handle_write(data).unwrap();
+            }
+            runner.drain_queued_messages().await.unwrap();
+
+            // only the whisper burst goes out, the rest wait on the limiter
+            let sent = connector.0.lock().unwrap().sent.clone();
+            let whispers = sent.iter().filter(|s| s.starts_with("PRIVMSG jtv :/w ")).count();
+            assert_eq!(whispers, 3);
+
+            let ch = runner.get_channel_mut("jtv").unwrap();
+            assert_eq!(ch.rate_limited.rate_limit.get_cap(), 3);
+            assert_eq!(ch.rate_limited.queue.len(), 2);
+        });
+    }
+
+    #[test]
+    fn outbound_filter() {
+        futures_lite::future::block_on(async move {
+            let connector = EchoConnector::default();
+            let mut runner = connect_test_runner(&connector).await;
+
+            runner.set_outbound_filter(Box::new(|data: &[u8]| {
+                let data = std::str::from_utf8(data).unwrap();
+                if data.contains("heck") {
+                    Filter::Drop
+                } else if data.contains("darn") {
+                    Filter::Replace(data.replace("darn", "d**n").into_bytes())
+                } else if data.contains("oops") {
+                    // not a complete message, so it gets dropped
+                    Filter::Replace(b"oops".to_vec())
+                } else {
+                    Filter::Allow
+                }
+            }));
+
+            let mut writer = runner.writer();
+            for msg in &["hello", "oh heck", "darn it", "oops"] {
+                writer.encode(commands::privmsg("#museun", msg)).await.unwrap();
+            }
+
+            while let Some(data) = runner.writer_rx.try_recv() {
+                runner.handle_write(data).unwrap();
+            }
+            runner.drain_queued_messages().await.unwrap();
+
+            let sent = connector.0.lock().unwrap().sent.clone();
+            let sent = sent
+                .iter()
+                .filter(|s| s.starts_with("PRIVMSG #museun"))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                sent,
+                vec!["PRIVMSG #museun :hello\r\n", "PRIVMSG #museun :d**n it\r\n"]
+            );
+        });
+    }
+
+    #[test]
+    fn queued_join_after_ready() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 001 justinfan1234 :Welcome, GLHF!\r\n",
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #museun\r\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            runner.queue_join("museun");
+            assert!(!runner.is_on_channel("#museun"));
+
+            loop {
+                match runner.next_message().await.unwrap() {
+                    Status::Message(Commands::Join(..)) => break,
+                    Status::Message(..) => continue,
+                    status => panic!("unexpected status: {:?}", status),
+                }
+            }
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(lines.last().unwrap(), "JOIN #museun\r\n");
+            assert!(runner.is_on_channel("#museun"));
+        });
+    }
+
+    async fn wait_for_join(runner: &mut AsyncRunner) {
+        loop {
+            match runner.step().await.unwrap() {
+                StepResult::Status(Status::Message(Commands::Join(..))) => break,
+                StepResult::Status(Status::Message(..)) | StepResult::Nothing => continue,
+                status => panic!("unexpected status: {:?}", status),
+            }
+        }
+    }
+
+    #[test]
+    fn queued_joins_are_rate_limited() {
+        futures_lite::future::block_on(async move {
+            let connector = TestConnector::default();
+            connector
+                .conn
+                .write_data(concat!(
+                    ":tmi.trovo.tv 376 justinfan1234 :>\r\n",
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #foo\r\n",
+                    ":justinfan1234!justinfan1234@justinfan1234.tmi.trovo.tv JOIN #bar\r\n",
+                ))
+                .await;
+
+            let user_config = UserConfig::builder().anonymous().build().unwrap();
+            let mut runner = AsyncRunner::connect(connector.clone(), &user_config)
+                .await
+                .unwrap();
+
+            // the rate limit uses the runner's clock
+            let clock = crate::test::TestClock::new();
+            runner.set_clock(clock.clone());
+            runner.set_join_rate_limit(RateLimit::full(1, Duration::from_secs(30)));
+
+            // these are the same channel
+            runner.queue_join("Foo");
+            runner.queue_join("#foo");
+            runner.queue_join("bar");
+
+            // only one fits in the bucket
+            wait_for_join(&mut runner).await;
+            assert!(runner.is_on_channel("#foo"));
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            let joins = lines.iter().filter(|s| s.starts_with("JOIN ")).collect::<Vec<_>>();
+            assert_eq!(joins, vec!["JOIN #foo\r\n"]);
+
+            clock.advance(Duration::from_secs(30));
+            wait_for_join(&mut runner).await;
+            assert!(runner.is_on_channel("#bar"));
+
+            let lines = connector.conn.read_all_lines().await.unwrap();
+            assert_eq!(lines, vec!["JOIN #bar\r\n"]);
+        });
+    }
+}
 }
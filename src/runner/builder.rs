@@ -0,0 +1,93 @@
+use super::{timeout::REGISTRATION_TIMEOUT, AsyncRunner, ConnectionState, Error};
+use crate::{channel::Sender, connector::Connector, decoder::MAX_MESSAGE_SIZE, trovo::UserConfig};
+
+use futures_lite::{AsyncRead, AsyncWrite};
+use std::time::Duration;
+
+// the default size of the read buffer, the same as `futures_lite::io::BufReader`
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+// how many `client-nonce`s to remember for `AsyncRunner::is_own_echo`
+const DEDUPE_WINDOW: usize = 64;
+
+/// A builder for tuning an [AsyncRunner] before it connects
+///
+/// This is made with [AsyncRunner::builder()]. The defaults are the same as [AsyncRunner::connect()].
+#[derive(Debug, Clone)]
+pub struct AsyncRunnerBuilder {
+    pub(super) timeout: Duration,
+    pub(super) read_buffer_size: usize,
+    pub(super) max_message_size: usize,
+    pub(super) writer_capacity: Option<usize>,
+    pub(super) dedupe_window: usize,
+    pub(super) state_tx: Option<Sender<ConnectionState>>,
+}
+
+impl Default for AsyncRunnerBuilder {
+    fn default() -> Self {
+        Self {
+            timeout: REGISTRATION_TIMEOUT,
+            read_buffer_size: READ_BUFFER_SIZE,
+            max_message_size: MAX_MESSAGE_SIZE,
+            writer_capacity: None,
+            dedupe_window: DEDUPE_WINDOW,
+            state_tx: None,
+        }
+    }
+}
+
+impl AsyncRunnerBuilder {
+    /// How long to wait for the connection to be ready.
+    ///
+    /// This defaults to `5` seconds. See [AsyncRunner::connect_with_timeout()].
+    pub fn registration_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The size, in bytes, of the buffer used for reading from the connection.
+    ///
+    /// This defaults to `8 KiB`.
+    pub fn read_buffer_size(mut self, size: usize) -> Self {
+        self.read_buffer_size = size;
+        self
+    }
+
+    /// The maximum size, in bytes, of a message read from the connection.
+    ///
+    /// This defaults to `8 KiB`. See [AsyncDecoder::with_max_message_size()](crate::AsyncDecoder::with_max_message_size()).
+    pub fn max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    /// How many messages the [writers](AsyncRunner::writer()) can queue up before
+    /// the runner has to handle them.
+    ///
+    /// Once this many are queued, [AsyncWriter::encode()](crate::writer::AsyncWriter::encode())
+    /// waits for room. By default there is no limit.
+    pub fn writer_capacity(mut self, capacity: usize) -> Self {
+        self.writer_capacity.replace(capacity);
+        self
+    }
+
+    /// How many of your recently sent `client-nonce`s to remember for [AsyncRunner::is_own_echo()].
+    ///
+    /// This defaults to `64`.
+    pub fn dedupe_window(mut self, window: usize) -> Self {
+        self.dedupe_window = window;
+        self
+    }
+
+    /// Connect with the provided connector and the provided UserConfig, using these settings.
+    pub async fn connect<C>(
+        self,
+        connector: C,
+        user_config: &UserConfig,
+    ) -> Result<AsyncRunner, Error>
+    where
+        C: Connector + 'static,
+        for<'a> &'a C::Output: AsyncRead + AsyncWrite + Send + Sync + Unpin,
+    {
+        AsyncRunner::connect_inner(connector, user_config, self).await
+    }
+}
@@ -1,7 +1,10 @@
+use crate::trovo::Capability;
 use std::collections::HashSet;
 
 /// Capabiltiies Trovo acknowledged.
+#[non_exhaustive]
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Capabilities {
     /// You have the [membership](https://dev.trovo.tv/docs/irc/membership) capability
     pub membership: bool,
@@ -11,4 +14,8 @@ pub struct Capabilities {
     pub tags: bool,
     /// A set of unknown capabilities Trovo sent to use
     pub unknown: HashSet<String>,
+    /// The capabilities Trovo acknowledged (`CAP ACK`), in the order they were acknowledged
+    pub acknowledged: Vec<Capability>,
+    /// The capabilities Trovo rejected (`CAP NAK`), in the order they were rejected
+    pub rejected: Vec<Capability>,
 }
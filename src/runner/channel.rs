@@ -1,8 +1,12 @@
 cfg_async! {
 use super::rate_limit::{PreviousRate, RateLimitedEncoder};
-use crate::rate_limit::{RateClass, RateLimit};
+use crate::{
+    clock::Clock,
+    rate_limit::{RateClass, RateLimit},
+};
 use std::{
     collections::{HashMap, VecDeque},
+    sync::Arc,
     time::Duration,
 };
 
@@ -20,6 +24,11 @@ pub struct Channel {
     pub(crate) rate_limited: RateLimitedEncoder,
     pub(crate) previous: Option<PreviousRate>,
     pub(crate) rated_limited_at: Option<std::time::Instant>,
+    pub(crate) clock: Arc<dyn Clock>,
+    // whether the rate limit was set with `set_rate_limit`
+    pub(crate) custom: bool,
+    // whether we saw our own JOIN, rather than only sending to it (e.g. whispers)
+    pub(crate) joined: bool,
 }
 
 impl std::fmt::Debug for Channel {
@@ -29,8 +38,9 @@ impl std::fmt::Debug for Channel {
 }
 
 impl Channel {
-    pub(crate) fn new(name: String) -> Self {
-        let rate_limit = RateLimit::from_class(RateClass::Regular);
+    pub(crate) fn new(name: String, clock: Arc<dyn Clock>) -> Self {
+        let mut rate_limit = default_rate_limit(&name);
+        rate_limit.set_clock(clock.clone());
         let rate_limited = RateLimitedEncoder {
             rate_limit,
             queue: VecDeque::new(),
@@ -40,13 +50,29 @@ impl Channel {
             rate_limited,
             previous: None,
             rated_limited_at: None,
+            clock,
+            custom: false,
+            joined: false,
         }
     }
 
     /// Set the [RateClass] for this channel
     pub fn set_rate_class(&mut self, rate_class: RateClass) {
         self.rate_limited.rate_limit = RateLimit::from_class(rate_class);
+        self.rate_limited.rate_limit.set_clock(self.clock.clone());
         self.rated_limited_at.take();
+        self.custom = false;
+    }
+
+    /// Use a custom [RateLimit] for this channel, e.g. one made with a [RateLimitBuilder](crate::rate_limit::RateLimitBuilder)
+    ///
+    /// The runner won't switch the rate class for a custom rate limit when your mod status changes.
+    /// Use [Channel::set_rate_class()] or [Channel::reset_rate_limit()] to go back to that.
+    pub fn set_rate_limit(&mut self, rate_limit: RateLimit) {
+        self.rate_limited.rate_limit = rate_limit;
+        self.rate_limited.rate_limit.set_clock(self.clock.clone());
+        self.rated_limited_at.take();
+        self.custom = true;
     }
 
     /// Mark this channel as being under slow mode for `duration`
@@ -71,23 +97,43 @@ impl Channel {
     /// Mark that you've been rate limited on this channel
     pub fn set_rate_limited(&mut self) {
         self.rate_limited.rate_limit.set_cap(1);
-        self.rated_limited_at.replace(std::time::Instant::now());
+        self.rated_limited_at.replace(self.clock.now());
     }
 
     /// Reset to the default rate class
     pub fn reset_rate_limit(&mut self) {
-        let PreviousRate { cap, period } = self.previous.take().unwrap_or_default();
-        self.rate_limited.rate_limit = RateLimit::full(cap, period);
+        self.rate_limited.rate_limit = match self.previous.take() {
+            Some(PreviousRate { cap, period }) => RateLimit::full(cap, period),
+            None => default_rate_limit(&self.name),
+        };
+        self.rate_limited.rate_limit.set_clock(self.clock.clone());
         self.rated_limited_at.take();
+        self.custom = false;
+    }
+}
+
+// whispers are sent to the 'jtv' pseudo-channel, which has its own limit
+fn default_rate_limit(name: &str) -> RateLimit {
+    match name {
+        "jtv" => RateLimit::trovo_whisper(),
+        _ => RateLimit::from_class(RateClass::Regular),
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Channels {
     pub map: HashMap<String, Channel>,
+    pub clock: Arc<dyn Clock>,
 }
 
 impl Channels {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            map: HashMap::new(),
+            clock,
+        }
+    }
+
     pub fn is_on(&self, name: &str) -> bool {
         self.map.contains_key(name)
     }
@@ -102,10 +148,26 @@ impl Channels {
             return;
         }
 
-        let channel = Channel::new(name.to_string());
+        let channel = Channel::new(name.to_string(), self.clock.clone());
         self.map.insert(name.to_string(), channel);
     }
 
+    // track the channel and mark it as joined
+    pub fn join(&mut self, name: &str) {
+        self.add(name);
+        if let Some(channel) = self.map.get_mut(name) {
+            channel.joined = true;
+        }
+    }
+
+    // the channels we saw our own JOIN for
+    pub fn joined(&self) -> impl Iterator<Item = &str> + '_ {
+        self.map
+            .values()
+            .filter(|channel| channel.joined)
+            .map(|channel| channel.name.as_str())
+    }
+
     pub fn remove(&mut self, name: &str) {
         self.map.remove(name);
     }
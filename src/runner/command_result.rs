@@ -0,0 +1,238 @@
+use crate::messages::Notice;
+
+/// A `NOTICE` that Trovo sent in response to a command you sent, e.g. a `/ban`
+///
+/// See [AsyncRunner::command_result()](crate::AsyncRunner::command_result) for how to get these.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandResult {
+    /// The channel the command was sent to
+    pub channel: String,
+    /// The command that was sent, e.g. `/ban museun`
+    pub command: String,
+    /// The notice Trovo replied with
+    pub notice: Notice<'static>,
+}
+
+impl CommandResult {
+    /// The raw `msg-id` of the notice, e.g. `bad_ban_no_user`
+    pub fn msg_id(&self) -> &str {
+        self.notice.tags().get("msg-id").unwrap_or_default()
+    }
+
+    /// Whether Trovo reported the command as successful
+    pub fn is_success(&self) -> bool {
+        self.msg_id().ends_with("_success")
+    }
+
+    /// Whether the `msg-id` is a response to this slash `command`
+    ///
+    /// See [RESPONSES] for which msg-ids each command gets.
+    pub(crate) fn is_response(command: &str, msg_id: &str) -> bool {
+        let name = match command
+            .strip_prefix('/')
+            .and_then(|s| s.split_whitespace().next())
+        {
+            Some(name) => name,
+            None => return false,
+        };
+
+        RESPONSES.iter().any(|(command, msg_ids)| {
+            command.eq_ignore_ascii_case(name) && msg_ids.contains(&msg_id)
+        })
+    }
+}
+
+/// The `msg-id`s Trovo can reply with for each slash command
+const RESPONSES: &[(&str, &[&str])] = &[
+    (
+        "ban",
+        &[
+            "ban_success",
+            "already_banned",
+            "bad_ban_admin",
+            "bad_ban_anon",
+            "bad_ban_broadcaster",
+            "bad_ban_global_mod",
+            "bad_ban_mod",
+            "bad_ban_no_user",
+            "bad_ban_self",
+            "bad_ban_staff",
+            "usage_ban",
+        ],
+    ),
+    (
+        "unban",
+        &["unban_success", "bad_unban_no_ban", "usage_unban"],
+    ),
+    (
+        "timeout",
+        &[
+            "timeout_success",
+            "already_banned",
+            "bad_timeout_admin",
+            "bad_timeout_anon",
+            "bad_timeout_broadcaster",
+            "bad_timeout_duration",
+            "bad_timeout_global_mod",
+            "bad_timeout_mod",
+            "bad_timeout_self",
+            "bad_timeout_staff",
+            "usage_timeout",
+        ],
+    ),
+    (
+        "untimeout",
+        &[
+            "untimeout_success",
+            "timeout_no_timeout",
+            "untimeout_banned",
+            "usage_untimeout",
+        ],
+    ),
+    (
+        "mod",
+        &["mod_success", "bad_mod_banned", "bad_mod_mod", "usage_mod"],
+    ),
+    ("unmod", &["unmod_success", "bad_unmod_mod", "usage_unmod"]),
+    ("mods", &["room_mods", "no_mods", "usage_mods"]),
+    (
+        "emoteonly",
+        &[
+            "emote_only_on",
+            "already_emote_only_on",
+            "usage_emote_only_on",
+        ],
+    ),
+    (
+        "emoteonlyoff",
+        &[
+            "emote_only_off",
+            "already_emote_only_off",
+            "usage_emote_only_off",
+        ],
+    ),
+    ("r9kbeta", &["r9k_on", "already_r9k_on", "usage_r9k_on"]),
+    (
+        "r9kbetaoff",
+        &["r9k_off", "already_r9k_off", "usage_r9k_off"],
+    ),
+    (
+        "subscribers",
+        &["subs_on", "already_subs_on", "usage_subs_on"],
+    ),
+    (
+        "subscribersoff",
+        &["subs_off", "already_subs_off", "usage_subs_off"],
+    ),
+    ("slow", &["slow_on", "bad_slow_duration", "usage_slow_on"]),
+    ("slowoff", &["slow_off", "usage_slow_off"]),
+    (
+        "followers",
+        &["followers_on", "followers_on_zero", "usage_followers_on"],
+    ),
+    ("followersoff", &["followers_off", "usage_followers_off"]),
+    (
+        "host",
+        &[
+            "host_success",
+            "host_success_viewers",
+            "bad_host_error",
+            "bad_host_hosting",
+            "bad_host_rate_exceeded",
+            "bad_host_rejected",
+            "bad_host_self",
+            "hosts_remaining",
+            "usage_host",
+        ],
+    ),
+    (
+        "unhost",
+        &[
+            "host_off",
+            "not_hosting",
+            "bad_unhost_error",
+            "usage_unhost",
+        ],
+    ),
+    (
+        "raid",
+        &[
+            "raid_error_already_raiding",
+            "raid_error_forbidden",
+            "raid_error_self",
+            "raid_error_too_many_viewers",
+            "raid_error_unexpected",
+            "raid_notice_mature",
+            "raid_notice_restricted_chat",
+            "usage_raid",
+        ],
+    ),
+    (
+        "unraid",
+        &[
+            "unraid_success",
+            "unraid_error_no_active_raid",
+            "unraid_error_unexpected",
+            "usage_unraid",
+        ],
+    ),
+    (
+        "color",
+        &["color_changed", "turbo_only_color", "usage_color"],
+    ),
+    (
+        "commercial",
+        &[
+            "commercial_success",
+            "bad_commercial_error",
+            "usage_commercial",
+        ],
+    ),
+    (
+        "delete",
+        &[
+            "delete_message_success",
+            "bad_delete_message_broadcaster",
+            "bad_delete_message_mod",
+        ],
+    ),
+    ("marker", &["bad_marker_client", "usage_marker"]),
+    ("help", &["cmds_available", "no_help", "usage_help"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_result_is_response() {
+        let tests = &[
+            ("/ban museun", "bad_ban_no_user", true),
+            ("/ban museun", "ban_success", true),
+            ("/ban museun", "already_banned", true),
+            ("/ban museun", "bad_unban_no_ban", false),
+            ("/unban museun", "bad_unban_no_ban", true),
+            ("/unban museun", "ban_success", false),
+            ("/timeout museun 10", "timeout_success", true),
+            ("/emoteonly", "emote_only_on", true),
+            ("/emoteonly", "emote_only_off", false),
+            ("/r9kbeta", "r9k_on", true),
+            ("/subscribers", "subs_on", true),
+            ("/subscribersoff", "subs_on", false),
+            ("/unknown", "ban_success", false),
+            ("/mod museun", "bad_ban_no_user", false),
+            ("ban museun", "ban_success", false),
+            ("/", "ban_success", false),
+        ];
+
+        for (command, msg_id, expected) in tests {
+            assert_eq!(
+                CommandResult::is_response(command, msg_id),
+                *expected,
+                "{} -> {}",
+                command,
+                msg_id
+            );
+        }
+    }
+}
@@ -0,0 +1,28 @@
+/// The state of the connection to Trovo
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum ConnectionState {
+    /// Opening the connection
+    Connecting,
+    /// Connected, and waiting for Trovo to finish the registration
+    Registering,
+    /// Ready to use
+    Connected,
+    /// Trovo asked us to reconnect
+    Reconnecting,
+    /// The connection was closed, either by quitting or it was lost
+    Closed,
+}
+
+impl std::fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            Self::Connecting => "connecting",
+            Self::Registering => "registering",
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::Closed => "closed",
+        };
+        f.write_str(state)
+    }
+}
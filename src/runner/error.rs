@@ -1,4 +1,4 @@
-use crate::{DecodeError, MessageError};
+use crate::{trovo::Capability, DecodeError, MessageError};
 
 /// An error returned by a Runner
 #[derive(Debug)]
@@ -14,6 +14,8 @@ pub enum Error {
         /// The capability name
         cap: String,
     },
+    /// Trovo rejected a capability the runner requires, e.g. `Tags` or `Commands`.
+    CapabilityRejected(Capability),
     /// You're already on that channel
     AlreadyOnChannel {
         /// The channel name
@@ -29,12 +31,28 @@ pub enum Error {
         /// The channel name
         channel: String,
     },
+    /// Trovo didn't confirm joining this channel in time
+    JoinTimeout {
+        /// The channel name
+        channel: String,
+    },
+    /// The message being waited for didn't arrive in time
+    WaitTimeout,
     /// Your connection timed out.
     TimedOut,
+    /// The connection wasn't ready in time, e.g. Trovo never acknowledged the capabilities.
+    RegistrationTimeout,
     /// Trovo restarted the server, you should reconnect.
     ShouldReconnect,
     /// An unexpected EOF was found -- this means the connectionc losed abnormally.
     UnexpectedEof,
+    /// Trovo sent a message longer than the maximum message size
+    MessageTooLong {
+        /// The maximum message size, in bytes
+        max: usize,
+    },
+    /// Trovo sent a message that ended with a bare `\n` instead of `\r\n`
+    BareNewline,
 }
 
 impl std::fmt::Display for Error {
@@ -46,12 +64,24 @@ impl std::fmt::Display for Error {
             Self::InvalidCap { cap } => {
                 write!(f, "request capability '{}' was not acknowledged", cap)
             }
+            Self::CapabilityRejected(cap) => {
+                write!(f, "required capability '{:?}' was rejected", cap)
+            }
             Self::AlreadyOnChannel { channel } => write!(f, "already on channel '{}'", channel),
             Self::NotOnChannel { channel } => write!(f, "not on channel '{}'", channel),
             Self::BannedFromChannel { channel } => write!(f, "banned from channel '{}'", channel),
+            Self::JoinTimeout { channel } => {
+                write!(f, "timed out waiting to join channel '{}'", channel)
+            }
+            Self::WaitTimeout => write!(f, "timed out waiting for a reply"),
             Self::TimedOut => write!(f, "your connection timed out"),
+            Self::RegistrationTimeout => {
+                write!(f, "timed out waiting for the connection to be ready")
+            }
             Self::ShouldReconnect => write!(f, "you should reconnect. Trovo restarted the server"),
             Self::UnexpectedEof => write!(f, "reached an unexpected EOF"),
+            Self::MessageTooLong { max } => write!(f, "read a message longer than {} bytes", max),
+            Self::BareNewline => write!(f, "read a message that ended with a bare \\n"),
         }
     }
 }
@@ -74,6 +104,9 @@ impl From<DecodeError> for Error {
             DecodeError::InvalidUtf8(err) => Self::InvalidUtf8(err),
             DecodeError::ParseError(err) => Self::ParsingFailure(err),
             DecodeError::Eof => Self::UnexpectedEof,
+            DecodeError::TimedOut => Self::TimedOut,
+            DecodeError::MessageTooLong { max } => Self::MessageTooLong { max },
+            DecodeError::BareNewline => Self::BareNewline,
         }
     }
 }
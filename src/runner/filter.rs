@@ -0,0 +1,10 @@
+/// What an [outbound filter](crate::AsyncRunner::set_outbound_filter()) does with a message before it is written
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Filter {
+    /// Write the message as-is
+    Allow,
+    /// Write this instead of the message. It must be a complete IRC message, ending with `\r\n`
+    Replace(Vec<u8>),
+    /// Don't write the message
+    Drop,
+}
@@ -1,9 +1,15 @@
-use crate::{runner::Capabilities, trovo::Color};
+use crate::{
+    runner::Capabilities,
+    trovo::{Badge, Color},
+};
 
 /// Your identity on Trovo.
 ///
 /// Currently this is only updated when you connect.
-#[derive(Debug, Clone)]
+///
+/// With the `serde` feature this can be serialized, e.g. to log it for a later reconnect.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Identity {
     /// An anonymous identity.
     Anonymous {
@@ -24,6 +30,7 @@ pub enum Identity {
     /// This has more information about your user than a `Basic` identity.
     ///
     /// This is created if you've enabled atleast `Tags` and `Commands`.
+    #[non_exhaustive]
     Full {
         /// Your username
         name: String,
@@ -33,6 +40,12 @@ pub enum Identity {
         display_name: Option<String>,
         /// You display color, if set
         color: Color,
+        /// Your global badges, as sent by Trovo (e.g. `premium/1`)
+        ///
+        /// Use [Identity::badges()] to parse them.
+        badges: Vec<String>,
+        /// Your available emote sets
+        emote_sets: Vec<String>,
         /// The capabilities you'll have
         caps: Capabilities,
     },
@@ -49,4 +62,70 @@ impl Identity {
             Self::Basic { name, .. } | Self::Full { name, .. } => &*name,
         }
     }
+
+    /// Get the capabilities negotiated for this identity
+    pub fn capabilities(&self) -> &Capabilities {
+        match self {
+            Self::Anonymous { caps } | Self::Basic { caps, .. } | Self::Full { caps, .. } => caps,
+        }
+    }
+
+    /// Get your global badges from this identity
+    ///
+    /// This is only available for a [Full](Identity::Full) identity.
+    pub fn badges(&self) -> Vec<Badge<'_>> {
+        match self {
+            Self::Full { badges, .. } => badges.iter().filter_map(|s| Badge::parse(s)).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::trovo::{BadgeKind, Capability};
+
+    #[test]
+    fn identity_serde() {
+        let caps = Capabilities {
+            membership: true,
+            commands: true,
+            tags: true,
+            acknowledged: vec![
+                Capability::Membership,
+                Capability::Tags,
+                Capability::Commands,
+            ],
+            ..Capabilities::default()
+        };
+
+        let identity = Identity::Full {
+            name: "museun".into(),
+            user_id: 23_196_011,
+            display_name: Some("Museun".into()),
+            color: "#FF00FF".parse().unwrap(),
+            badges: vec!["premium/1".into(), "foo/2".into()],
+            emote_sets: vec!["0".into(), "1234".into()],
+            caps,
+        };
+
+        let json = serde_json::to_string(&identity).unwrap();
+        let output: Identity = serde_json::from_str(&json).unwrap();
+        assert_eq!(output, identity);
+
+        let kinds = output
+            .badges()
+            .into_iter()
+            .map(|b| b.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![BadgeKind::Premium, BadgeKind::Unknown("foo")]);
+
+        let identity = Identity::Anonymous {
+            caps: Capabilities::default(),
+        };
+        let json = serde_json::to_string(&identity).unwrap();
+        assert_eq!(serde_json::from_str::<Identity>(&json).unwrap(), identity);
+        assert!(identity.badges().is_empty());
+    }
 }
@@ -5,13 +5,16 @@
 //! 1. create a [UserConfig](crate::UserConfig).
 //! 1. create and connect the [AsyncRunner] via its [AsyncRunner::connect()] method
 //! 1. now you're connected to Trovo, so next things you can do.
-//!     1. join a channel with: [AsyncRunner::join()],
+//!     1. join a channel with: [AsyncRunner::join()] (or [AsyncRunner::queue_join()] to not wait for it),
 //!     1. write messages with the [AsyncWriter](crate::writer::AsyncWriter) provided by [AsyncRunner::writer()].
 //!     1. signal you want to quit with the [AsyncRunner::quit_handle()]
 //!
 
 mod status;
-pub use status::{Status, StepResult};
+pub use status::{Status, StatusKind, StepResult};
+
+mod connection_state;
+pub use connection_state::ConnectionState;
 
 mod capabilities;
 pub use capabilities::Capabilities;
@@ -22,6 +25,15 @@ pub use identity::Identity;
 mod error;
 pub use error::Error;
 
+mod command_result;
+pub use command_result::CommandResult;
+
+mod retry;
+pub use retry::RetryStrategy;
+
+mod filter;
+pub use filter::Filter;
+
 #[allow(dead_code)]
 mod timeout;
 
@@ -37,6 +49,9 @@ cfg_async! {
 cfg_async! {
     mod async_runner;
     pub use async_runner::AsyncRunner;
+
+    mod builder;
+    pub use builder::AsyncRunnerBuilder;
 }
 
 cfg_async! {
@@ -34,6 +34,8 @@ impl RateLimitedEncoder {
                         "local rate limit for '{}' hit",
                         name
                     );
+                    // keep it for the next drain
+                    self.queue.push_front(data);
                     break;
                 }
             }
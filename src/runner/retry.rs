@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+/// How to retry something that failed, e.g. connecting to Trovo.
+///
+/// The delay starts at the `initial` delay and is doubled after each attempt,
+/// up to the `max` delay.
+///
+/// ```
+/// # use trovochat::runner::RetryStrategy;
+/// # use std::time::Duration;
+/// let strategy = RetryStrategy::new(Duration::from_secs(1), Duration::from_secs(5)).max_attempts(4);
+/// assert_eq!(strategy.delay_for(1), Some(Duration::from_secs(1)));
+/// assert_eq!(strategy.delay_for(2), Some(Duration::from_secs(2)));
+/// assert_eq!(strategy.delay_for(3), Some(Duration::from_secs(4)));
+/// assert_eq!(strategy.delay_for(4), Some(Duration::from_secs(5)));
+/// // that was the last attempt
+/// assert_eq!(strategy.delay_for(5), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryStrategy {
+    initial: Duration,
+    max: Duration,
+    max_attempts: Option<usize>,
+}
+
+impl Default for RetryStrategy {
+    /// Starts at `1` second, up to `1` minute, retrying `10` times.
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), Duration::from_secs(60)).max_attempts(10)
+    }
+}
+
+impl RetryStrategy {
+    /// Create an exponential backoff from the `initial` delay up to the `max` delay.
+    ///
+    /// This retries forever, use [RetryStrategy::max_attempts()] to limit it.
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            max_attempts: None,
+        }
+    }
+
+    /// Always wait for the same `delay` between attempts.
+    ///
+    /// This retries forever, use [RetryStrategy::max_attempts()] to limit it.
+    pub const fn constant(delay: Duration) -> Self {
+        Self::new(delay, delay)
+    }
+
+    /// Give up after this many retries
+    pub const fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// How long to wait before the `attempt`th retry (starting at `1`).
+    ///
+    /// This returns `None` if you should give up.
+    pub fn delay_for(&self, attempt: usize) -> Option<Duration> {
+        if attempt == 0 || matches!(self.max_attempts, Some(max) if attempt > max) {
+            return None;
+        }
+
+        let factor = 2_u32.saturating_pow((attempt - 1) as u32);
+        let delay = self.initial.checked_mul(factor).unwrap_or(self.max);
+        Some(std::cmp::min(delay, self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_backoff() {
+        let strategy = RetryStrategy::new(Duration::from_millis(100), Duration::from_secs(1));
+        let expected = &[100, 200, 400, 800, 1000, 1000];
+        for (attempt, expected) in (1..).zip(expected) {
+            assert_eq!(
+                strategy.delay_for(attempt),
+                Some(Duration::from_millis(*expected))
+            );
+        }
+
+        // it doesn't overflow
+        assert_eq!(strategy.delay_for(100), Some(Duration::from_secs(1)));
+        assert_eq!(strategy.delay_for(0), None);
+    }
+
+    #[test]
+    fn retry_constant() {
+        let strategy = RetryStrategy::constant(Duration::from_secs(3)).max_attempts(2);
+        assert_eq!(strategy.delay_for(1), Some(Duration::from_secs(3)));
+        assert_eq!(strategy.delay_for(2), Some(Duration::from_secs(3)));
+        assert_eq!(strategy.delay_for(3), None);
+    }
+}
@@ -19,3 +19,84 @@ pub enum Status<'a> {
     /// Loop run to completion
     Eof,
 }
+
+impl<'a> Status<'a> {
+    /// The kind of this status, without its message
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            Self::Message(..) => StatusKind::Message,
+            Self::Quit => StatusKind::Quit,
+            Self::Eof => StatusKind::Eof,
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Status<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message(msg) => write!(f, "message: {}", msg.raw().trim_end()),
+            Self::Quit => f.write_str("quit"),
+            Self::Eof => f.write_str("eof"),
+        }
+    }
+}
+
+/// The kind of a [Status], e.g. for logging or serializing the outcome of the loop
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub enum StatusKind {
+    /// A message was produced
+    Message,
+    /// The user quit the loop
+    Quit,
+    /// Loop run to completion
+    Eof,
+}
+
+impl std::fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Message => f.write_str("message"),
+            Self::Quit => f.write_str("quit"),
+            Self::Eof => f.write_str("eof"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{irc::parse, FromIrcMessage as _};
+
+    #[test]
+    fn status_display() {
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n";
+        let msg = Commands::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            Status::Message(msg).to_string(),
+            "message: :test!user@host PRIVMSG #museun :this is a test"
+        );
+        assert_eq!(Status::Quit.to_string(), "quit");
+        assert_eq!(Status::Eof.to_string(), "eof");
+        assert_eq!(Status::Eof.kind().to_string(), "eof");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn status_kind_serde() {
+        let input = ":test!user@host PRIVMSG #museun :this is a test\r\n";
+        let msg = Commands::from_irc(parse(input).next().unwrap().unwrap()).unwrap();
+
+        for (status, expected) in &[
+            (Status::Message(msg), r#""Message""#),
+            (Status::Quit, r#""Quit""#),
+            (Status::Eof, r#""Eof""#),
+        ] {
+            let json = serde_json::to_string(&status.kind()).unwrap();
+            assert_eq!(json, *expected);
+
+            let out = serde_json::from_str::<StatusKind>(&json).unwrap();
+            assert_eq!(out, status.kind());
+        }
+    }
+}
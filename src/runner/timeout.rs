@@ -8,18 +8,23 @@ pub enum TimeoutState {
 }
 
 impl TimeoutState {
-    pub fn activity() -> Self {
-        Self::Activity(Instant::now())
+    pub fn activity(now: Instant) -> Self {
+        Self::Activity(now)
     }
 
-    pub fn waiting_for_pong() -> Self {
-        Self::WaitingForPong(Instant::now())
+    pub fn waiting_for_pong(now: Instant) -> Self {
+        Self::WaitingForPong(now)
     }
 }
 
 pub const WINDOW: Duration = Duration::from_secs(45);
 pub const TIMEOUT: Duration = Duration::from_secs(10);
 pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(30);
+pub const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(5);
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+pub const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const COMMAND_RESULT_TIMEOUT: Duration = Duration::from_secs(10);
 
 cfg_async! {
     pub async fn next_delay() {
@@ -0,0 +1,40 @@
+use crate::clock::Clock;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// A [Clock] that only moves when you [advance](TestClock::advance()) it
+///
+/// Clones share the same time, so you can hand a clone to a
+/// [RateLimit](crate::rate_limit::RateLimit) and advance it from your test.
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestClock {
+    /// Create a new clock, starting at the current time
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Move the clock forward by `dur`
+    pub fn advance(&self, dur: Duration) {
+        *self.now.lock().unwrap() += dur;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
@@ -4,6 +4,9 @@ pub use self::str::*;
 mod tags_builder;
 pub use tags_builder::{BuilderError, TagsBuilder, UserTags};
 
+mod clock;
+pub use clock::TestClock;
+
 #[cfg(feature = "testing")]
 #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
 mod conn;
@@ -34,8 +34,50 @@ pub enum BadgeKind<'a> {
     Unknown(&'a str),
 }
 
+impl<'a> BadgeKind<'a> {
+    /// The position this badge should be rendered at, relative to other badges.
+    ///
+    /// Lower values are displayed first. [Unknown] badges are always displayed last.
+    ///
+    /// [Unknown]: BadgeKind::Unknown
+    pub const fn display_order(&self) -> u8 {
+        match self {
+            Self::Broadcaster => 0,
+            Self::Moderator => 1,
+            Self::VIP => 2,
+            Self::Subscriber => 3,
+            Self::Staff => 4,
+            Self::Admin => 5,
+            Self::GlobalMod => 6,
+            Self::Partner => 7,
+            Self::Turbo => 8,
+            Self::Premium => 9,
+            Self::Bits => 10,
+            Self::Unknown(..) => u8::MAX,
+        }
+    }
+}
+
+impl<'a> PartialOrd for BadgeKind<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for BadgeKind<'a> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            // unknown badges are ordered by their name so the ordering stays total
+            (Self::Unknown(left), Self::Unknown(right)) => left.cmp(right),
+            _ => self.display_order().cmp(&other.display_order()),
+        }
+    }
+}
+
 /// Badges attached to a message
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// Badges are ordered by their [kind][BadgeKind::display_order], then by their data.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 pub struct Badge<'a> {
     /// The kind of the Badge
@@ -77,4 +119,52 @@ impl<'a> Badge<'a> {
 /// Metadata to the chat badges
 pub type BadgeInfo<'a> = Badge<'a>;
 
-// TODO tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_badges() {
+        let input =
+            "subscriber/12,bits/100,premium/1,foo/1,vip/1,moderator/1,broadcaster/1,staff/1";
+        let mut badges = input
+            .split(',')
+            .filter_map(Badge::parse)
+            .collect::<Vec<_>>();
+        badges.sort();
+
+        let expected = vec![
+            BadgeKind::Broadcaster,
+            BadgeKind::Moderator,
+            BadgeKind::VIP,
+            BadgeKind::Subscriber,
+            BadgeKind::Staff,
+            BadgeKind::Premium,
+            BadgeKind::Bits,
+            BadgeKind::Unknown("foo"),
+        ];
+        let kinds = badges
+            .into_iter()
+            .map(|badge| badge.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, expected);
+    }
+
+    #[test]
+    fn sort_unknown_badges() {
+        let mut badges = vec![
+            BadgeKind::Unknown("b"),
+            BadgeKind::Turbo,
+            BadgeKind::Unknown("a"),
+        ];
+        badges.sort();
+        assert_eq!(
+            badges,
+            vec![
+                BadgeKind::Turbo,
+                BadgeKind::Unknown("a"),
+                BadgeKind::Unknown("b"),
+            ]
+        );
+    }
+}
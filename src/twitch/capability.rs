@@ -20,6 +20,11 @@ pub enum Capability {
 }
 
 impl Capability {
+    /// Every capability, in the order they should be requested in
+    pub fn all() -> &'static [Capability] {
+        &[Self::Membership, Self::Tags, Self::Commands]
+    }
+
     /// Encode this capability as a string, to be sent to the server
     pub fn encode_as_str(self) -> &'static str {
         match self {
@@ -29,6 +34,52 @@ impl Capability {
         }
     }
 
+    /// Encode these capabilities as a single `CAP REQ` line, to be sent to the server
+    ///
+    /// The capabilities are [ordered](Capability::ordered()) first. If `caps` is empty, this is an empty string.
+    ///
+    /// ```
+    /// # use trovochat::trovo::Capability;
+    /// assert_eq!(
+    ///     Capability::request_line(Capability::all()),
+    ///     "CAP REQ :trovo.tv/membership trovo.tv/tags trovo.tv/commands\r\n"
+    /// );
+    /// ```
+    pub fn request_line(caps: &[Capability]) -> String {
+        let caps = Self::ordered(caps);
+        if caps.is_empty() {
+            return String::new();
+        }
+
+        let names = caps.iter().map(|cap| cap.name()).collect::<Vec<_>>();
+        format!("CAP REQ :{}\r\n", names.join(" "))
+    }
+
+    // whether the runner can't work without this capability. a `CAP REQ` is
+    // acknowledged or rejected as a whole, so these are requested apart from the rest
+    pub(crate) const fn is_required(self) -> bool {
+        matches!(self, Self::Tags | Self::Commands)
+    }
+
+    // the name Trovo uses for this capability
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Membership => "trovo.tv/membership",
+            Self::Tags => "trovo.tv/tags",
+            Self::Commands => "trovo.tv/commands",
+        }
+    }
+
+    /// Sort these capabilities into the order they should be requested in, removing any duplicates.
+    ///
+    /// Trovo expects `Membership` to be requested first, followed by `Tags` and then `Commands`.
+    pub fn ordered(caps: &[Capability]) -> Vec<Capability> {
+        let mut caps = caps.to_vec();
+        caps.sort();
+        caps.dedup();
+        caps
+    }
+
     /// Attempts to 'parse' this capability from a string
     ///
     /// This will take the form of `trovo.tv/$tag` and produce a [Capability]
@@ -65,15 +65,34 @@ use std::{convert::TryFrom, str::FromStr};
 pub enum ParseError {
     /// An invalid hex string for `RGB`
     InvalidHexString,
+    /// An invalid CSS `rgb(r, g, b)` string for `RGB`
+    InvalidCssString,
     /// Unknown color name
     UnknownColor,
+    /// A hex string that wasn't 6 digits long, see [RGB::try_from_hex]
+    InvalidHexLength {
+        /// How many digits there were
+        len: usize,
+    },
+    /// A hex string with a character that isn't a hex digit, see [RGB::try_from_hex]
+    InvalidHexDigit {
+        /// The first bad character
+        digit: char,
+    },
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::InvalidHexString => f.write_str("invalid hex string"),
+            Self::InvalidCssString => f.write_str("invalid css rgb string"),
             Self::UnknownColor => f.write_str("unknown color"),
+            Self::InvalidHexLength { len } => {
+                write!(f, "invalid hex string: expected 6 digits, got {}", len)
+            }
+            Self::InvalidHexDigit { digit } => {
+                write!(f, "invalid hex string: {:?} is not a hex digit", digit)
+            }
         }
     }
 }
@@ -83,22 +102,7 @@ impl std::error::Error for ParseError {}
 impl FromStr for RGB {
     type Err = ParseError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let input = input.trim();
-        let input = match (input.chars().next(), input.len()) {
-            (Some('#'), 7) => &input[1..],
-            (.., 6) => input,
-            _ => return Err(ParseError::InvalidHexString),
-        };
-
-        u32::from_str_radix(input, 16)
-            .map(|s| {
-                Self(
-                    ((s >> 16) & 0xFF) as _,
-                    ((s >> 8) & 0xFF) as _,
-                    (s & 0xFF) as _,
-                )
-            })
-            .map_err(|_| ParseError::InvalidHexString)
+        Self::try_from_hex(input).map_err(|_| ParseError::InvalidHexString)
     }
 }
 
@@ -134,6 +138,88 @@ impl RGB {
     pub const fn blue(self) -> u8 {
         self.2
     }
+
+    /// The relative luminance of this color, from `0.0` (black) to `1.0` (white)
+    ///
+    /// This uses the sRGB formula from [WCAG 2.0](https://www.w3.org/TR/WCAG20/#relativeluminancedef)
+    pub fn relative_luminance(self) -> f32 {
+        fn linear(channel: u8) -> f32 {
+            let c = f32::from(channel) / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+
+    /// Try to parse a `#RRGGBB` or `RRGGBB` hex color
+    ///
+    /// Unlike [FromStr], which only gives a [ParseError::InvalidHexString], this
+    /// reports whether the length or one of the digits was wrong.
+    ///
+    /// ```
+    /// # use trovochat::trovo::color::{ParseError, RGB};
+    /// assert_eq!(RGB::try_from_hex("#1E90FF").unwrap(), RGB(30, 144, 255));
+    /// assert!(matches!(
+    ///     RGB::try_from_hex("#1E90F"),
+    ///     Err(ParseError::InvalidHexLength { len: 5 })
+    /// ));
+    /// assert!(matches!(
+    ///     RGB::try_from_hex("#1E90FG"),
+    ///     Err(ParseError::InvalidHexDigit { digit: 'G' })
+    /// ));
+    /// ```
+    pub fn try_from_hex(input: &str) -> Result<Self, ParseError> {
+        let input = input.trim();
+        let digits = input.strip_prefix('#').unwrap_or(input);
+
+        let len = digits.chars().count();
+        if len != 6 {
+            return Err(ParseError::InvalidHexLength { len });
+        }
+
+        if let Some(digit) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidHexDigit { digit });
+        }
+
+        // all 6 characters are ascii hex digits, so this cannot fail
+        let s = u32::from_str_radix(digits, 16).expect("valid hex digits");
+        Ok(Self(
+            ((s >> 16) & 0xFF) as _,
+            ((s >> 8) & 0xFF) as _,
+            (s & 0xFF) as _,
+        ))
+    }
+
+    /// Try to parse a CSS-style `rgb(r, g, b)` color, e.g. `rgb(30, 144, 255)`.
+    ///
+    /// This also accepts the `#RRGGBB` and `RRGGBB` forms that [FromStr] accepts.
+    ///
+    /// ```
+    /// # use trovochat::trovo::color::RGB;
+    /// assert_eq!(RGB::from_css("rgb(30, 144, 255)").unwrap(), RGB(30, 144, 255));
+    /// assert_eq!(RGB::from_css("#1E90FF").unwrap(), RGB(30, 144, 255));
+    /// ```
+    pub fn from_css(input: &str) -> Result<Self, ParseError> {
+        let input = input.trim();
+        let inner = match input.strip_prefix("rgb(").and_then(|s| s.strip_suffix(')')) {
+            Some(inner) => inner,
+            None if !input.starts_with("rgb") => return input.parse(),
+            None => return Err(ParseError::InvalidCssString),
+        };
+
+        let mut parts = inner
+            .split(',')
+            .map(|s| s.trim().parse().map_err(|_| ParseError::InvalidCssString));
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(r), Some(g), Some(b), None) => Ok(Self(r?, g?, b?)),
+            _ => Err(ParseError::InvalidCssString),
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -212,6 +298,33 @@ pub struct Color {
 impl FromStr for Color {
     type Err = ParseError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input, false)
+    }
+}
+
+impl Color {
+    /// Parse a color like [FromStr] does, but report why a hex color is invalid
+    ///
+    /// This uses [RGB::try_from_hex] for anything that isn't a color name, so
+    /// you get a [ParseError::InvalidHexLength] or [ParseError::InvalidHexDigit]
+    /// instead of a [ParseError::InvalidHexString].
+    ///
+    /// ```
+    /// # use trovochat::trovo::color::{Color, ParseError};
+    /// assert!(matches!(
+    ///     "#1E90FG".parse::<Color>(),
+    ///     Err(ParseError::InvalidHexString)
+    /// ));
+    /// assert!(matches!(
+    ///     Color::parse_strict("#1E90FG"),
+    ///     Err(ParseError::InvalidHexDigit { digit: 'G' })
+    /// ));
+    /// ```
+    pub fn parse_strict(input: &str) -> Result<Self, ParseError> {
+        Self::parse(input, true)
+    }
+
+    fn parse(input: &str, strict: bool) -> Result<Self, ParseError> {
         use TrovoColor::*;
         let find = |color| {
             let colors = trovo_colors();
@@ -248,6 +361,7 @@ impl FromStr for Color {
             "sea_green" => find(SeaGreen),
             "spring_green" => find(SpringGreen),
             "yellow_green" => find(YellowGreen),
+            _ if strict => (Turbo, RGB::try_from_hex(input)?),
             _ => (Turbo, input.parse()?),
         };
 
@@ -267,6 +381,30 @@ impl Default for Color {
     }
 }
 
+impl Color {
+    /// The [WCAG contrast ratio](https://www.w3.org/TR/WCAG20/#contrast-ratiodef) of this color against the `bg` color
+    ///
+    /// This ranges from `1.0` (no contrast) to `21.0` (black on white)
+    ///
+    /// ```
+    /// # use trovochat::trovo::color::{Color, RGB};
+    /// let color: Color = "Blue".parse().unwrap();
+    /// assert!(color.contrast_against(RGB(0xFF, 0xFF, 0xFF)) > 8.0);
+    /// ```
+    pub fn contrast_against(&self, bg: RGB) -> f32 {
+        let (fg, bg) = (self.rgb.relative_luminance(), bg.relative_luminance());
+        let (lighter, darker) = if fg > bg { (fg, bg) } else { (bg, fg) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Whether this color is readable on the `bg` color
+    ///
+    /// This is a [contrast ratio](Color::contrast_against) of atleast `4.5`, the WCAG AA level for normal text
+    pub fn is_readable_on(&self, bg: RGB) -> bool {
+        self.contrast_against(bg) >= 4.5
+    }
+}
+
 impl std::fmt::Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use TrovoColor::*;
@@ -493,4 +631,114 @@ mod tests {
         let rgb = RGB::default();
         assert_eq!(rgb, RGB(0xFF, 0xFF, 0xFF))
     }
+
+    #[test]
+    fn rgb_from_css() {
+        let tests = &[
+            ("rgb(30, 144, 255)", RGB(30, 144, 255)),
+            ("rgb(30,144,255)", RGB(30, 144, 255)),
+            ("  rgb( 0 , 0 , 0 )  ", RGB(0, 0, 0)),
+            ("#1E90FF", RGB(30, 144, 255)),
+            ("1E90FF", RGB(30, 144, 255)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(RGB::from_css(input).unwrap(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn rgb_from_css_invalid() {
+        let tests = &[
+            "rgb(30, 144)",
+            "rgb(30, 144, 255, 1)",
+            "rgb(30, 144, 256)",
+            "rgb(-1, 144, 255)",
+            "rgb(a, b, c)",
+            "rgb(30, 144, 255",
+            "rgb 30, 144, 255",
+            "rgb()",
+        ];
+        for input in tests {
+            assert!(
+                matches!(RGB::from_css(input), Err(ParseError::InvalidCssString)),
+                "{}",
+                input
+            );
+        }
+
+        assert!(matches!(
+            RGB::from_css("#1E90F"),
+            Err(ParseError::InvalidHexString)
+        ));
+    }
+
+    #[test]
+    fn rgb_try_from_hex() {
+        let tests = &[
+            ("#1E90FF", RGB(30, 144, 255)),
+            ("1e90ff", RGB(30, 144, 255)),
+            ("  #000000 ", RGB(0, 0, 0)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(RGB::try_from_hex(input).unwrap(), *expected, "{}", input);
+        }
+
+        for (input, expected) in &[("", 0), ("#", 0), ("#1E90F", 5), ("1E90FF00", 8)] {
+            assert!(
+                matches!(
+                    RGB::try_from_hex(input),
+                    Err(ParseError::InvalidHexLength { len }) if len == *expected
+                ),
+                "{}",
+                input
+            );
+        }
+
+        for (input, expected) in &[("#1E90FG", 'G'), ("+1E90F", '+'), ("##1E90F", '#')] {
+            assert!(
+                matches!(
+                    RGB::try_from_hex(input),
+                    Err(ParseError::InvalidHexDigit { digit }) if digit == *expected
+                ),
+                "{}",
+                input
+            );
+        }
+
+        // the FromStr impl keeps its less specific error
+        assert!(matches!(
+            "#1E90FG".parse::<RGB>(),
+            Err(ParseError::InvalidHexString)
+        ));
+    }
+
+    #[test]
+    fn contrast() {
+        const BLACK: RGB = RGB(0x00, 0x00, 0x00);
+        const WHITE: RGB = RGB(0xFF, 0xFF, 0xFF);
+
+        let black = Color {
+            kind: TrovoColor::Turbo,
+            rgb: BLACK,
+        };
+        assert!((black.contrast_against(WHITE) - 21.0).abs() < 0.01);
+        // its symmetric
+        let white = Color::default();
+        assert!((white.contrast_against(BLACK) - 21.0).abs() < 0.01);
+        assert!((white.contrast_against(WHITE) - 1.0).abs() < 0.01);
+
+        assert!(black.is_readable_on(WHITE));
+        assert!(!white.is_readable_on(WHITE));
+
+        // #FF0000 on white is 4.0:1
+        let red: Color = "Red".parse().unwrap();
+        assert!((red.contrast_against(WHITE) - 4.0).abs() < 0.01);
+        assert!(!red.is_readable_on(WHITE));
+        assert!(red.is_readable_on(BLACK));
+
+        // #0000FF on white is 8.59:1
+        let blue: Color = "Blue".parse().unwrap();
+        assert!((blue.contrast_against(WHITE) - 8.59).abs() < 0.01);
+        assert!(blue.is_readable_on(WHITE));
+    }
 }
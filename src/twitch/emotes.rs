@@ -9,6 +9,9 @@ They are presented (to the irc connection) in a `id:range1,range2/id2:range1,..`
 `"testing Kappa"` would be `25:8-13`
 
 `"Kappa testing Kappa"` would be `25:0-5,14-19`
+
+This only has the emote ids. There isn't a documented Trovo CDN url for emote images,
+so this crate doesn't build them. Look the images up by id instead.
 */
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
@@ -37,6 +40,92 @@ impl Emotes {
             emotes.into()
         })
     }
+
+    /// Get the text in `message` that this emote covers
+    ///
+    /// The ranges are `char` indices, so this works with messages that have multi-byte characters.
+    /// Every range covers the same emote, so only the first one is used.
+    ///
+    /// This returns `None` if the range isn't inside of the message.
+    ///
+    /// ```
+    /// # use trovochat::trovo::Emotes;
+    /// let emotes = Emotes::parse_item("25:6-10").unwrap();
+    /// assert_eq!(emotes.text_in("héllo Kappa"), Some("Kappa"));
+    /// ```
+    pub fn text_in<'a>(&self, message: &'a str) -> Option<&'a str> {
+        let Range { start, end } = self.ranges.first()?;
+        let (start, end) = (*start as usize, *end as usize);
+
+        // the byte offset of each char, and of the end of the message
+        let mut offsets = message
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(message.len()));
+
+        // the end is inclusive, so take the offset of the char after it
+        let begin = offsets.nth(start)?;
+        let end = offsets.nth(end.checked_sub(start)?)?;
+        message.get(begin..end)
+    }
+}
+
+/// A single emote in a message, borrowed from the `emotes` tag
+///
+/// Unlike [Emotes], the `id` is kept as a string -- newer emotes have ids like `emotesv2_<hex>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Emote<'a> {
+    /// The id of this emote, e.g. `25` for `Kappa`
+    pub id: &'a str,
+    /// The `(start, end)` positions of this emote in the message.
+    ///
+    /// These are `char` indices (not byte indices), and `end` is inclusive, as Trovo sends them.
+    pub ranges: Vec<(usize, usize)>,
+}
+
+impl<'a> Emote<'a> {
+    /// Parse the `emotes` tag, returning an iterator over each emote
+    ///
+    /// Malformed emotes are skipped.
+    ///
+    /// ```
+    /// # use trovochat::trovo::Emote;
+    /// let mut emotes = Emote::parse("25:0-4,12-16/emotesv2_dcd06b30a5c24f6eb871e8f5edbd44f7:6-10");
+    ///
+    /// let kappa = emotes.next().unwrap();
+    /// assert_eq!(kappa.id, "25");
+    /// assert_eq!(kappa.ranges, vec![(0, 4), (12, 16)]);
+    ///
+    /// let other = emotes.next().unwrap();
+    /// assert_eq!(other.id, "emotesv2_dcd06b30a5c24f6eb871e8f5edbd44f7");
+    /// assert_eq!(other.ranges, vec![(6, 10)]);
+    ///
+    /// assert!(emotes.next().is_none());
+    /// ```
+    pub fn parse(input: &'a str) -> impl Iterator<Item = Self> + 'a {
+        input.split_terminator('/').filter_map(Self::parse_item)
+    }
+
+    /// Parse a single emote, e.g. `25:0-4,6-10`
+    pub fn parse_item(item: &'a str) -> Option<Self> {
+        let (id, tail) = get_parts(item, ':')?;
+        if id.is_empty() {
+            return None;
+        }
+
+        let ranges = tail
+            .split_terminator(',')
+            .map(|range| {
+                let (start, end) = get_parts(range, '-')?;
+                let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+                Some((start, end)).filter(|_| start <= end)
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|ranges| !ranges.is_empty())?;
+
+        Some(Self { id, ranges })
+    }
 }
 
 #[inline]
@@ -103,4 +192,66 @@ mod tests {
             assert_eq!(emotes, *expect);
         }
     }
+
+    #[test]
+    fn emotes_text_in() {
+        let message = "héllo 🙂 Kappa Keepo Kappa";
+        let emotes = Emotes::parse("25:8-12,20-24/1902:14-18").collect::<Vec<_>>();
+        assert_eq!(emotes[0].text_in(message), Some("Kappa"));
+        assert_eq!(emotes[1].text_in(message), Some("Keepo"));
+
+        let inputs = &[("25:0-4", "Kappa", Some("Kappa")), ("25:0-4", "Kapp", None)];
+        for (input, message, expected) in inputs {
+            let emotes = Emotes::parse_item(input).unwrap();
+            assert_eq!(emotes.text_in(message), *expected, "{}", input);
+        }
+
+        let emotes = Emotes {
+            id: 25,
+            ranges: vec![],
+        };
+        assert_eq!(emotes.text_in("Kappa"), None);
+
+        let emotes = Emotes {
+            id: 25,
+            ranges: vec![4..0],
+        };
+        assert_eq!(emotes.text_in("Kappa"), None);
+    }
+
+    #[test]
+    fn parse_emote() {
+        let emotes = Emote::parse("25:0-4,6-10/emotesv2_abc:12-16").collect::<Vec<_>>();
+        assert_eq!(
+            emotes,
+            vec![
+                Emote {
+                    id: "25",
+                    ranges: vec![(0, 4), (6, 10)]
+                },
+                Emote {
+                    id: "emotesv2_abc",
+                    ranges: vec![(12, 16)]
+                },
+            ]
+        );
+
+        assert_eq!(Emote::parse("").count(), 0);
+    }
+
+    #[test]
+    fn parse_emote_malformed() {
+        let inputs = &[
+            "25", "25:", ":0-4", "25:0-", "25:a-4", "25:4-0", "25:0-4,x", "25:-",
+        ];
+        for input in inputs {
+            assert!(Emote::parse_item(input).is_none(), "{}", input);
+        }
+
+        // the malformed ones are skipped
+        let emotes = Emote::parse("25:0-4/bad/33:6-x/86:6-10").collect::<Vec<_>>();
+        assert_eq!(emotes.len(), 2);
+        assert_eq!(emotes[0].id, "25");
+        assert_eq!(emotes[1].id, "86");
+    }
 }
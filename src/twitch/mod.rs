@@ -7,7 +7,7 @@ mod userconfig;
 pub use userconfig::{UserConfig, UserConfigBuilder, UserConfigError};
 
 mod emotes;
-pub use emotes::Emotes;
+pub use emotes::{Emote, Emotes};
 
 mod badge;
 pub use badge::{Badge, BadgeInfo, BadgeKind};
@@ -43,10 +43,35 @@ impl UserConfig {
         UserConfigBuilder::default()
     }
 
+    /// Create an anonymous, read-only config with no capabilities
+    ///
+    /// This uses the [ANONYMOUS_LOGIN](crate::ANONYMOUS_LOGIN) credentials
+    pub fn anonymous() -> Self {
+        let (name, token) = crate::ANONYMOUS_LOGIN;
+        Self {
+            name: name.to_string(),
+            token: token.to_string(),
+            capabilities: vec![],
+        }
+    }
+
     /// Determines whether this config was requested as anonymous
     pub fn is_anonymous(&self) -> bool {
         self.name == crate::JUSTINFAN1234 && self.token == crate::JUSTINFAN1234
     }
+
+    /// The exact bytes a client sends to register with this config.
+    ///
+    /// This is a `CAP REQ` for the optional capabilities and one for the required ones,
+    /// followed by the `PASS` and `NICK` -- the same thing [register](crate::commands::register()) encodes.
+    pub fn registration_bytes(&self) -> Vec<u8> {
+        use crate::Encodable as _;
+        let mut buf = vec![];
+        crate::commands::register(self)
+            .encode(&mut buf)
+            .expect("encoding to a Vec cannot fail");
+        buf
+    }
 }
 
 /// User config error returned by the [UserConfigBuilder]
@@ -125,11 +150,7 @@ impl UserConfigBuilder {
     ///
     /// [here]: Capability
     pub fn enable_all_capabilities(self) -> Self {
-        self.capabilities(&[
-            Capability::Membership,
-            Capability::Tags,
-            Capability::Commands,
-        ])
+        self.capabilities(Capability::all())
     }
 
     /// Tries to build the UserConfig
@@ -237,6 +258,7 @@ mod tests {
         );
 
         assert!(config.is_anonymous());
+        assert_eq!(config, UserConfig::anonymous());
     }
 
     #[test]
@@ -300,4 +322,23 @@ mod tests {
             .unwrap_err();
         matches!(err, UserConfigError::InvalidToken);
     }
+
+    #[test]
+    fn registration_bytes_all_caps() {
+        let config = UserConfig::builder()
+            .anonymous()
+            .capabilities(&[Capability::Commands, Capability::Tags])
+            .enable_all_capabilities()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.registration_bytes(),
+            b"CAP REQ :trovo.tv/membership\r\n\
+            CAP REQ :trovo.tv/tags trovo.tv/commands\r\n\
+            PASS justinfan1234\r\n\
+            NICK justinfan1234\r\n"
+                .to_vec()
+        );
+    }
 }
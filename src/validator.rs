@@ -2,6 +2,7 @@ use crate::{
     irc::{IrcMessage, MessageError, TagIndices},
     MaybeOwnedIndex,
 };
+use alloc::string::ToString;
 
 /// This trait is provided as an easy way of defining your own custom events.
 pub trait Validator {
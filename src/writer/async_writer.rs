@@ -4,13 +4,25 @@ use crate::Encodable;
 
 use futures_lite::AsyncWrite;
 use io::Write;
-use std::io::{self};
+use std::{
+    borrow::Cow,
+    io::{self},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The maximum length, in bytes, Trovo allows for the text of a message
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+
+// `client-nonce`s waiting for Trovo to echo them back, shared with the runner
+pub(crate) type Confirmations = Arc<Mutex<Vec<(String, Sender<()>)>>>;
 
 /// An asynchronous writer.
 #[derive(Clone)]
 pub struct AsyncWriter<W> {
     inner: AsyncEncoder<W>,
     activity_tx: Sender<()>,
+    confirmations: Confirmations,
 }
 
 impl<W> std::fmt::Debug for AsyncWriter<W> {
@@ -53,9 +65,14 @@ where
         Self {
             inner: AsyncEncoder::new(inner),
             activity_tx,
+            confirmations: Confirmations::default(),
         }
     }
 
+    pub(crate) fn confirmations(&self) -> &Confirmations {
+        &self.confirmations
+    }
+
     /// Encode this [Encodable] message to the writer.
     pub async fn encode<M>(&mut self, msg: M) -> io::Result<()>
     where
@@ -71,6 +88,78 @@ where
         Ok(())
     }
 
+    /// Encode a single raw line to the writer. This appends the `\r\n` for you.
+    ///
+    /// This returns an [InvalidInput][std::io::ErrorKind::InvalidInput] error if
+    /// `data` contains a `\r` or `\n`, as that would allow it to be split into
+    /// multiple commands.
+    pub async fn encode_raw(&mut self, data: &str) -> io::Result<()> {
+        if data.contains(&['\r', '\n'][..]) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "raw data cannot contain a CR or LF",
+            ));
+        }
+        self.encode(crate::commands::raw(data)).await
+    }
+
+    /// Send `data` to `channel`, truncating it to fit if it's too long.
+    ///
+    /// If `data` is longer than [MAX_MESSAGE_LENGTH] bytes, it is cut on a char boundary
+    /// and an ellipsis (`…`) is appended.
+    pub async fn say_truncated(&mut self, channel: &str, data: &str) -> io::Result<()> {
+        let data = truncate(data, MAX_MESSAGE_LENGTH);
+        self.encode(crate::commands::privmsg(channel, &data)).await
+    }
+
+    /// Send `data` to `channel` and wait for Trovo to confirm it.
+    ///
+    /// This attaches a unique `client-nonce` tag to the `PRIVMSG` and resolves once
+    /// the runner sees a `USERSTATE` or `PRIVMSG` echoed back with the same nonce.
+    /// If that doesn't happen within `timeout`, a [TimedOut][std::io::ErrorKind::TimedOut]
+    /// error is returned.
+    ///
+    /// The runner has to be driven (e.g. with [AsyncRunner::next_message()][next])
+    /// while waiting, otherwise this cannot be confirmed.
+    ///
+    /// [next]: crate::AsyncRunner::next_message()
+    pub async fn flush_and_confirm(
+        &mut self,
+        channel: &str,
+        data: &str,
+        timeout: Duration,
+    ) -> io::Result<()> {
+        let nonce = format!("{}-{}", crate::util::timestamp(), fastrand::u32(..));
+
+        let mut line = format!("@client-nonce={} ", nonce).into_bytes();
+        crate::commands::privmsg(channel, data).encode(&mut line)?;
+
+        let (tx, rx) = crate::channel::bounded(1);
+        self.confirmations.lock().unwrap().push((nonce.clone(), tx));
+
+        let confirmed = async {
+            self.encode(line).await?;
+            rx.recv().await.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "Runner has closed its sender")
+            })
+        };
+        let timed_out = async {
+            futures_timer::Delay::new(timeout).await;
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Trovo did not confirm the message in time",
+            ))
+        };
+
+        let result = futures_lite::future::or(confirmed, timed_out).await;
+        // it'll have been removed if it was confirmed
+        self.confirmations
+            .lock()
+            .unwrap()
+            .retain(|(pending, _)| *pending != nonce);
+        result
+    }
+
     /// Encode a slice of [Encodable] messages to the writer.
     pub async fn encode_many<'a, I, M>(&mut self, msgs: I) -> io::Result<()>
     where
@@ -84,3 +173,99 @@ where
         Ok(())
     }
 }
+
+fn truncate(data: &str, max: usize) -> Cow<'_, str> {
+    const ELLIPSIS: char = '…';
+
+    if data.len() <= max {
+        return Cow::Borrowed(data);
+    }
+
+    let mut end = max - ELLIPSIS.len_utf8();
+    while !data.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let mut out = String::with_capacity(end + ELLIPSIS.len_utf8());
+    out.push_str(&data[..end]);
+    out.push(ELLIPSIS);
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::MpscWriter;
+
+    #[test]
+    fn encode_raw() {
+        let (tx, rx) = crate::channel::unbounded();
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+        let mut writer = AsyncWriter::new(MpscWriter::new(tx), activity_tx);
+
+        futures_lite::future::block_on(async move {
+            writer.encode_raw("PRIVMSG #museun :hello").await.unwrap();
+            assert_eq!(&*rx.recv().await.unwrap(), b"PRIVMSG #museun :hello\r\n");
+
+            for input in &[
+                "PRIVMSG #museun :hello\r\nQUIT",
+                "PRIVMSG #museun :hello\nQUIT",
+                "PRIVMSG #museun :hello\r",
+            ] {
+                let err = writer.encode_raw(input).await.unwrap_err();
+                assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            }
+            assert!(rx.try_recv().is_none());
+        });
+    }
+
+    #[test]
+    fn flush_and_confirm_timeout() {
+        let (tx, rx) = crate::channel::unbounded();
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+        let mut writer = AsyncWriter::new(MpscWriter::new(tx), activity_tx);
+
+        futures_lite::future::block_on(async move {
+            let err = writer
+                .flush_and_confirm("museun", "hello", Duration::from_millis(50))
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+            assert!(writer.confirmations().lock().unwrap().is_empty());
+
+            let line = rx.recv().await.unwrap();
+            let line = std::str::from_utf8(&line).unwrap();
+            assert!(line.starts_with("@client-nonce="));
+            assert!(line.ends_with(" PRIVMSG #museun :hello\r\n"));
+        });
+    }
+
+    #[test]
+    fn say_truncated() {
+        let (tx, rx) = crate::channel::unbounded();
+        let (activity_tx, _activity_rx) = crate::channel::unbounded();
+        let mut writer = AsyncWriter::new(MpscWriter::new(tx), activity_tx);
+
+        futures_lite::future::block_on(async move {
+            writer.say_truncated("museun", "hello").await.unwrap();
+            assert_eq!(&*rx.recv().await.unwrap(), b"PRIVMSG #museun :hello\r\n");
+
+            // 600 bytes of a 2-byte char
+            let input = "\u{E9}".repeat(300);
+            writer.say_truncated("museun", &input).await.unwrap();
+
+            let line = rx.recv().await.unwrap();
+            let line = std::str::from_utf8(&line).unwrap();
+            let data = line
+                .strip_prefix("PRIVMSG #museun :")
+                .and_then(|s| s.strip_suffix("\r\n"))
+                .unwrap();
+
+            assert!(data.len() <= MAX_MESSAGE_LENGTH);
+            let data = data.strip_suffix('…').unwrap();
+            // 497 would split a char, so it backs up to 496
+            assert_eq!(data.len(), 496);
+            assert_eq!(data, &input[..496]);
+        });
+    }
+}
@@ -1,7 +1,7 @@
 //! A set of writers
 
 mod async_writer;
-pub use async_writer::AsyncWriter;
+pub use async_writer::{AsyncWriter, MAX_MESSAGE_LENGTH};
 
 mod mpsc_writer;
 pub use mpsc_writer::MpscWriter;
@@ -2,19 +2,28 @@ use crate::Encodable;
 
 use futures_lite::AsyncWrite;
 use std::{
+    future::Future,
     io::{self, Write},
     pin::Pin,
     task::{Context, Poll},
 };
 
+type PendingSend = Pin<Box<dyn Future<Output = Result<(), Box<[u8]>>> + Send + Sync>>;
+
 /// A mpsc-based writer.
 ///
 /// This can be used both a [std::io::Write] instance and an [AsyncWrite][async-write] instance.
 ///
+/// If the channel is [bounded](crate::channel::bounded) and full, flushing
+/// asynchronously waits for room, while flushing synchronously returns a
+/// [WouldBlock](std::io::ErrorKind::WouldBlock) error.
+///
 /// [async-write]: futures_lite::AsyncWrite
 pub struct MpscWriter {
     buf: Vec<u8>,
     channel: crate::channel::Sender<Box<[u8]>>,
+    // a line waiting for room in the channel
+    pending: Option<PendingSend>,
 }
 
 impl std::fmt::Debug for MpscWriter {
@@ -28,6 +37,7 @@ impl Clone for MpscWriter {
         Self {
             buf: Vec::new(),
             channel: self.channel.clone(),
+            pending: None,
         }
     }
 }
@@ -38,6 +48,7 @@ impl MpscWriter {
         Self {
             buf: Vec::new(),
             channel,
+            pending: None,
         }
     }
 
@@ -62,24 +73,36 @@ impl MpscWriter {
         Some(tail.into_boxed_slice())
     }
 
-    fn inner_flush(&mut self) -> std::io::Result<()> {
+    // try to send the next line, giving it back if the channel is full
+    fn try_flush(&mut self) -> io::Result<Option<Box<[u8]>>> {
         use crate::channel::TrySendError;
 
         let tail = match self.split_buf() {
             Some(tail) => tail,
             None => {
                 log::warn!("cannot flush an incomplete buffer");
-                return Ok(());
+                return Ok(None);
             }
         };
 
         match self.channel.try_send(tail) {
-            Ok(..) => Ok(()),
-            Err(TrySendError::Closed(..)) => Err(io::Error::new(
-                io::ErrorKind::UnexpectedEof,
-                "writer was closed",
-            )),
-            Err(TrySendError::Full(..)) => unreachable!(),
+            Ok(..) => Ok(None),
+            Err(TrySendError::Closed(..)) => Err(closed()),
+            Err(TrySendError::Full(tail)) => Ok(Some(tail)),
+        }
+    }
+
+    fn inner_flush(&mut self) -> io::Result<()> {
+        match self.try_flush()? {
+            Some(tail) => {
+                // put it back so a later flush can try again
+                self.buf.splice(0..0, tail.iter().copied());
+                Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "writer channel is full",
+                ))
+            }
+            None => Ok(()),
         }
     }
 }
@@ -106,9 +129,26 @@ impl AsyncWrite for MpscWriter {
         Poll::Ready(Ok(buf.len()))
     }
 
-    fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        let mut this = self.as_mut();
-        Poll::Ready(this.inner_flush())
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(pending) = &mut this.pending {
+                let res = futures_lite::ready!(pending.as_mut().poll(cx));
+                this.pending.take();
+                res.map_err(|_| closed())?;
+                return Poll::Ready(Ok(()));
+            }
+
+            let tail = match this.try_flush()? {
+                Some(tail) => tail,
+                None => return Poll::Ready(Ok(())),
+            };
+
+            // wait for the channel to have room for it
+            let channel = this.channel.clone();
+            this.pending
+                .replace(Box::pin(async move { channel.send(tail).await }));
+        }
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -116,6 +156,10 @@ impl AsyncWrite for MpscWriter {
     }
 }
 
+fn closed() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "writer was closed")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,4 +187,31 @@ mod tests {
         assert!(m.flush().is_ok());
         assert_eq!(&*rx.try_recv().unwrap(), b"\r\n");
     }
+
+    #[test]
+    fn mpsc_full_channel() {
+        use futures_lite::AsyncWriteExt;
+
+        let (tx, rx) = crate::channel::bounded(1);
+        let mut m = MpscWriter::new(tx);
+
+        futures_lite::future::block_on(async move {
+            AsyncWriteExt::write_all(&mut m, b"a\r\n").await.unwrap();
+            AsyncWriteExt::flush(&mut m).await.unwrap();
+
+            // a sync flush can't wait, so the line is kept
+            let _ = Write::write(&mut m, b"b\r\n").unwrap();
+            let err = Write::flush(&mut m).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+            // an async flush waits for room
+            let flush = AsyncWriteExt::flush(&mut m);
+            futures_lite::pin!(flush);
+            assert!(futures_lite::future::poll_once(&mut flush).await.is_none());
+
+            assert_eq!(&*rx.recv().await.unwrap(), b"a\r\n");
+            flush.await.unwrap();
+            assert_eq!(&*rx.recv().await.unwrap(), b"b\r\n");
+        });
+    }
 }
@@ -0,0 +1,42 @@
+// run with: cargo test --no-default-features --test no_std
+//
+// the library isn't built with `cfg(test)` for this, so this checks that the parsing compiles without `std`
+#![cfg(not(feature = "std"))]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use trovochat::{irc, IntoOwned as _, IrcMessage, MessageError};
+
+#[test]
+fn parse_without_std() {
+    let input =
+        "@badges=moderator/1;color=#1E90FF :museun!museun@museun PRIVMSG #museun :hello world\r\n\
+                 PING :1234\r\n";
+
+    let msgs = irc::parse(input).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(msgs.len(), 2);
+
+    let msg = &msgs[0];
+    assert_eq!(msg.get_command(), IrcMessage::PRIVMSG);
+    assert_eq!(msg.nth_arg(0), Some("#museun"));
+    assert_eq!(msg.get_data(), Some("hello world"));
+    let (_, color) = msg.tags_iter().find(|(key, _)| *key == "color").unwrap();
+    assert_eq!(&*color, "#1E90FF");
+
+    let owned: IrcMessage<'static> = msgs[1].clone().into_owned();
+    assert_eq!(owned.get_command(), IrcMessage::PING);
+}
+
+#[test]
+fn parse_one_without_std() {
+    let (next, msg) = irc::parse_one("PING :1234\r\nPING :5678\r\n").unwrap();
+    assert_eq!(next, 12);
+    assert_eq!(msg.get_data(), Some("1234"));
+
+    assert!(matches!(
+        irc::parse_one("PING :1234").unwrap_err(),
+        MessageError::IncompleteMessage { pos: 0 }
+    ));
+}